@@ -0,0 +1,56 @@
+// <evil-counter> custom element
+//
+// Copy this file next to the files `wasm-pack build --target web` writes to
+// ./pkg, then on any page:
+//
+//   <script type="module" src="./pkg/evil-counter.js"></script>
+//   <evil-counter initial="10" step="5" min="0" max="100"></evil-counter>
+//
+// Every change fires a bubbling `evil-counter-change` event whose `detail`
+// is `{ value, previous }`. The Rust side lives in src/element.rs.
+
+// Stop the WASM start function from mounting the full app into <body>
+globalThis.__webtestNoAutoMount = true;
+
+const { default: init, mountEvilCounter } = await import("./webtest.js");
+await init();
+
+class EvilCounter extends HTMLElement {
+  static observedAttributes = ["initial", "step", "min", "max", "goal", "unit", "exact", "precision", "expression", "timer", "countdown"];
+
+  #handle = null;
+
+  connectedCallback() {
+    this.#mount();
+  }
+
+  disconnectedCallback() {
+    this.#unmount();
+  }
+
+  attributeChangedCallback(name, oldValue, newValue) {
+    if (!this.#handle || oldValue === newValue) return;
+    // Remount with the new settings, but keep the current count
+    // unless `initial` itself was changed. Unmount first: setting
+    // `initial` calls this method again, which must then do nothing.
+    const value = this.#handle.value();
+    this.#unmount();
+    if (name !== "initial") {
+      this.setAttribute("initial", String(value));
+    }
+    this.#mount();
+  }
+
+  #mount() {
+    if (!this.#handle) this.#handle = mountEvilCounter(this);
+  }
+
+  #unmount() {
+    this.#handle?.free();
+    this.#handle = null;
+  }
+}
+
+if (!customElements.get("evil-counter")) {
+  customElements.define("evil-counter", EvilCounter);
+}
@@ -0,0 +1,18 @@
+#!/bin/sh
+# Trunk post_build hook: stamps the service worker with this build's version
+# and the list of files to precache.
+#
+# Trunk gives every WASM/JS file a content hash in its name, so hashing the
+# list of file names plus their contents gives a version that changes exactly
+# when the build output changes.
+set -eu
+
+cd "$TRUNK_STAGING_DIR"
+
+files=$(find . -type f ! -name sw.js | sed 's|^\./||' | LC_ALL=C sort)
+version=$(for f in $files; do printf '%s\n' "$f"; cat "$f"; done | sha256sum | cut -c1-16)
+precache=$(for f in $files; do printf '"%s",' "$f"; done | sed 's/,$//')
+
+sed -e "s|__BUILD_VERSION__|$version|" -e "s|\"__PRECACHE__\"|$precache|" sw.js > sw.js.stamped
+mv sw.js.stamped sw.js
+echo "service worker stamped with version $version"
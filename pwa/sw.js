@@ -0,0 +1,56 @@
+// Service worker: makes the counter work offline.
+//
+// `__BUILD_VERSION__` and `__PRECACHE__` are filled in after every Trunk
+// build by pwa/stamp-sw.sh (see the hook in Trunk.toml). A new build means a
+// new version, a new cache name, and a fresh copy of the hashed WASM/JS files.
+
+const VERSION = "__BUILD_VERSION__";
+const CACHE = `webtest-${VERSION}`;
+const PRECACHE = ["./", "__PRECACHE__"];
+
+// Download everything the app needs up front.
+// We do NOT skipWaiting() here: the app shows an "update available" banner
+// and the user decides when to switch (see src/pwa.rs).
+self.addEventListener("install", (event) => {
+  event.waitUntil(caches.open(CACHE).then((cache) => cache.addAll(PRECACHE)));
+});
+
+// Drop caches from older builds once this version takes over.
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) =>
+        Promise.all(
+          keys
+            .filter((key) => key.startsWith("webtest-") && key !== CACHE)
+            .map((key) => caches.delete(key)),
+        ),
+      )
+      .then(() => self.clients.claim()),
+  );
+});
+
+// The "Reload" button in the update banner sends this message.
+self.addEventListener("message", (event) => {
+  if (event.data === "SKIP_WAITING") {
+    self.skipWaiting();
+  }
+});
+
+// Pages: network first (so updates are noticed), cached page when offline.
+// Everything else: cache first. API calls to the `server` binary are never cached.
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  const url = new URL(request.url);
+  if (request.method !== "GET" || url.origin !== self.location.origin || url.pathname.includes("/api/")) {
+    return;
+  }
+
+  if (request.mode === "navigate") {
+    event.respondWith(fetch(request).catch(() => caches.match("./", { cacheName: CACHE })));
+    return;
+  }
+
+  event.respondWith(caches.match(request, { cacheName: CACHE }).then((cached) => cached || fetch(request)));
+});
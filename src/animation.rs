@@ -0,0 +1,113 @@
+// ============================================================================
+// ANIMATION - ROLLING NUMBERS AND A SHAKE ON BAND CHANGES
+// ============================================================================
+//
+// WHAT: Two effects that make changes easier to follow:
+//
+//       - `Tween`: instead of jumping from 10 to 20, `CounterDisplay` counts
+//         up through 11, 12, ... in a fraction of a second
+//       - `shake`: when the count moves into another band (see src/band.rs),
+//         the counter's card shakes and flashes in the new band's color
+//
+// WHY "prefers-reduced-motion"? Motion can make some people dizzy or sick,
+//      and they can ask every website to tone it down in their system
+//      settings. `prefers_reduced_motion` reads that setting; when it's on,
+//      numbers just change and cards stay still.
+//
+// HOW: `Tween` is plain Rust: given the current time it says which number
+//      to show, so tests/animation.rs checks it without waiting. The shake
+//      uses the browser's Web Animations API (`element.animate(...)`), which
+//      plays keyframes on one element without any stylesheet - the counter
+//      styles everything inline, and `<evil-counter>` may live in a page
+//      with nobody else's CSS.
+//
+// LEARNING: "Easing" makes animations feel physical. `ease_out` starts fast
+//           and slows down at the end, like a wheel coming to rest.
+
+use wasm_bindgen::JsCast;
+
+/// `t` (0.0 to 1.0) eased out: quick at first, gently settling at 1.0
+pub fn ease_out(t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// A number rolling from `from` to `to`; times are in ms
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tween {
+    from: i32,
+    to: i32,
+    start: f64,
+}
+
+impl Tween {
+    /// How long rolling to a new number takes
+    pub const DURATION: f64 = 300.0;
+
+    /// Time between two frames (about 60 per second)
+    pub const FRAME: f64 = 16.0;
+
+    /// Start rolling from `from` to `to` at time `start`
+    pub fn new(from: i32, to: i32, start: f64) -> Self {
+        Self { from, to, start }
+    }
+
+    /// Where the tween is headed
+    pub fn target(&self) -> i32 {
+        self.to
+    }
+
+    /// The number to show at time `now` (exactly `to` once done)
+    pub fn value_at(&self, now: f64) -> i32 {
+        if self.is_done(now) {
+            return self.to;
+        }
+        let progress = ease_out((now - self.start) / Self::DURATION);
+        // In f64, so even i32::MIN to i32::MAX can't overflow
+        let (from, to) = (f64::from(self.from), f64::from(self.to));
+        (from + (to - from) * progress).round() as i32
+    }
+
+    /// Whether the tween has reached `to`
+    pub fn is_done(&self, now: f64) -> bool {
+        now - self.start >= Self::DURATION
+    }
+}
+
+/// Whether the user asked for less motion (always `false` outside a browser)
+pub fn prefers_reduced_motion() -> bool {
+    web_sys::window()
+        .and_then(|window| {
+            window
+                .match_media("(prefers-reduced-motion: reduce)")
+                .ok()
+                .flatten()
+        })
+        .is_some_and(|query| query.matches())
+}
+
+/// Shake `element` sideways while a `color` glow flashes around it
+///
+/// The first and last keyframes leave out the glow, so the animation starts
+/// from and returns to the element's own shadow. Like sounds and
+/// vibrations, a failed animation is simply skipped.
+pub fn shake(element: &web_sys::Element, color: &str) {
+    let keyframes = format!(
+        r#"[
+            {{"transform": "translateX(0)"}},
+            {{"transform": "translateX(-6px)", "boxShadow": "0 0 30px {color}"}},
+            {{"transform": "translateX(6px)"}},
+            {{"transform": "translateX(-4px)"}},
+            {{"transform": "translateX(2px)"}},
+            {{"transform": "translateX(0)"}}
+        ]"#
+    );
+    // web-sys only offers `animate` behind an unstable flag, so we look the
+    // method up ourselves: `element.animate(keyframes, 400)`
+    let animate = js_sys::Reflect::get(element, &"animate".into())
+        .ok()
+        .and_then(|animate| animate.dyn_into::<js_sys::Function>().ok());
+    if let (Some(animate), Ok(keyframes)) = (animate, js_sys::JSON::parse(&keyframes)) {
+        let _ = animate.call2(element, &keyframes, &400.into());
+    }
+}
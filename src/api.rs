@@ -0,0 +1,259 @@
+// ============================================================================
+// REST API - SHARED TYPES AND BROWSER CLIENT
+// ============================================================================
+//
+// WHAT: The JSON shapes exchanged between the browser and the `server`
+//       binary, plus small async functions the browser uses to call it.
+//
+// WHY: Counters stored on a server can be shared across devices. Keeping the
+//      request/response types in ONE module means the client and the server
+//      can never disagree about field names.
+//
+// ENDPOINTS (served by `src/routes.rs`):
+// - GET    /api/counters                 -> Vec<CounterRecord>
+// - POST   /api/counters                 NewCounter -> CounterRecord
+// - GET    /api/counters/{id}            -> CounterRecord
+// - PUT    /api/counters/{id}            CounterUpdate -> CounterRecord
+// - DELETE /api/counters/{id}            -> 204 No Content
+// - GET    /api/counters/{id}/operations -> Vec<Operation>
+// - POST   /api/counters/{id}/operations AppendOperation -> CounterRecord
+// - GET    /api/counters/{id}/crdt       -> CrdtCounter
+// - POST   /api/counters/{id}/crdt       CrdtCounter -> merged CrdtCounter
+// - POST   /api/replicas                 -> NewReplica (a fresh replica id)
+//
+// CONFIGURATION: the browser only talks to a server when the page contains
+//
+//     <meta name="webtest-api" content="http://127.0.0.1:3000" />
+//
+// and optionally `<meta name="webtest-counter-id" content="3" />` to pick a
+// specific counter. Without the meta tag the app works purely locally.
+//
+// EXACT COUNTERS: created with `"exact": true`, a counter also keeps its
+// exact value (see src/rational.rs) as a string like "3/2", so "/2" keeps
+// the fraction across saves. `value` is always its whole part.
+
+use crate::crdt::{CrdtCounter, ReplicaId};
+use crate::operation::Operation;
+use crate::rational::Rational;
+use gloo_net::http::{Request, Response};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt;
+
+/// A counter as stored on the server
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CounterRecord {
+    pub id: u64,
+    pub name: String,
+    pub value: i32,
+    /// The exact value of an exact counter (`value` is its whole part)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exact: Option<Rational>,
+}
+
+/// Body of `POST /api/counters`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewCounter {
+    pub name: String,
+    #[serde(default)]
+    pub value: i32,
+    /// Keep an exact value (see the module comment)
+    #[serde(default)]
+    pub exact: bool,
+}
+
+/// Body of `PUT /api/counters/{id}` - only the given fields change
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CounterUpdate {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub value: Option<i32>,
+    /// A new exact value (exact counters only; wins over `value`)
+    #[serde(default)]
+    pub exact: Option<Rational>,
+}
+
+/// Body of `POST /api/counters/{id}/operations`
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct AppendOperation {
+    pub op: Operation,
+}
+
+/// Response of `POST /api/replicas`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewReplica {
+    /// Unique to this browser tab, never `SERVER_REPLICA`
+    pub replica: ReplicaId,
+}
+
+/// Where the server lives, read from the page's `<meta>` tags
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiConfig {
+    /// e.g. `http://127.0.0.1:3000` (no trailing slash)
+    pub base_url: String,
+    /// Which counter to use; `None` means "the first one, or create one"
+    pub counter_id: Option<u64>,
+}
+
+impl ApiConfig {
+    /// Read the configuration from `<meta name="webtest-api">`
+    ///
+    /// Returns `None` when the tag is missing, which keeps the counter local.
+    /// Browser only: call it from an `Effect`, never during server rendering.
+    pub fn from_page() -> Option<Self> {
+        let document = web_sys::window()?.document()?;
+        let meta = |name: &str| {
+            document
+                .query_selector(&format!("meta[name=\"{name}\"]"))
+                .ok()
+                .flatten()
+                .and_then(|el| el.get_attribute("content"))
+                .map(|content| content.trim().to_string())
+                .filter(|content| !content.is_empty())
+        };
+
+        let base_url = meta("webtest-api")?.trim_end_matches('/').to_string();
+        let counter_id = meta("webtest-counter-id").and_then(|id| id.parse().ok());
+        Some(Self {
+            base_url,
+            counter_id,
+        })
+    }
+}
+
+/// Something went wrong talking to the server
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ApiError {
+    /// The request never got a response (server down, CORS, bad JSON...)
+    Network(String),
+    /// The server answered with a non-2xx status code
+    Status(u16),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Network(msg) => write!(f, "network error: {msg}"),
+            ApiError::Status(code) => write!(f, "server returned HTTP {code}"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<gloo_net::Error> for ApiError {
+    fn from(err: gloo_net::Error) -> Self {
+        ApiError::Network(err.to_string())
+    }
+}
+
+/// Turn a response into `T`, treating non-2xx statuses as errors
+async fn parse<T: DeserializeOwned>(response: Response) -> Result<T, ApiError> {
+    if !response.ok() {
+        return Err(ApiError::Status(response.status()));
+    }
+    Ok(response.json().await?)
+}
+
+/// `GET /api/counters`
+pub async fn list_counters(base_url: &str) -> Result<Vec<CounterRecord>, ApiError> {
+    parse(
+        Request::get(&format!("{base_url}/api/counters"))
+            .send()
+            .await?,
+    )
+    .await
+}
+
+/// `GET /api/counters/{id}`
+pub async fn get_counter(base_url: &str, id: u64) -> Result<CounterRecord, ApiError> {
+    parse(
+        Request::get(&format!("{base_url}/api/counters/{id}"))
+            .send()
+            .await?,
+    )
+    .await
+}
+
+/// `POST /api/counters`
+pub async fn create_counter(base_url: &str, new: &NewCounter) -> Result<CounterRecord, ApiError> {
+    let request = Request::post(&format!("{base_url}/api/counters")).json(new)?;
+    parse(request.send().await?).await
+}
+
+/// `PUT /api/counters/{id}`
+pub async fn update_counter(
+    base_url: &str,
+    id: u64,
+    update: &CounterUpdate,
+) -> Result<CounterRecord, ApiError> {
+    let request = Request::put(&format!("{base_url}/api/counters/{id}")).json(update)?;
+    parse(request.send().await?).await
+}
+
+/// `POST /api/counters/{id}/operations`
+pub async fn append_operation(
+    base_url: &str,
+    id: u64,
+    op: Operation,
+) -> Result<CounterRecord, ApiError> {
+    let request = Request::post(&format!("{base_url}/api/counters/{id}/operations"))
+        .json(&AppendOperation { op })?;
+    parse(request.send().await?).await
+}
+
+/// `GET /api/counters/{id}/crdt`
+pub async fn get_crdt(base_url: &str, id: u64) -> Result<CrdtCounter, ApiError> {
+    parse(
+        Request::get(&format!("{base_url}/api/counters/{id}/crdt"))
+            .send()
+            .await?,
+    )
+    .await
+}
+
+/// `POST /api/replicas` - a replica id for this tab's offline edits
+pub async fn allocate_replica(base_url: &str) -> Result<ReplicaId, ApiError> {
+    let response = Request::post(&format!("{base_url}/api/replicas"))
+        .send()
+        .await?;
+    Ok(parse::<NewReplica>(response).await?.replica)
+}
+
+/// `POST /api/counters/{id}/crdt` - merge a replica's state into the server's
+///
+/// The response is the merged state; adopt it to stay in step with the server.
+pub async fn merge_counter(
+    base_url: &str,
+    id: u64,
+    state: &CrdtCounter,
+) -> Result<CrdtCounter, ApiError> {
+    let request = Request::post(&format!("{base_url}/api/counters/{id}/crdt")).json(state)?;
+    parse(request.send().await?).await
+}
+
+/// Find the counter this page should use
+///
+/// Uses `counter_id` when configured, otherwise the first counter on the
+/// server that is `exact` (or not) like the page's, creating one when the
+/// server has none yet.
+pub async fn resolve_counter(config: &ApiConfig, exact: bool) -> Result<CounterRecord, ApiError> {
+    if let Some(id) = config.counter_id {
+        return get_counter(&config.base_url, id).await;
+    }
+    let counters = list_counters(&config.base_url).await?;
+    match counters
+        .into_iter()
+        .find(|record| record.exact.is_some() == exact)
+    {
+        Some(record) => Ok(record),
+        None => {
+            let new = NewCounter {
+                name: "Counter".to_string(),
+                value: 0,
+                exact,
+            };
+            create_counter(&config.base_url, &new).await
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// ============================================================================
+// BANDS - HOW HIGH OR LOW A COUNT IS
+// ============================================================================
+//
+// WHAT: The four ranges `CounterMessage` talks about:
+//
+//           negative: < 0     zero: 0     normal: 1..=50     high: > 50
+//
+// WHY A TYPE? Both the message under a counter and the board's search
+//      (`band:high`, see src/search.rs) need the same thresholds. Keeping
+//      them here means "HIGH" can never mean two different things.
+//
+// LEARNING: `match Band::of(value)` makes the compiler check that every band
+//           is handled - add a fifth one and every `match` must change.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Which range a count falls into (see the module comment)
+///
+/// Stored by name ("high", ...) in the notification settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Band {
+    Negative,
+    Zero,
+    Normal,
+    High,
+}
+
+impl Band {
+    /// Counts above this are `High`
+    pub const HIGH_ABOVE: i32 = 50;
+
+    /// Every band, lowest first
+    pub const ALL: [Band; 4] = [Band::Negative, Band::Zero, Band::Normal, Band::High];
+
+    /// The band `value` falls into
+    pub fn of(value: i32) -> Band {
+        match value {
+            ..0 => Band::Negative,
+            0 => Band::Zero,
+            v if v > Self::HIGH_ABOVE => Band::High,
+            _ => Band::Normal,
+        }
+    }
+
+    /// The lowercase name used in searches ("high", ...)
+    pub fn name(self) -> &'static str {
+        match self {
+            Band::Negative => "negative",
+            Band::Zero => "zero",
+            Band::Normal => "normal",
+            Band::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Band {
+    type Err = String;
+
+    /// Parses a band name, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|band| band.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| s.trim().to_string())
+    }
+}
@@ -0,0 +1,102 @@
+/// ============================================================================
+/// SERVER - RENDERS THE COUNTER APP TO HTML (SSR)
+/// ============================================================================
+///
+/// With plain client-side rendering the browser receives an empty <body> and
+/// has to download and run the WASM before anything appears. This binary
+/// renders `App` to HTML on the server instead, so the first paint already
+/// shows the counter and its message. The WASM (built with the `hydrate`
+/// feature) then "hydrates" that HTML to make the buttons interactive.
+///
+/// RUN IT:
+/// ```bash
+/// # 1. Build the hydrating client into ./pkg
+/// wasm-pack build --target web --no-default-features --features hydrate
+///
+/// # 2. Start the server (native build, so override the wasm32 default target)
+/// cargo run --target x86_64-unknown-linux-gnu --no-default-features --features ssr --bin server
+/// ```
+///
+/// It also serves the counters REST API under `/api/counters` (see
+/// `src/api.rs` for the endpoints), so counters can be shared across devices,
+/// and a WebSocket at `/api/ws` that pushes every change to every open
+/// browser (see `src/sync.rs` for the protocol). Both live in `src/routes.rs`.
+///
+/// CONFIGURATION (environment variables):
+/// - `WEBTEST_ADDR` - address to listen on (default `127.0.0.1:3000`)
+/// - `WEBTEST_PKG_DIR` - directory with the wasm-pack output (default `pkg`)
+/// - `WEBTEST_DATA` - JSON file the counters are stored in (default `counters.json`)
+///
+/// TRY THE API:
+/// ```bash
+/// curl -X POST localhost:3000/api/counters -H 'content-type: application/json' -d '{"name":"door a"}'
+/// curl -X POST localhost:3000/api/counters/1/operations -H 'content-type: application/json' -d '{"op":"increment"}'
+/// curl localhost:3000/api/counters
+/// ```
+use axum::{response::Html, routing::get, Router};
+use leptos::prelude::*;
+use tower_http::{cors::CorsLayer, services::ServeDir};
+use webtest::{
+    routes::{api_routes, AppState},
+    store::FileStore,
+    App,
+};
+
+/// The same HTML page Trunk uses, so both modes share one set of styles
+const INDEX_HTML: &str = include_str!("../../index.html");
+
+/// Globals the hydration runtime reads on startup.
+/// We have no server resources or errors to hand over, so they are empty.
+const HYDRATION_GLOBALS: &str =
+    "<script>__RESOLVED_RESOURCES=[];__SERIALIZED_ERRORS=[];__INCOMPLETE_CHUNKS=[];</script>";
+
+/// Loads the wasm-pack output and calls the exported `hydrate()` function
+const HYDRATE_SCRIPT: &str = r#"<script type="module">
+import init, { hydrate } from "/pkg/webtest.js";
+await init();
+hydrate();
+</script>"#;
+
+/// Render `App` to a string of HTML
+///
+/// A fresh reactive `Owner` is created per request so signals from one
+/// request are never shared with another; it is dropped (and its signals
+/// disposed) as soon as the HTML is produced.
+fn render_app() -> String {
+    let owner = Owner::new();
+    owner.with(|| view! { <App /> }.to_html())
+}
+
+/// GET / - the full page with the pre-rendered app inside <body>
+async fn index() -> Html<String> {
+    let body = format!(
+        "<body>{}{}{}</body>",
+        render_app(),
+        HYDRATION_GLOBALS,
+        HYDRATE_SCRIPT
+    );
+    Html(INDEX_HTML.replace("<body></body>", &body))
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = std::env::var("WEBTEST_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".to_string());
+    let pkg_dir = std::env::var("WEBTEST_PKG_DIR").unwrap_or_else(|_| "pkg".to_string());
+    let data_path = std::env::var("WEBTEST_DATA").unwrap_or_else(|_| "counters.json".to_string());
+
+    let store =
+        FileStore::open(&data_path).unwrap_or_else(|e| panic!("could not open {data_path}: {e}"));
+
+    let app = Router::new()
+        .route("/", get(index))
+        .nest_service("/pkg", ServeDir::new(pkg_dir))
+        .merge(api_routes(AppState::new(store)))
+        // The Trunk dev server runs on another port, so allow cross-origin calls
+        .layer(CorsLayer::permissive());
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .unwrap_or_else(|e| panic!("could not bind {addr}: {e}"));
+    println!("Serving the counter app on http://{addr}");
+    axum::serve(listener, app).await.expect("server error");
+}
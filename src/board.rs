@@ -0,0 +1,512 @@
+// ============================================================================
+// BOARD - SEVERAL NAMED COUNTERS, AND FORMULAS OVER THEM
+// ============================================================================
+//
+// WHAT: The state behind `CounterBoard` (src/lib.rs): a list of named
+//       counters (`door_a`, `wins`, ...) and a list of formulas that compute
+//       values from them (`total = door_a + door_b`, see src/formula.rs).
+//
+// WHY NAMES? Formulas refer to counters by name, so every counter and
+//      formula on a board has a unique name that is a valid identifier.
+//
+// HOW: Like `CounterControl` (src/control.rs), a `Board` is a `Copy` handle
+//      made of signals. Each counter gets its own `CounterControl`, so the
+//      board can read every count without owning the `Counter` components.
+//      `values()` reads those counts, with their units, so calling it
+//      inside a `Memo` makes the memo re-run whenever a count or the list
+//      of formulas changes.
+//
+// GROUPS: Counters can be sorted into named folders. A counter's `group`
+//      is the folder it is in (`None` for the counters outside any folder),
+//      and `group_total` adds a folder's counts up with `Quantity::sum`
+//      (src/units.rs), so a folder mixing liters and kilograms shows an
+//      error instead of a meaningless total. Folders can be collapsed; the
+//      board remembers which ones are, so their header and their counters
+//      agree.
+//
+// TAGS: Each counter has a list of tags (`#kitchen`), which the search bar
+//      can look for (see src/search.rs). `search` returns the ids of the
+//      matching counters.
+//
+// LEARNING: Counters are added from click handlers, which run outside any
+//           reactive owner. The board remembers the owner it was created
+//           under and creates each counter's signals there, so they are
+//           cleaned up together with the board.
+
+use crate::control::CounterControl;
+use crate::formula::{self, Formula, FormulaError};
+use crate::operation::Operation;
+use crate::search::Query;
+use crate::units::{Quantity, Unit, UnitError};
+use leptos::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/// One named counter on a board
+#[derive(Clone)]
+pub struct BoardCounter {
+    /// Stable key for rendering lists (names could be reused later)
+    pub id: u64,
+    pub name: String,
+    /// The count it starts at
+    pub initial: i32,
+    /// What it measures, if anything
+    pub unit: Option<Unit>,
+    /// The folder it is in (`None`: not in a folder)
+    pub group: Option<String>,
+    /// Tags to find it by, without the `#`
+    pub tags: Vec<String>,
+    /// Whether its card shows the countdown
+    pub countdown: bool,
+    /// The target its card tracks progress towards
+    pub goal: Option<i32>,
+    /// Whether it keeps exact fractions (see src/rational.rs)
+    pub exact: bool,
+    /// Decimals shown for its exact value
+    pub precision: usize,
+    pub control: CounterControl,
+}
+
+/// Why something couldn't be added to a board
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoardError {
+    /// Not usable as a name in formulas
+    InvalidName(String),
+    /// Another counter or formula already has this name
+    NameTaken(String),
+    /// Not a valid formula definition
+    Formula(FormulaError),
+    /// Not a known unit
+    Unit(UnitError),
+    /// There is no folder with this name
+    UnknownGroup(String),
+    /// Tags are single words
+    InvalidTag(String),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::InvalidName(name) => write!(
+                f,
+                "`{name}` is not a valid name (use letters, digits and `_`)"
+            ),
+            BoardError::NameTaken(name) => write!(f, "`{name}` is already used"),
+            BoardError::Formula(err) => write!(f, "{err}"),
+            BoardError::Unit(err) => write!(f, "{err}"),
+            BoardError::UnknownGroup(group) => write!(f, "there is no folder `{group}`"),
+            BoardError::InvalidTag(tag) => write!(f, "`{tag}` is not a valid tag (use one word)"),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+/// Named counters and formulas (see the module comment)
+#[derive(Clone, Copy)]
+pub struct Board {
+    counters: RwSignal<Vec<BoardCounter>>,
+    formulas: RwSignal<Vec<Formula>>,
+    /// Folder names, in the order they were added
+    groups: RwSignal<Vec<String>>,
+    /// The folders that are collapsed
+    collapsed: RwSignal<Vec<String>>,
+    next_id: StoredValue<u64>,
+    /// Where counters added later create their signals
+    owner: StoredValue<Option<Owner>>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Board {
+    /// An empty board, owned by the current reactive owner
+    pub fn new() -> Self {
+        Self {
+            counters: RwSignal::new(Vec::new()),
+            formulas: RwSignal::new(Vec::new()),
+            groups: RwSignal::new(Vec::new()),
+            collapsed: RwSignal::new(Vec::new()),
+            next_id: StoredValue::new(0),
+            owner: StoredValue::new(Owner::current()),
+        }
+    }
+
+    /// The counters, in the order they were added
+    pub fn counters(self) -> ReadSignal<Vec<BoardCounter>> {
+        self.counters.read_only()
+    }
+
+    /// The formulas, in the order they were added
+    pub fn formulas(self) -> ReadSignal<Vec<Formula>> {
+        self.formulas.read_only()
+    }
+
+    /// The folder names, in the order they were added
+    pub fn groups(self) -> ReadSignal<Vec<String>> {
+        self.groups.read_only()
+    }
+
+    /// Where `group` comes on the page: 0 for the counters outside any
+    /// folder, then 1, 2, ... for the folders in order (tracked)
+    pub fn group_position(self, group: Option<&str>) -> usize {
+        group.map_or(0, |group| {
+            self.groups
+                .with(|groups| groups.iter().position(|g| g == group))
+                .map_or(0, |position| position + 1)
+        })
+    }
+
+    /// The folder of the counter with `id` (tracked)
+    pub fn group_of(self, id: u64) -> Option<String> {
+        self.counters.with(|counters| {
+            counters
+                .iter()
+                .find(|counter| counter.id == id)
+                .and_then(|counter| counter.group.clone())
+        })
+    }
+
+    /// Whether the counter with `id` shows the countdown (tracked)
+    pub fn has_countdown(self, id: u64) -> bool {
+        self.read_counter(id, |counter| counter.countdown)
+            .unwrap_or_default()
+    }
+
+    /// Show or hide the countdown of the counter with `id`
+    pub fn set_countdown(self, id: u64, countdown: bool) {
+        self.update_counter(id, |counter| counter.countdown = countdown);
+    }
+
+    /// The goal of the counter with `id` (tracked)
+    pub fn goal_of(self, id: u64) -> Option<i32> {
+        self.read_counter(id, |counter| counter.goal).flatten()
+    }
+
+    /// Set (or with `None`, clear) the goal of the counter with `id`
+    pub fn set_goal(self, id: u64, goal: Option<i32>) {
+        self.update_counter(id, |counter| counter.goal = goal);
+    }
+
+    /// Whether the counter with `id` keeps exact fractions (tracked)
+    pub fn is_exact(self, id: u64) -> bool {
+        self.read_counter(id, |counter| counter.exact)
+            .unwrap_or_default()
+    }
+
+    /// Switch the counter with `id` to exact fractions or whole numbers
+    pub fn set_exact(self, id: u64, exact: bool) {
+        self.update_counter(id, |counter| counter.exact = exact);
+    }
+
+    /// Decimals shown for the exact value of the counter with `id` (tracked)
+    pub fn precision_of(self, id: u64) -> usize {
+        self.read_counter(id, |counter| counter.precision)
+            .unwrap_or(2)
+    }
+
+    /// Set how many decimals the counter with `id` shows
+    pub fn set_precision(self, id: u64, precision: usize) {
+        self.update_counter(id, |counter| counter.precision = precision);
+    }
+
+    /// Whether the folder `group` is collapsed (tracked)
+    pub fn is_collapsed(self, group: &str) -> bool {
+        self.collapsed
+            .with(|collapsed| collapsed.iter().any(|g| g == group))
+    }
+
+    /// Collapse or expand the folder `group`
+    pub fn set_collapsed(self, group: &str, collapsed: bool) {
+        self.collapsed.update(|folders| {
+            folders.retain(|g| g != group);
+            if collapsed {
+                folders.push(group.to_string());
+            }
+        });
+    }
+
+    /// The control of the counter called `name`
+    pub fn control(self, name: &str) -> Option<CounterControl> {
+        self.counters.with_untracked(|counters| {
+            counters
+                .iter()
+                .find(|counter| counter.name == name)
+                .map(|counter| counter.control)
+        })
+    }
+
+    /// Add what the board's text field describes:
+    ///
+    /// - `door_a` - a counter
+    /// - `water ml` - a counter measured in a unit (see src/units.rs)
+    /// - `door_a #entrance #ground` - a counter with tags (also with a unit)
+    /// - `total = door_a + door_b` - a formula
+    pub fn add_from_input(self, input: &str) -> Result<(), BoardError> {
+        if input.contains('=') {
+            return self.add_formula(input);
+        }
+        let (tags, words): (Vec<&str>, Vec<&str>) = input
+            .split_whitespace()
+            .partition(|word| word.starts_with('#'));
+        let (name, unit) = match words[..] {
+            [name] => (name, None),
+            [name, unit] => (name, Some(unit.parse().map_err(BoardError::Unit)?)),
+            _ => return Err(BoardError::InvalidName(input.trim().to_string())),
+        };
+        // Check every tag first, so a bad one doesn't leave the counter behind
+        for tag in &tags {
+            tag_name(tag)?;
+        }
+        let (id, _) = self.insert_counter(name, 0, unit)?;
+        for tag in tags {
+            self.add_tag(id, tag)?;
+        }
+        Ok(())
+    }
+
+    /// Add a counter called `name`, starting at `initial`, outside any folder
+    pub fn add_counter(
+        self,
+        name: &str,
+        initial: i32,
+        unit: Option<Unit>,
+    ) -> Result<CounterControl, BoardError> {
+        self.insert_counter(name, initial, unit)
+            .map(|(_, control)| control)
+    }
+
+    /// `add_counter`, also returning the new counter's id
+    fn insert_counter(
+        self,
+        name: &str,
+        initial: i32,
+        unit: Option<Unit>,
+    ) -> Result<(u64, CounterControl), BoardError> {
+        let name = name.trim();
+        self.check_name(name)?;
+        let control = match self.owner.get_value() {
+            Some(owner) => owner.with(CounterControl::new),
+            None => CounterControl::new(),
+        };
+        // Formulas and totals see the count even before its `Counter` is shown
+        _ = control.signal(initial);
+        let id = self.next_id();
+        self.counters.update(|counters| {
+            counters.push(BoardCounter {
+                id,
+                name: name.to_string(),
+                initial,
+                unit,
+                group: None,
+                tags: Vec::new(),
+                countdown: false,
+                goal: None,
+                exact: false,
+                precision: 2,
+                control,
+            })
+        });
+        Ok((id, control))
+    }
+
+    /// Add an (empty) folder
+    pub fn add_group(self, name: &str) -> Result<(), BoardError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(BoardError::InvalidName(String::new()));
+        }
+        if self
+            .groups
+            .with_untracked(|groups| groups.iter().any(|g| g == name))
+        {
+            return Err(BoardError::NameTaken(name.to_string()));
+        }
+        self.groups.update(|groups| groups.push(name.to_string()));
+        Ok(())
+    }
+
+    /// Move the counter with `id` into the folder `group` (`None`: out of any)
+    ///
+    /// Only its place on the page changes: its `Counter` stays the same, with
+    /// its count, undo history and running timers.
+    pub fn move_counter(self, id: u64, group: Option<&str>) -> Result<(), BoardError> {
+        if let Some(group) = group {
+            if !self
+                .groups
+                .with_untracked(|groups| groups.iter().any(|g| g == group))
+            {
+                return Err(BoardError::UnknownGroup(group.to_string()));
+            }
+        }
+        self.counters.update(|counters| {
+            if let Some(counter) = counters.iter_mut().find(|counter| counter.id == id) {
+                counter.group = group.map(str::to_string);
+            }
+        });
+        Ok(())
+    }
+
+    /// Tag the counter with `id` (a leading `#` is dropped; tags already
+    /// there, in any case, are ignored)
+    pub fn add_tag(self, id: u64, tag: &str) -> Result<(), BoardError> {
+        let tag = tag_name(tag)?;
+        self.counters.update(|counters| {
+            if let Some(counter) = counters.iter_mut().find(|counter| counter.id == id) {
+                if !counter
+                    .tags
+                    .iter()
+                    .any(|t| t.to_lowercase() == tag.to_lowercase())
+                {
+                    counter.tags.push(tag.to_string());
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Take `tag` off the counter with `id`
+    pub fn remove_tag(self, id: u64, tag: &str) {
+        self.counters.update(|counters| {
+            if let Some(counter) = counters.iter_mut().find(|counter| counter.id == id) {
+                counter.tags.retain(|t| t != tag);
+            }
+        });
+    }
+
+    /// The tags of the counter with `id` (tracked)
+    pub fn tags(self, id: u64) -> Vec<String> {
+        self.counters.with(|counters| {
+            counters
+                .iter()
+                .find(|counter| counter.id == id)
+                .map(|counter| counter.tags.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    /// The ids of the counters matching `query`, in order (tracked: every
+    /// count is read, so call it inside a `Memo`)
+    pub fn search(self, query: &Query) -> Vec<u64> {
+        self.counters.with(|counters| {
+            counters
+                .iter()
+                .filter(|counter| {
+                    query.matches(&counter.name, &counter.tags, counter.control.count().get())
+                })
+                .map(|counter| counter.id)
+                .collect()
+        })
+    }
+
+    /// The controls of the counters in `group`, in order
+    fn group_controls(self, group: Option<&str>) -> Vec<CounterControl> {
+        self.counters.with_untracked(|counters| {
+            counters
+                .iter()
+                .filter(|counter| counter.group.as_deref() == group)
+                .map(|counter| counter.control)
+                .collect()
+        })
+    }
+
+    /// "Reset" every counter in `group`, as if each one's button was clicked
+    pub fn reset_group(self, group: Option<&str>) {
+        for control in self.group_controls(group) {
+            control.apply(Operation::Reset);
+        }
+    }
+
+    /// The counts in `group` added up (tracked: call it inside a `Memo`)
+    ///
+    /// In the unit of the folder's first counter; `Err` when the folder
+    /// mixes units of different kinds (or measured and plain counters).
+    pub fn group_total(self, group: Option<&str>) -> Result<Quantity, UnitError> {
+        let quantities: Vec<Quantity> = self.counters.with(|counters| {
+            counters
+                .iter()
+                .filter(|counter| counter.group.as_deref() == group)
+                .map(|counter| Quantity::new(counter.control.count().get(), counter.unit))
+                .collect()
+        });
+        Quantity::sum(quantities)
+    }
+
+    /// Add a formula from its definition, like `total = door_a + door_b`
+    ///
+    /// Names it uses don't have to exist yet: until they do, the formula
+    /// shows an "unknown name" error.
+    pub fn add_formula(self, definition: &str) -> Result<(), BoardError> {
+        let formula: Formula = definition.parse().map_err(BoardError::Formula)?;
+        self.check_name(&formula.name)?;
+        self.formulas.update(|formulas| formulas.push(formula));
+        Ok(())
+    }
+
+    /// Every formula's value (or error), by name
+    ///
+    /// Tracks every count and the formula list: call it inside a `Memo`.
+    pub fn values(self) -> HashMap<String, Result<Quantity, FormulaError>> {
+        let counts: HashMap<String, Quantity> = self.counters.with(|counters| {
+            counters
+                .iter()
+                .map(|counter| {
+                    let count = Quantity::new(counter.control.count().get(), counter.unit);
+                    (counter.name.clone(), count)
+                })
+                .collect()
+        });
+        self.formulas
+            .with(|formulas| formula::evaluate_all(&|name| counts.get(name).copied(), formulas))
+    }
+
+    fn check_name(self, name: &str) -> Result<(), BoardError> {
+        if !formula::is_valid_name(name) {
+            return Err(BoardError::InvalidName(name.to_string()));
+        }
+        let taken = self
+            .counters
+            .with_untracked(|counters| counters.iter().any(|counter| counter.name == name))
+            || self
+                .formulas
+                .with_untracked(|formulas| formulas.iter().any(|formula| formula.name == name));
+        match taken {
+            true => Err(BoardError::NameTaken(name.to_string())),
+            false => Ok(()),
+        }
+    }
+
+    /// Read the counter with `id` (tracked), if it exists
+    fn read_counter<T>(self, id: u64, read: impl FnOnce(&BoardCounter) -> T) -> Option<T> {
+        self.counters
+            .with(|counters| counters.iter().find(|counter| counter.id == id).map(read))
+    }
+
+    /// Change the counter with `id`, if it exists
+    fn update_counter(self, id: u64, change: impl FnOnce(&mut BoardCounter)) {
+        self.counters.update(|counters| {
+            if let Some(counter) = counters.iter_mut().find(|counter| counter.id == id) {
+                change(counter);
+            }
+        });
+    }
+
+    fn next_id(self) -> u64 {
+        let id = self.next_id.get_value();
+        self.next_id.set_value(id + 1);
+        id
+    }
+}
+
+/// `tag` without its `#`, if it's a usable tag: one word, not empty
+fn tag_name(tag: &str) -> Result<&str, BoardError> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix('#').unwrap_or(tag);
+    if tag.is_empty() || tag.contains(char::is_whitespace) {
+        return Err(BoardError::InvalidTag(tag.to_string()));
+    }
+    Ok(tag)
+}
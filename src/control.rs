@@ -0,0 +1,93 @@
+// ============================================================================
+// COUNTER CONTROL - DRIVING A COUNTER FROM OUTSIDE THE COMPONENT
+// ============================================================================
+//
+// WHAT: A small handle that code OUTSIDE a `Counter` (the JavaScript API in
+//       src/js_api.rs, for example) uses to read its count and apply
+//       operations to it, exactly as if a button had been clicked.
+//
+// HOW: Create a `CounterControl`, pass it as `<Counter control=control />`
+//      and keep a copy. The counter stores its count signal and its `apply`
+//      function in the control, so operations sent through the control go
+//      through the same bounds checks and server sync as the buttons.
+//
+// LEARNING: `RwSignal` and `StoredValue` are `Copy` handles into Leptos'
+//           reactive system, so the control can be copied freely. They are
+//           disposed together with the owner that created them; after that
+//           the `try_` methods simply return `None`.
+
+use crate::operation::Operation;
+use leptos::prelude::*;
+
+/// The `apply` function a mounted `Counter` registers
+type ApplyFn = Box<dyn Fn(Operation)>;
+
+/// The `undo` function a mounted `Counter` registers
+type UndoFn = Box<dyn Fn()>;
+
+/// Read and drive a `Counter` from outside (see the module comment)
+#[derive(Clone, Copy)]
+pub struct CounterControl {
+    count: RwSignal<i32>,
+    apply: StoredValue<Option<ApplyFn>, LocalStorage>,
+    undo: StoredValue<Option<UndoFn>, LocalStorage>,
+}
+
+impl Default for CounterControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CounterControl {
+    /// A control not attached to any counter yet
+    pub fn new() -> Self {
+        Self {
+            count: RwSignal::new(0),
+            apply: StoredValue::new_local(None),
+            undo: StoredValue::new_local(None),
+        }
+    }
+
+    /// The counter's count, as a signal (reading it inside an `Effect` tracks it)
+    pub fn count(self) -> ReadSignal<i32> {
+        self.count.read_only()
+    }
+
+    /// The current count without tracking it (0 once the counter is gone)
+    pub fn get(self) -> i32 {
+        self.count.try_get_untracked().unwrap_or_default()
+    }
+
+    /// Apply `op` as if its button had been clicked
+    ///
+    /// Returns `false` when no counter is attached (not mounted yet, or unmounted).
+    pub fn apply(self, op: Operation) -> bool {
+        self.apply
+            .try_with_value(|apply| apply.as_ref().map(|apply| apply(op)))
+            .flatten()
+            .is_some()
+    }
+
+    /// Undo the newest change, as if "Undo" had been clicked
+    ///
+    /// Returns `false` when no counter is attached.
+    pub fn undo(self) -> bool {
+        self.undo
+            .try_with_value(|undo| undo.as_ref().map(|undo| undo()))
+            .flatten()
+            .is_some()
+    }
+
+    /// Used by `Counter`: start at `initial` and hand out the count signal
+    pub(crate) fn signal(self, initial: i32) -> (ReadSignal<i32>, WriteSignal<i32>) {
+        self.count.set(initial);
+        self.count.split()
+    }
+
+    /// Used by `Counter`: register the functions that perform operations and undo
+    pub(crate) fn attach(self, apply: impl Fn(Operation) + 'static, undo: impl Fn() + 'static) {
+        self.apply.set_value(Some(Box::new(apply)));
+        self.undo.set_value(Some(Box::new(undo)));
+    }
+}
@@ -0,0 +1,149 @@
+// ============================================================================
+// CRDT COUNTER - MERGING COUNTERS EDITED ON SEVERAL DEVICES
+// ============================================================================
+//
+// WHAT: A counter that can be edited on several devices ("replicas"), even
+//       offline, and later merged so every device ends up with the SAME
+//       value - no matter in which order the devices sync.
+//
+// WHY: "Last write wins" loses clicks. If my phone says 12 and my laptop
+//      says 13, which one is right? Maybe BOTH added clicks while offline.
+//
+// HOW: A CRDT (Conflict-free Replicated Data Type) remembers enough history
+//      to merge without asking anyone:
+//
+// 1. +1 / -1 (and +n / -n) form a PN-counter. Each replica counts ITS OWN
+//    increments (P) and decrements (N). Merging takes the max per replica,
+//    so a click is counted exactly once however often states are exchanged.
+//    value = base + sum(P) - sum(N)
+//
+// 2. *2, /2, Reset and Set don't commute with +1 ((5+1)*2 != 5*2+1), so they
+//    start a new EPOCH: the current value becomes the new `base` and the
+//    P/N counts are cleared. Epochs are ordered by (clock, replica id), and
+//    merging keeps the state with the newest epoch.
+//
+//    STRATEGY: a `*2`, `/2`, `Reset` or `Set` wins over +1/-1 clicks made
+//    concurrently on other replicas (clicks it had not seen yet are dropped).
+//    Two concurrent multiplicative operations: the higher (clock, replica)
+//    wins. Clicks made AFTER syncing with the new epoch are kept as usual.
+//
+// LEARNING: `merge` is commutative, associative and idempotent - the three
+//           properties that make replicas converge. tests/crdt.rs checks
+//           them with property-based tests.
+
+use crate::operation::Operation;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Identifies one device/tab editing a counter. The server uses [`SERVER_REPLICA`];
+/// each browser tab asks it for a fresh id (`POST /api/replicas`).
+pub type ReplicaId = u64;
+
+/// The replica id the `server` binary applies its own operations under
+pub const SERVER_REPLICA: ReplicaId = 0;
+
+/// When the current `base` was set: ordered by `clock`, then by `replica`
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Epoch {
+    pub clock: u64,
+    pub replica: ReplicaId,
+}
+
+/// A mergeable counter state (see the module comment for the strategy)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrdtCounter {
+    epoch: Epoch,
+    base: i32,
+    increments: BTreeMap<ReplicaId, u64>,
+    decrements: BTreeMap<ReplicaId, u64>,
+}
+
+impl CrdtCounter {
+    /// A counter at zero - every replica may start from this state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current value
+    ///
+    /// Computed in `i64` and clamped to the `i32` range, so the result only
+    /// depends on the merged state, never on the order clicks arrived in.
+    /// Every step saturates: the counts may come from another device.
+    pub fn value(&self) -> i32 {
+        let sum = |counts: &BTreeMap<ReplicaId, u64>| {
+            counts
+                .values()
+                .map(|&n| i64::try_from(n).unwrap_or(i64::MAX))
+                .fold(0i64, i64::saturating_add)
+        };
+        let value = i64::from(self.base)
+            .saturating_add(sum(&self.increments))
+            .saturating_sub(sum(&self.decrements));
+        value.clamp(i32::MIN.into(), i32::MAX.into()) as i32
+    }
+
+    /// The epoch the current `base` belongs to
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// Whether a state received from elsewhere is in range: its clock can
+    /// still advance, and no count is beyond what `value` can add up
+    pub fn is_valid(&self) -> bool {
+        let in_range =
+            |counts: &BTreeMap<ReplicaId, u64>| counts.values().all(|&n| i64::try_from(n).is_ok());
+        self.epoch.clock < u64::MAX && in_range(&self.increments) && in_range(&self.decrements)
+    }
+
+    /// Apply `op` on behalf of `replica`
+    pub fn apply(&mut self, replica: ReplicaId, op: Operation) {
+        let add = |counts: &mut BTreeMap<ReplicaId, u64>, n: u64| {
+            let count = counts.entry(replica).or_default();
+            *count = count.saturating_add(n);
+        };
+        match op {
+            Operation::Increment => add(&mut self.increments, 1),
+            Operation::Decrement => add(&mut self.decrements, 1),
+            Operation::Add(n) if n >= 0 => add(&mut self.increments, n.unsigned_abs().into()),
+            Operation::Add(n) => add(&mut self.decrements, n.unsigned_abs().into()),
+            Operation::Reset | Operation::Double | Operation::Halve | Operation::Set(_) => {
+                self.set(replica, op.apply(self.value()));
+            }
+        }
+    }
+
+    /// Overwrite the value on behalf of `replica` (starts a new epoch)
+    pub fn set(&mut self, replica: ReplicaId, value: i32) {
+        self.epoch = Epoch {
+            clock: self.epoch.clock.saturating_add(1),
+            replica,
+        };
+        self.base = value;
+        self.increments.clear();
+        self.decrements.clear();
+    }
+
+    /// Merge another replica's state into this one
+    pub fn merge(&mut self, other: &CrdtCounter) {
+        if other.epoch > self.epoch {
+            *self = other.clone();
+        } else if other.epoch == self.epoch {
+            // Same epoch means same base; `max` keeps merge deterministic
+            // even if a replica ever reused an epoch by mistake
+            self.base = self.base.max(other.base);
+            merge_max(&mut self.increments, &other.increments);
+            merge_max(&mut self.decrements, &other.decrements);
+        }
+        // An older epoch has nothing we need: it was superseded
+    }
+}
+
+/// Per-replica maximum of two count maps
+fn merge_max(ours: &mut BTreeMap<ReplicaId, u64>, theirs: &BTreeMap<ReplicaId, u64>) {
+    for (&replica, &count) in theirs {
+        let entry = ours.entry(replica).or_default();
+        *entry = (*entry).max(count);
+    }
+}
@@ -0,0 +1,139 @@
+// ============================================================================
+// <evil-counter> - THE COUNTER AS A CUSTOM ELEMENT
+// ============================================================================
+//
+// WHAT: Lets any web page (no Rust, no Leptos) embed the counter:
+//
+//     <script type="module" src="./evil-counter.js"></script>
+//     <evil-counter initial="10" step="5" min="0" max="100"></evil-counter>
+//
+// `unit="ml"` makes it count milliliters (see src/units.rs).
+// The boolean `exact` attribute keeps fractions ("/2" on 3 shows 1.5, with
+// `precision` decimals). The boolean `expression` attribute adds the expression input, `timer` the
+// auto-increment timer controls and `countdown` adds the countdown with its alarm. `goal="100"` shows a
+// progress bar towards 100.
+//
+// HOW: Custom elements must be JavaScript classes, so js/evil-counter.js
+//      defines the class and calls `mountEvilCounter` (below) whenever an
+//      element is added to the page. We mount our `Counter` component INSIDE
+//      that element with `mount_to`, the per-element cousin of `mount_to_body`.
+//
+// EVENTS: every change fires an `evil-counter-change` DOM event on the
+//         element, with `event.detail = { value, previous }`. The event
+//         bubbles, so a page can listen on `document` for all counters.
+//
+// LEARNING: `#[wasm_bindgen]` on a function or struct exports it to
+//           JavaScript. `js_name` picks the JavaScript name.
+
+use crate::operation::Bounds;
+use crate::units::Unit;
+use crate::Counter;
+use leptos::prelude::*;
+use std::any::Any;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use web_sys::{CustomEvent, CustomEventInit, HtmlElement};
+
+/// Name of the DOM event fired on every change
+pub const CHANGE_EVENT: &str = "evil-counter-change";
+
+/// A counter mounted inside an `<evil-counter>` element
+///
+/// Dropping it (or calling `free()` from JavaScript) unmounts the counter.
+#[wasm_bindgen]
+pub struct EvilCounterHandle {
+    /// Leptos' `UnmountHandle`; its concrete type is long and uninteresting,
+    /// all we need is that dropping it removes the counter from the page
+    _mounted: Box<dyn Any>,
+    value: Rc<Cell<i32>>,
+}
+
+#[wasm_bindgen]
+impl EvilCounterHandle {
+    /// The current count (used to keep the value when attributes change)
+    pub fn value(&self) -> i32 {
+        self.value.get()
+    }
+}
+
+/// Mount a `Counter` inside `host`, configured from its attributes
+///
+/// Reads `initial`, `step`, `min`, `max`, `goal`, `unit`, `exact`,
+/// `precision`, `expression`, `timer` and `countdown`; missing or unparsable
+/// attributes (and a `step` below 1) fall back to the `Counter` defaults.
+#[wasm_bindgen(js_name = mountEvilCounter)]
+pub fn mount_evil_counter(host: HtmlElement) -> EvilCounterHandle {
+    let attribute = |name: &str| {
+        host.get_attribute(name)
+            .and_then(|value| value.trim().parse::<i32>().ok())
+    };
+    let initial = attribute("initial").unwrap_or(0);
+    // "-" subtracts the step, so it must be positive
+    let step = attribute("step").filter(|step| *step > 0).unwrap_or(1);
+    let (min, max) = (attribute("min"), attribute("max"));
+    let goal = attribute("goal");
+    let unit = host
+        .get_attribute("unit")
+        .and_then(|unit| unit.parse::<Unit>().ok());
+    let exact = host.has_attribute("exact");
+    let precision = attribute("precision").map_or(2, |digits| digits.max(0) as usize);
+    let expression = host.has_attribute("expression");
+    let timer = host.has_attribute("timer");
+    let countdown = host.has_attribute("countdown");
+
+    let value = Rc::new(Cell::new(Bounds { min, max }.clamp(initial)));
+
+    let mounted = leptos::mount::mount_to(host.clone(), {
+        let (host, value) = (host.clone(), value.clone());
+        move || {
+            // `Callback` must be `Send`, but DOM elements and `Rc` are not:
+            // a local `StoredValue` is a `Copy` + `Send` handle to them
+            let target = StoredValue::new_local((host, value));
+            let on_change = Callback::new(move |(previous, current): (i32, i32)| {
+                target.with_value(|(host, value)| {
+                    value.set(current);
+                    dispatch_change(host, previous, current);
+                });
+            });
+            view! {
+                <Counter
+                    initial=initial
+                    step=step
+                    min=min
+                    max=max
+                    goal=goal
+                    unit=unit
+                    exact=exact
+                    precision=precision
+                    expression=expression
+                    timer=timer
+                    countdown=countdown
+                    on_change=on_change
+                />
+            }
+        }
+    });
+
+    EvilCounterHandle {
+        _mounted: Box::new(mounted),
+        value,
+    }
+}
+
+/// Fire `evil-counter-change` on `host` with `{ value, previous }` as detail
+fn dispatch_change(host: &HtmlElement, previous: i32, value: i32) {
+    let detail = js_sys::Object::new();
+    _ = js_sys::Reflect::set(&detail, &"value".into(), &value.into());
+    _ = js_sys::Reflect::set(&detail, &"previous".into(), &previous.into());
+
+    let init = CustomEventInit::new();
+    init.set_bubbles(true);
+    // `composed` lets the event escape a shadow root, should the page use one
+    init.set_composed(true);
+    init.set_detail(&detail);
+
+    if let Ok(event) = CustomEvent::new_with_event_init_dict(CHANGE_EVENT, &init) {
+        _ = host.dispatch_event(&event);
+    }
+}
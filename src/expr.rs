@@ -0,0 +1,322 @@
+// ============================================================================
+// EXPRESSIONS - TYPING `count * 3 + 7` INSTEAD OF CLICKING
+// ============================================================================
+//
+// WHAT: A tiny calculator language. An expression is parsed once into a tree
+//       (`Expr`) and evaluated with the counter's current value as `count`:
+//
+//           count * 3 + 7        (count / 4) ^ 2        -(count % 10)
+//
+//       Integers only: `/` rounds toward zero like the "/2" button, `%` is
+//       the remainder and `^` the power (`2 ^ 3 ^ 2` is `2 ^ 9`).
+//
+// WHY OUR OWN PARSER? Evaluating user input with JavaScript's `eval` would
+//      run ANY code. This language can only do arithmetic, and every failure
+//      is an `ExprError` instead of a panic:
+//      - invalid syntax (with the position of the problem), including
+//        input too long or too deeply nested to evaluate safely
+//      - unknown names, division by zero, negative exponents
+//      - overflow: every step uses checked i32 arithmetic
+//
+// HOW: "Recursive descent" - one function per precedence level, each calling
+//      the next tighter one:
+//
+//          sum     = product (("+" | "-") product)*
+//          product = unary (("*" | "/" | "%") unary)*
+//          unary   = "-" unary | power
+//          power   = atom ("^" unary)?
+//          atom    = number | name | "(" sum ")"
+//
+// LEARNING: `?` on a `Result` returns the error early, so each parsing step
+//           reads like the grammar above without any error plumbing.
+
+use std::fmt;
+
+/// Deepest allowed nesting; stops "((((((..." from overflowing the stack
+const MAX_DEPTH: usize = 64;
+
+/// Most tokens in one expression. `1+1+1...` isn't nested, but its tree is
+/// as deep as it is long, and evaluating (or dropping) it recurses that deep.
+const MAX_TOKENS: usize = 1000;
+
+/// Why an expression couldn't be parsed or evaluated
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExprError {
+    /// Not a valid expression; `position` counts characters from 0
+    Syntax { position: usize, message: String },
+    /// A name other than the known ones (just `count` for a counter)
+    UnknownName(String),
+    /// `x / 0` or `x % 0`
+    DivisionByZero,
+    /// `x ^ -1` would not be an integer
+    NegativeExponent,
+    /// The result (or a step on the way) doesn't fit in an i32
+    Overflow,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprError::Syntax { position, message } => {
+                write!(f, "syntax error at position {}: {message}", position + 1)
+            }
+            ExprError::UnknownName(name) => write!(f, "unknown name `{name}`"),
+            ExprError::DivisionByZero => write!(f, "division by zero"),
+            ExprError::NegativeExponent => write!(f, "negative exponents are not allowed"),
+            ExprError::Overflow => write!(f, "the result is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// A binary operator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+impl BinaryOp {
+    /// `a op b`, failing instead of overflowing
+    pub fn apply(self, a: i32, b: i32) -> Result<i32, ExprError> {
+        match self {
+            BinaryOp::Add => a.checked_add(b).ok_or(ExprError::Overflow),
+            BinaryOp::Sub => a.checked_sub(b).ok_or(ExprError::Overflow),
+            BinaryOp::Mul => a.checked_mul(b).ok_or(ExprError::Overflow),
+            BinaryOp::Div | BinaryOp::Rem if b == 0 => Err(ExprError::DivisionByZero),
+            // `i32::MIN / -1` is the only other way these can fail
+            BinaryOp::Div => a.checked_div(b).ok_or(ExprError::Overflow),
+            BinaryOp::Rem => a.checked_rem(b).ok_or(ExprError::Overflow),
+            BinaryOp::Pow => {
+                let exponent = u32::try_from(b).map_err(|_| ExprError::NegativeExponent)?;
+                a.checked_pow(exponent).ok_or(ExprError::Overflow)
+            }
+        }
+    }
+}
+
+/// A parsed expression (see the module comment)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Number(i32),
+    Name(String),
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Parse `input` into an expression tree
+    pub fn parse(input: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            next: 0,
+            depth: 0,
+            end: input.chars().count(),
+        };
+        let expr = parser.sum()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some((position, token)) => Err(syntax(position, format!("unexpected {token}"))),
+        }
+    }
+
+    /// Evaluate, looking up names with `lookup`
+    pub fn eval(&self, lookup: &dyn Fn(&str) -> Option<i32>) -> Result<i32, ExprError> {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Name(name) => lookup(name).ok_or_else(|| ExprError::UnknownName(name.clone())),
+            Expr::Neg(inner) => inner.eval(lookup)?.checked_neg().ok_or(ExprError::Overflow),
+            Expr::Binary(op, left, right) => op.apply(left.eval(lookup)?, right.eval(lookup)?),
+        }
+    }
+
+    /// Every name the expression uses, in order of appearance
+    pub fn names(&self) -> Vec<&str> {
+        match self {
+            Expr::Number(_) => vec![],
+            Expr::Name(name) => vec![name],
+            Expr::Neg(inner) => inner.names(),
+            Expr::Binary(_, left, right) => {
+                let mut names = left.names();
+                names.extend(right.names());
+                names
+            }
+        }
+    }
+}
+
+/// Parse and evaluate `input` with `count` as the only name
+pub fn evaluate(input: &str, count: i32) -> Result<i32, ExprError> {
+    Expr::parse(input)?.eval(&|name| (name == "count").then_some(count))
+}
+
+fn syntax(position: usize, message: impl Into<String>) -> ExprError {
+    ExprError::Syntax {
+        position,
+        message: message.into(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(i32),
+    Name(String),
+    Op(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "number {n}"),
+            Token::Name(name) => write!(f, "name `{name}`"),
+            Token::Op(op) => write!(f, "`{op}`"),
+        }
+    }
+}
+
+/// Split the input into tokens, each with its character position
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while let Some(&c) = chars.get(i) {
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = if c.is_ascii_digit() {
+            while chars.get(i).is_some_and(char::is_ascii_digit) {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().collect();
+            Token::Number(digits.parse().map_err(|_| ExprError::Overflow)?)
+        } else if c.is_alphabetic() || c == '_' {
+            while chars
+                .get(i)
+                .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+            {
+                i += 1;
+            }
+            Token::Name(chars[start..i].iter().collect())
+        } else if "+-*/%^()".contains(c) {
+            i += 1;
+            Token::Op(c)
+        } else {
+            return Err(syntax(start, format!("unexpected character `{c}`")));
+        };
+        if tokens.len() == MAX_TOKENS {
+            return Err(syntax(start, "expression is too long"));
+        }
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    depth: usize,
+    /// Position reported for "unexpected end"
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<(usize, &Token)> {
+        self.tokens
+            .get(self.next)
+            .map(|(position, token)| (*position, token))
+    }
+
+    /// Consume the next token if it is one of the operators in `ops`
+    fn eat(&mut self, ops: &str) -> Option<char> {
+        match self.peek() {
+            Some((_, Token::Op(op))) if ops.contains(*op) => {
+                let op = *op;
+                self.next += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expr, ExprError> {
+        let mut left = self.product()?;
+        while let Some(op) = self.eat("+-") {
+            let op = if op == '+' {
+                BinaryOp::Add
+            } else {
+                BinaryOp::Sub
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Expr, ExprError> {
+        let mut left = self.unary()?;
+        while let Some(op) = self.eat("*/%") {
+            let op = match op {
+                '*' => BinaryOp::Mul,
+                '/' => BinaryOp::Div,
+                _ => BinaryOp::Rem,
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ExprError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            let position = self.peek().map_or(self.end, |(position, _)| position);
+            return Err(syntax(position, "expression is nested too deeply"));
+        }
+        let expr = match self.eat("-") {
+            Some(_) => self.unary().map(|inner| Expr::Neg(Box::new(inner))),
+            None => self.power(),
+        };
+        self.depth -= 1;
+        expr
+    }
+
+    fn power(&mut self) -> Result<Expr, ExprError> {
+        let base = self.atom()?;
+        match self.eat("^") {
+            // Right-associative: the exponent may itself contain `^`
+            Some(_) => Ok(Expr::Binary(
+                BinaryOp::Pow,
+                Box::new(base),
+                Box::new(self.unary()?),
+            )),
+            None => Ok(base),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, ExprError> {
+        let Some((position, token)) = self.peek() else {
+            return Err(syntax(self.end, "unexpected end, expected a number"));
+        };
+        let expr = match token {
+            Token::Number(n) => Expr::Number(*n),
+            Token::Name(name) => Expr::Name(name.clone()),
+            Token::Op('(') => {
+                self.next += 1;
+                let inner = self.sum()?;
+                if self.eat(")").is_none() {
+                    let position = self.peek().map_or(self.end, |(position, _)| position);
+                    return Err(syntax(position, "expected `)`"));
+                }
+                return Ok(inner);
+            }
+            token => return Err(syntax(position, format!("unexpected {token}"))),
+        };
+        self.next += 1;
+        Ok(expr)
+    }
+}
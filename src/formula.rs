@@ -0,0 +1,357 @@
+// ============================================================================
+// FORMULAS - COUNTERS COMPUTED FROM OTHER COUNTERS
+// ============================================================================
+//
+// WHAT: A formula counter is defined as `name = expression`, for example
+//
+//           total = door_a + door_b        ratio = wins * 100 / games
+//
+//       using the expression language of src/expr.rs. Names refer to other
+//       counters on the board, or to other formulas.
+//
+// VALUES ARE WHOLE NUMBERS, like every count: `/` rounds toward zero, so
+//      `ratio = wins / games` is 0 until `wins` catches up with `games`.
+//      Scale first to keep the digits you want (`wins * 100 / games` is a
+//      percentage).
+//
+// UNITS FOLLOW THE COUNTERS (see src/units.rs): `water + juice` adds 1 l
+//      and 500 ml up as 1500 ml - both sides are brought to the smaller
+//      unit, so the sum stays whole. `water * 2` is still in ml, and
+//      `juice / water` (same dimension) is a plain number. Anything else -
+//      ml plus g, ml plus a plain number, ml times ml - is a unit error,
+//      not a meaningless number.
+//
+// WHY CYCLE DETECTION? `a = b + 1` and `b = a + 1` define each other, and
+//      `x = x + 1` defines itself: there is no value to compute. Evaluating
+//      naively would recurse forever.
+//
+// HOW: Two passes.
+//      1. Tarjan's algorithm splits the formulas into "strongly connected
+//         components": groups that all (indirectly) use each other. Every
+//         formula in a group of two or more - or using itself - is in a
+//         loop, and reports its shortest one (`a → b → a`) as its error.
+//      2. A depth-first walk evaluates the rest. Each formula first evaluates
+//         the formulas it uses (remembering every result, so shared ones are
+//         computed once), then itself. A formula that uses a broken one is
+//         reported as broken too, naming the culprit, instead of showing a
+//         misleading number. Loops were found in pass 1, so this never
+//         recurses forever.
+//
+// LEARNING: This module is plain Rust with no Leptos in it. The board wraps
+//           `evaluate_all` in a `Memo` (see `CounterBoard` in src/lib.rs),
+//           which re-runs it only when a counter or formula changes.
+
+use crate::expr::{BinaryOp, Expr, ExprError};
+use crate::units::{Quantity, Unit, UnitError};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+/// A named formula, parsed from `name = expression`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Formula {
+    pub name: String,
+    /// The expression as typed, for display
+    pub source: String,
+    pub expr: Expr,
+}
+
+/// Why a formula has no value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormulaError {
+    /// Not of the form `name = expression`
+    Definition(String),
+    /// The expression failed to parse or evaluate
+    Expr(ExprError),
+    /// The formula is part of a loop; the path starts and ends at the same name
+    Cycle(Vec<String>),
+    /// It uses another formula that has an error
+    Broken(String),
+    /// It combines units that don't go together (`water + flour`)
+    Unit(UnitError),
+}
+
+impl fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormulaError::Definition(message) => f.write_str(message),
+            FormulaError::Expr(err) => write!(f, "{err}"),
+            FormulaError::Cycle(path) => write!(f, "circular formula: {}", path.join(" → ")),
+            FormulaError::Broken(name) => write!(f, "uses `{name}`, which has an error"),
+            FormulaError::Unit(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for FormulaError {}
+
+/// Whether `name` can name a counter or formula (like a Rust variable)
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+impl FromStr for Formula {
+    type Err = FormulaError;
+
+    /// Parses `name = expression`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((name, source)) = s.split_once('=') else {
+            return Err(FormulaError::Definition(
+                "write formulas as `name = expression`".to_string(),
+            ));
+        };
+        let (name, source) = (name.trim(), source.trim());
+        if !is_valid_name(name) {
+            return Err(FormulaError::Definition(format!(
+                "`{name}` is not a valid name"
+            )));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            expr: Expr::parse(source).map_err(FormulaError::Expr)?,
+        })
+    }
+}
+
+/// Every formula's value (or error), by name
+///
+/// `counter` looks up the plain counters' values, with their units.
+pub fn evaluate_all(
+    counter: &dyn Fn(&str) -> Option<Quantity>,
+    formulas: &[Formula],
+) -> HashMap<String, Result<Quantity, FormulaError>> {
+    let by_name: HashMap<&str, &Formula> = formulas.iter().map(|f| (f.name.as_str(), f)).collect();
+    // The formulas each formula uses (plain counters aren't part of loops)
+    let uses: HashMap<&str, Vec<&str>> = formulas
+        .iter()
+        .map(|formula| {
+            let used = formula.expr.names().into_iter();
+            let used = used.filter(|name| by_name.contains_key(name)).collect();
+            (formula.name.as_str(), used)
+        })
+        .collect();
+
+    let mut walk = Walk {
+        counter,
+        formulas: by_name,
+        results: HashMap::new(),
+    };
+    for component in components(formulas, &uses) {
+        let [first] = component.as_slice() else {
+            for &name in &component {
+                let path = shortest_loop(&uses, &component, name);
+                walk.results
+                    .insert(name.to_string(), Err(FormulaError::Cycle(path)));
+            }
+            continue;
+        };
+        if uses[first].contains(first) {
+            let path = vec![first.to_string(), first.to_string()];
+            walk.results
+                .insert(first.to_string(), Err(FormulaError::Cycle(path)));
+        }
+    }
+    for formula in formulas {
+        // Every result is also kept in `walk.results`
+        _ = walk.visit(&formula.name);
+    }
+    walk.results
+}
+
+/// The strongly connected components of the `uses` graph (Tarjan's algorithm)
+fn components<'a>(
+    formulas: &'a [Formula],
+    uses: &HashMap<&'a str, Vec<&'a str>>,
+) -> Vec<Vec<&'a str>> {
+    let mut tarjan = Tarjan {
+        uses,
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        components: Vec::new(),
+    };
+    for formula in formulas {
+        if !tarjan.index.contains_key(formula.name.as_str()) {
+            tarjan.connect(&formula.name);
+        }
+    }
+    tarjan.components
+}
+
+/// The state of Tarjan's algorithm
+///
+/// Formulas get an `index` in the order they are reached; `low` is the
+/// smallest index reachable from a formula through formulas still on the
+/// `stack`. A formula whose `low` is its own index closes a component: it
+/// and everything above it on the stack.
+struct Tarjan<'a, 'g> {
+    uses: &'g HashMap<&'a str, Vec<&'a str>>,
+    index: HashMap<&'a str, usize>,
+    low: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Tarjan<'a, '_> {
+    fn connect(&mut self, name: &'a str) {
+        let index = self.index.len();
+        self.index.insert(name, index);
+        self.low.insert(name, index);
+        self.stack.push(name);
+
+        for &used in &self.uses[name] {
+            let reachable = match self.index.get(used) {
+                None => {
+                    self.connect(used);
+                    self.low[used]
+                }
+                Some(&used_index) if self.stack.contains(&used) => used_index,
+                // Already in a finished component
+                Some(_) => continue,
+            };
+            let low = self.low.get_mut(name).expect("visited");
+            *low = (*low).min(reachable);
+        }
+
+        if self.low[name] == index {
+            let start = self
+                .stack
+                .iter()
+                .rposition(|n| *n == name)
+                .expect("on the stack");
+            self.components.push(self.stack.split_off(start));
+        }
+    }
+}
+
+/// The shortest loop from `start` back to itself inside `component`
+fn shortest_loop(uses: &HashMap<&str, Vec<&str>>, component: &[&str], start: &str) -> Vec<String> {
+    let component: HashSet<&str> = component.iter().copied().collect();
+    // Breadth-first, remembering where each formula was reached from
+    let mut from: HashMap<&str, &str> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(name) = queue.pop_front() {
+        for &used in &uses[name] {
+            if used == start {
+                let mut path = vec![start.to_string()];
+                let mut back = name;
+                while back != start {
+                    path.insert(1, back.to_string());
+                    back = from[back];
+                }
+                path.push(start.to_string());
+                return path;
+            }
+            if component.contains(used) && !from.contains_key(used) {
+                from.insert(used, name);
+                queue.push_back(used);
+            }
+        }
+    }
+    // Every formula in a component is on a loop, so this isn't reached
+    vec![start.to_string(), start.to_string()]
+}
+
+/// The state of the depth-first walk in `evaluate_all`
+struct Walk<'a> {
+    counter: &'a dyn Fn(&str) -> Option<Quantity>,
+    formulas: HashMap<&'a str, &'a Formula>,
+    /// Every result so far; formulas in loops are in it from the start
+    results: HashMap<String, Result<Quantity, FormulaError>>,
+}
+
+impl Walk<'_> {
+    fn visit(&mut self, name: &str) -> Result<Quantity, FormulaError> {
+        if let Some(result) = self.results.get(name) {
+            return result.clone();
+        }
+        let Some(formula) = self.formulas.get(name).copied() else {
+            return (self.counter)(name)
+                .ok_or_else(|| FormulaError::Expr(ExprError::UnknownName(name.to_string())));
+        };
+
+        let mut values = HashMap::new();
+        let mut result = Ok(());
+        for used in formula.expr.names() {
+            if !self.formulas.contains_key(used) {
+                continue;
+            }
+            match self.visit(used) {
+                Ok(value) => {
+                    values.insert(used, value);
+                }
+                Err(_) => {
+                    result = Err(FormulaError::Broken(used.to_string()));
+                    break;
+                }
+            }
+        }
+
+        let result = result.and_then(|()| {
+            eval(&formula.expr, &|used| {
+                values.get(used).copied().or_else(|| (self.counter)(used))
+            })
+        });
+        self.results.insert(name.to_string(), result.clone());
+        result
+    }
+}
+
+/// Evaluate `expr` like `Expr::eval`, keeping track of units (see the
+/// module comment)
+fn eval(expr: &Expr, lookup: &dyn Fn(&str) -> Option<Quantity>) -> Result<Quantity, FormulaError> {
+    match expr {
+        Expr::Number(n) => Ok(Quantity::new(*n, None)),
+        Expr::Name(name) => {
+            lookup(name).ok_or_else(|| FormulaError::Expr(ExprError::UnknownName(name.clone())))
+        }
+        Expr::Neg(inner) => {
+            let quantity = eval(inner, lookup)?;
+            let value = whole_in(quantity, None)?
+                .checked_neg()
+                .ok_or(FormulaError::Expr(ExprError::Overflow))?;
+            Ok(Quantity::new(value, quantity.unit))
+        }
+        Expr::Binary(op, left, right) => {
+            let (a, b) = (eval(left, lookup)?, eval(right, lookup)?);
+            let mismatch = || FormulaError::Unit(UnitError::Incompatible(a.unit, b.unit));
+            // Two units of one dimension are compared in the smaller one
+            let common = match (a.unit, b.unit) {
+                (Some(x), Some(y)) if x.dimension() == y.dimension() => Some(x.smaller(y)),
+                (Some(_), Some(_)) => return Err(mismatch()),
+                _ => None,
+            };
+            let unit = match (op, a.unit, b.unit) {
+                (_, None, None) => None,
+                (BinaryOp::Add | BinaryOp::Sub | BinaryOp::Rem, Some(_), Some(_)) => common,
+                (BinaryOp::Div, Some(_), Some(_)) => None,
+                // Scaling by a plain number keeps the unit
+                (BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem, unit @ Some(_), None) => unit,
+                (BinaryOp::Mul, None, unit @ Some(_)) => unit,
+                _ => return Err(mismatch()),
+            };
+            let value = op
+                .apply(whole_in(a, common)?, whole_in(b, common)?)
+                .map_err(FormulaError::Expr)?;
+            Ok(Quantity::new(value, unit))
+        }
+    }
+}
+
+/// `quantity`'s value, converted to `unit` first when there is one
+///
+/// Formula values are whole, and `unit` is never larger than the
+/// quantity's own, so the result is whole too.
+fn whole_in(quantity: Quantity, unit: Option<Unit>) -> Result<i32, FormulaError> {
+    match unit {
+        Some(_) => Ok(quantity
+            .convert(unit)
+            .map_err(FormulaError::Unit)?
+            .value
+            .trunc()),
+        None => Ok(quantity.value.trunc()),
+    }
+}
@@ -0,0 +1,134 @@
+// ============================================================================
+// GESTURES - SWIPES, LONG PRESSES AND HAPTICS
+// ============================================================================
+//
+// WHAT: What makes the counter comfortable on a phone:
+//
+//           swipe up on the count       +step
+//           swipe down on the count     -step
+//           hold an operation button    repeat it, faster and faster,
+//                                       until released (keyboard too)
+//
+//       with a short buzz (the Vibration API) where the device has one.
+//
+// WHY POINTER EVENTS? `pointerdown`/`pointerup` fire for mouse, pen AND
+//      touch, so one set of handlers works everywhere. A swipe is just a
+//      press and a release far enough apart; holding a mouse button on "+1"
+//      repeats exactly like holding a finger on it.
+//
+// HOW: `SwipeTracker` is given where and when the pointer went down and up,
+//      and decides whether that was a swipe. `Hold` says how long to wait
+//      before each repeat of a held button. Both are plain Rust, so
+//      tests/gesture.rs checks them without a touchscreen; the components in
+//      src/lib.rs only feed them event coordinates and `timer::now()`.
+//
+// LEARNING: Not every browser can vibrate (desktop browsers and iOS Safari
+//           can't). `vibrate` checks for the method first and ignores
+//           errors - like a missing beep in src/sound.rs, a missing buzz
+//           must never break the counter.
+
+/// Which way a swipe went (on screen, so `Up` is towards the top)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Swipe {
+    Up,
+    Down,
+}
+
+/// Where and when a pointer went down
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Start {
+    x: f64,
+    y: f64,
+    time: f64,
+}
+
+/// Recognises vertical swipes from pointer positions (in px) and times (in ms)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SwipeTracker {
+    start: Option<Start>,
+}
+
+impl SwipeTracker {
+    /// Shortest vertical movement that counts as a swipe, in px
+    pub const MIN_DISTANCE: f64 = 30.0;
+
+    /// Longest a swipe may take, in ms; slower drags are not swipes
+    pub const MAX_DURATION: f64 = 800.0;
+
+    /// The pointer went down at (`x`, `y`)
+    pub fn start(&mut self, x: f64, y: f64, time: f64) {
+        self.start = Some(Start { x, y, time });
+    }
+
+    /// Forget the pointer (it was cancelled, e.g. by the browser scrolling)
+    pub fn cancel(&mut self) {
+        self.start = None;
+    }
+
+    /// The pointer went up at (`x`, `y`): was that a swipe, and which way?
+    ///
+    /// Mostly-sideways movements are not swipes, so a horizontal scroll
+    /// never changes the count.
+    pub fn end(&mut self, x: f64, y: f64, time: f64) -> Option<Swipe> {
+        let start = self.start.take()?;
+        let (dx, dy) = (x - start.x, y - start.y);
+        let quick = time - start.time <= Self::MAX_DURATION;
+        let vertical = dy.abs() >= Self::MIN_DISTANCE && dy.abs() > dx.abs();
+        match (quick && vertical, dy < 0.0) {
+            (false, _) => None,
+            // Screen coordinates grow downwards
+            (true, true) => Some(Swipe::Up),
+            (true, false) => Some(Swipe::Down),
+        }
+    }
+}
+
+/// When a held button repeats (in ms)
+///
+/// A long wait before the first repeat, so an ordinary tap never repeats,
+/// then repeats that come quicker every time (`interval`, then
+/// `interval * acceleration`, ...) until they reach `fastest`. Getting from
+/// 0 to 300 takes a few seconds instead of a minute.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hold {
+    /// How long the button must be held before it starts repeating
+    pub delay: f64,
+    /// Time before the second repeat
+    pub interval: f64,
+    /// Each wait is this fraction of the one before (below 1 speeds up)
+    pub acceleration: f64,
+    /// The shortest wait; repeats never come quicker than this
+    pub fastest: f64,
+}
+
+impl Default for Hold {
+    fn default() -> Self {
+        Self {
+            delay: 400.0,
+            interval: 150.0,
+            acceleration: 0.85,
+            fastest: 25.0,
+        }
+    }
+}
+
+impl Hold {
+    /// How long to wait for repeat number `repeat` (0 is the first one)
+    pub fn wait(self, repeat: u32) -> f64 {
+        match repeat {
+            0 => self.delay,
+            // `min` keeps a huge `repeat` from overflowing `powi`'s `i32`
+            n => (self.interval * self.acceleration.powi(n.min(1000) as i32 - 1)).max(self.fastest),
+        }
+    }
+}
+
+/// Buzz for `ms` milliseconds, if the device can (see the module comment)
+pub fn vibrate(ms: u32) {
+    let Some(navigator) = web_sys::window().map(|window| window.navigator()) else {
+        return;
+    };
+    if js_sys::Reflect::has(&navigator, &"vibrate".into()).unwrap_or(false) {
+        let _ = navigator.vibrate_with_duration(ms);
+    }
+}
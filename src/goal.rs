@@ -0,0 +1,120 @@
+// ============================================================================
+// GOAL - HOW FAR ALONG A DAILY TARGET ARE WE, AND WHEN WILL WE GET THERE?
+// ============================================================================
+//
+// WHAT: A counter can have a goal ("100 reviews today"). `Goal` turns the
+//       count into a fraction/percent for the progress bar, and `Pace`
+//       estimates when the goal will be reached at the current speed.
+//
+// HOW (the projection): `Pace` remembers the count at recent moments (the
+//      last `window` seconds). The rate is "how much the count grew between
+//      the oldest and the newest sample, per second"; the time left is
+//      "what's missing / rate". Only recent samples count, so a burst of work
+//      after a long break is projected at the burst's speed.
+//
+// LEARNING: Like `Ticker`, `Pace` is given the time instead of reading a
+//           clock, so tests/goal.rs can check projections without waiting.
+
+use std::collections::VecDeque;
+
+/// A target the count should reach (see the module comment)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Goal {
+    pub target: i32,
+}
+
+impl Goal {
+    /// How far `value` is towards the target, from 0.0 to 1.0
+    pub fn progress(self, value: i32) -> f64 {
+        if self.is_reached(value) {
+            1.0
+        } else if self.target <= 0 {
+            0.0
+        } else {
+            (f64::from(value) / f64::from(self.target)).clamp(0.0, 1.0)
+        }
+    }
+
+    /// `progress` as a whole percentage (only 100 once actually reached)
+    pub fn percent(self, value: i32) -> u32 {
+        match self.is_reached(value) {
+            true => 100,
+            false => ((self.progress(value) * 100.0).floor() as u32).min(99),
+        }
+    }
+
+    /// Whether `value` has reached the target
+    pub fn is_reached(self, value: i32) -> bool {
+        value >= self.target
+    }
+
+    /// The celebration shown once the goal is reached
+    pub fn message(self) -> String {
+        format!("🎉 Goal reached! {} done!", self.target)
+    }
+}
+
+/// The recent speed of a counter (see the module comment); times in seconds
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pace {
+    samples: VecDeque<(f64, i32)>,
+    window: f64,
+}
+
+impl Default for Pace {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_WINDOW)
+    }
+}
+
+impl Pace {
+    /// How many seconds of history the rate is based on by default
+    pub const DEFAULT_WINDOW: f64 = 300.0;
+
+    /// No samples yet, rate based on the last `window` seconds
+    pub fn new(window: f64) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            window,
+        }
+    }
+
+    /// Remember that the count was `value` at time `now`
+    pub fn record(&mut self, now: f64, value: i32) {
+        self.samples.push_back((now, value));
+        // Keep one sample older than the window, so the window is fully covered
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|(time, _)| now - time >= self.window)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// How much the count grew per second recently (`None` without two samples)
+    pub fn per_second(&self) -> Option<f64> {
+        let (start, first) = self.samples.front()?;
+        let (end, last) = self.samples.back()?;
+        (end > start).then(|| (f64::from(*last) - f64::from(*first)) / (end - start))
+    }
+
+    /// Seconds until `goal` is reached from `value` at the recent rate
+    ///
+    /// `None` when reached already, or when the count isn't growing.
+    /// The distance is computed in `f64`: it can be wider than an `i32`.
+    pub fn eta(&self, goal: Goal, value: i32) -> Option<f64> {
+        let rate = self.per_second().filter(|rate| *rate > 0.0)?;
+        (!goal.is_reached(value)).then(|| (f64::from(goal.target) - f64::from(value)) / rate)
+    }
+}
+
+/// A duration in seconds, rounded for humans: "45s", "2m 30s", "3h 5m"
+pub fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.max(0.0).round() as u64;
+    match (seconds / 3600, seconds % 3600 / 60, seconds % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s}s"),
+        (h, m, _) => format!("{h}h {m}m"),
+    }
+}
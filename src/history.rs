@@ -0,0 +1,128 @@
+// ============================================================================
+// HISTORY - WHAT HAPPENED, AND HOW TO UNDO IT
+// ============================================================================
+//
+// WHAT: A list of the changes made to a counter, newest last. The "Undo"
+//       button pops the newest change and puts the counter back to the value
+//       it had BEFORE that change.
+//
+// WHY REMEMBER `before`? Not every operation can be reversed: "/2" turns both
+//      5 and 4 into 2, and "Reset" forgets the value completely. Storing the
+//      value each change started from makes every undo exact.
+//
+// MILESTONES: events worth remembering that are not changes themselves, like
+//             "countdown finished". They are kept next to the changes but
+//             "Undo" skips them.
+//
+// GENERIC: values are `i32` by default; exact-mode counters remember
+//          `Rational`s (see src/rational.rs) so undo restores fractions too.
+//
+// LEARNING: `VecDeque` is a queue that is cheap to push and pop at both
+//           ends - we push new entries at the back and drop the oldest from
+//           the front once `limit` is reached.
+
+use crate::operation::Operation;
+use std::collections::VecDeque;
+
+/// One change made to a counter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry<T = i32> {
+    /// What was done
+    pub op: Operation,
+    /// The value before the operation (what undo restores)
+    pub before: T,
+    /// The value after the operation
+    pub after: T,
+}
+
+/// Something that happened to a counter without being a change
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Milestone {
+    /// What happened, e.g. "⏰ Countdown from 10 finished!"
+    pub message: String,
+    /// The count when it happened
+    pub value: i32,
+}
+
+/// The most recent changes of one counter (see the module comment)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct History<T = i32> {
+    entries: VecDeque<Entry<T>>,
+    milestones: VecDeque<Milestone>,
+    limit: usize,
+}
+
+impl<T: PartialEq> Default for History<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq> History<T> {
+    /// How many changes are remembered by default
+    pub const DEFAULT_LIMIT: usize = 100;
+
+    /// An empty history remembering up to `DEFAULT_LIMIT` changes
+    pub fn new() -> Self {
+        Self::with_limit(Self::DEFAULT_LIMIT)
+    }
+
+    /// An empty history remembering up to `limit` changes
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            milestones: VecDeque::new(),
+            limit,
+        }
+    }
+
+    /// Remember that `op` changed the value from `before` to `after`
+    ///
+    /// Operations that didn't change anything (like "Reset" at 0, or "+1"
+    /// at the maximum) are skipped, so "Undo" always visibly undoes something.
+    pub fn record(&mut self, op: Operation, before: T, after: T) {
+        if before == after || self.limit == 0 {
+            return;
+        }
+        if self.entries.len() == self.limit {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Entry { op, before, after });
+    }
+
+    /// Remember a milestone (the oldest is forgotten beyond `limit`)
+    pub fn mark(&mut self, milestone: Milestone) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.milestones.len() == self.limit {
+            self.milestones.pop_front();
+        }
+        self.milestones.push_back(milestone);
+    }
+
+    /// The remembered milestones, oldest first
+    pub fn milestones(&self) -> impl DoubleEndedIterator<Item = &Milestone> {
+        self.milestones.iter()
+    }
+
+    /// Forget the newest change and return it; restore `entry.before`
+    pub fn undo(&mut self) -> Option<Entry<T>> {
+        self.entries.pop_back()
+    }
+
+    /// The remembered changes, oldest first
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &Entry<T>> {
+        self.entries.iter()
+    }
+
+    /// How many changes can be undone
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there is nothing to undo
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
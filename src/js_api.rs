@@ -0,0 +1,235 @@
+// ============================================================================
+// JAVASCRIPT API - READ AND DRIVE A COUNTER FROM A HOST PAGE
+// ============================================================================
+//
+// WHAT: Functions and classes exported to JavaScript, so a page can mount
+//       the app, or a bare counter, into ANY element and talk to it:
+//
+//     import init, { mountApp, mountCounter } from "./pkg/webtest.js";
+//     await init();
+//     const app = mountApp("#sidebar");          // selector or element
+//     app.unmount();
+//
+//     const counter = mountCounter(document.getElementById("slot"));
+//     counter.setCount(10);
+//     counter.applyOperation("double");          // 20
+//     counter.applyOperation({ add: 5 });        // 25
+//     const stop = counter.subscribe((value, previous) => console.log(value));
+//     counter.getCount();                        // 25
+//     stop();                                    // no more callbacks
+//     counter.unmount();                         // or counter.free()
+//
+// TYPESCRIPT: `wasm-pack build` writes `pkg/webtest.d.ts` from these exports.
+//             The custom section below adds the `Operation` and
+//             `CountListener` types the methods refer to.
+//
+// INSTANCES: every mount gets its own reactive owner, so its signals,
+//            effects and server connection are independent of the others,
+//            and are all disposed when that mount is unmounted.
+//
+// LEARNING: Everything goes through `CounterControl` (src/control.rs), so
+//           JavaScript gets the same bounds checks and server sync as clicks.
+
+use crate::control::CounterControl;
+use crate::operation::Operation;
+use crate::{App, Counter};
+use leptos::mount::{mount_to, UnmountHandle};
+use leptos::prelude::*;
+use leptos::tachys::view::Mountable;
+use std::any::Any;
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlElement;
+
+/// Elements marked with this attribute get the app mounted by `main`
+const AUTO_MOUNT_SELECTOR: &str = "[data-webtest-app]";
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &'static str = r##"
+/** One counter operation, as sent to the server (see src/operation.rs) */
+export type Operation =
+  | "increment"
+  | "decrement"
+  | "reset"
+  | "double"
+  | "halve"
+  | { add: number }
+  | { set: number };
+
+/** Called after every change with the new and the previous count */
+export type CountListener = (value: number, previous: number) => void;
+
+/** Where to mount: a CSS selector such as "#app", or the element itself */
+export type MountTarget = string | HTMLElement;
+"##;
+
+/// Find the element to mount into: a CSS selector string or an element
+fn resolve_target(target: JsValue) -> Result<HtmlElement, JsError> {
+    if let Some(selector) = target.as_string() {
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or_else(|| JsError::new("no document to mount into"))?;
+        return document
+            .query_selector(&selector)
+            .map_err(|_| JsError::new(&format!("invalid selector {selector:?}")))?
+            .and_then(|element| element.dyn_into().ok())
+            .ok_or_else(|| JsError::new(&format!("no element matches {selector:?}")));
+    }
+    target
+        .dyn_into()
+        .map_err(|_| JsError::new("mount target must be a selector or an HTMLElement"))
+}
+
+/// Every `data-webtest-app` element on the page (used by `main`)
+pub(crate) fn auto_mount_targets() -> Vec<HtmlElement> {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return Vec::new();
+    };
+    let Ok(found) = document.query_selector_all(AUTO_MOUNT_SELECTOR) else {
+        return Vec::new();
+    };
+    (0..found.length())
+        .filter_map(|i| found.item(i)?.dyn_into().ok())
+        .collect()
+}
+
+/// Mount an independent, embedded `App` inside `target`
+pub(crate) fn mount_app_into(target: HtmlElement) -> UnmountHandle<impl Mountable> {
+    mount_to(target, || view! { <App embedded=true /> })
+}
+
+/// An app mounted by `mountApp`
+#[wasm_bindgen]
+pub struct AppHandle {
+    /// `None` once unmounted
+    mounted: Option<Box<dyn Any>>,
+}
+
+/// Mount the whole app inside `target` instead of taking over `<body>`
+///
+/// Can be called several times; every instance has its own state.
+/// Throws when the selector matches nothing.
+#[wasm_bindgen(js_name = mountApp)]
+pub fn mount_app(
+    #[wasm_bindgen(unchecked_param_type = "MountTarget")] target: JsValue,
+) -> Result<AppHandle, JsError> {
+    let target = resolve_target(target)?;
+    Ok(AppHandle {
+        mounted: Some(Box::new(mount_app_into(target))),
+    })
+}
+
+#[wasm_bindgen]
+impl AppHandle {
+    /// Remove the app from the page, disposing its signals, effects, event
+    /// listeners and server connection. Calling it again does nothing.
+    pub fn unmount(&mut self) {
+        self.mounted = None;
+    }
+}
+
+/// A counter mounted by `mountCounter`
+///
+/// Call `unmount()` (or `free()`) to remove it.
+#[wasm_bindgen]
+pub struct CounterHandle {
+    // Field order matters: the view is unmounted before its owner is dropped
+    /// Leptos' `UnmountHandle`; dropping it removes the counter from the page.
+    /// `None` once unmounted.
+    mounted: Option<Box<dyn Any>>,
+    /// Owns the control and every `subscribe` effect
+    owner: Owner,
+    control: CounterControl,
+}
+
+/// Mount a bare counter inside `target` (instead of taking over `<body>`)
+///
+/// Throws when the selector matches nothing.
+#[wasm_bindgen(js_name = mountCounter)]
+pub fn mount_counter(
+    #[wasm_bindgen(unchecked_param_type = "MountTarget")] target: JsValue,
+) -> Result<CounterHandle, JsError> {
+    let target = resolve_target(target)?;
+    let owner = Owner::new();
+    let (control, mounted) = owner.with(|| {
+        let control = CounterControl::new();
+        let mounted = mount_to(target, move || {
+            view! { <Counter control=control /> }
+        });
+        (control, mounted)
+    });
+
+    Ok(CounterHandle {
+        mounted: Some(Box::new(mounted)),
+        owner,
+        control,
+    })
+}
+
+#[wasm_bindgen]
+impl CounterHandle {
+    /// Remove the counter from the page and dispose its state and listeners
+    ///
+    /// Afterwards `getCount()` returns 0 and the other methods do nothing.
+    pub fn unmount(&mut self) {
+        self.mounted = None;
+        self.owner.cleanup();
+    }
+
+    /// The current count
+    #[wasm_bindgen(js_name = getCount)]
+    pub fn get_count(&self) -> i32 {
+        self.control.get()
+    }
+
+    /// Jump to `value` (clamped to the counter's bounds, synced like a click)
+    #[wasm_bindgen(js_name = setCount)]
+    pub fn set_count(&self, value: i32) {
+        self.control.apply(Operation::Set(value));
+    }
+
+    /// Go back to the count before the last change (like the "Undo" button)
+    pub fn undo(&self) {
+        self.control.undo();
+    }
+
+    /// Apply an operation such as `"double"` or `{ add: 5 }`
+    ///
+    /// Throws when `op` is not a valid `Operation`.
+    #[wasm_bindgen(js_name = applyOperation)]
+    pub fn apply_operation(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Operation")] op: JsValue,
+    ) -> Result<(), JsError> {
+        // JSON is the format `Operation` already speaks (serde), so reuse it
+        let json = js_sys::JSON::stringify(&op)
+            .ok()
+            .and_then(|json| json.as_string())
+            .unwrap_or_default();
+        let op: Operation = serde_json::from_str(&json)
+            .map_err(|err| JsError::new(&format!("invalid operation {json}: {err}")))?;
+        self.control.apply(op);
+        Ok(())
+    }
+
+    /// Call `listener(value, previous)` after every change
+    ///
+    /// Returns a function that stops the callbacks.
+    #[wasm_bindgen(unchecked_return_type = "() => void")]
+    pub fn subscribe(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "CountListener")] listener: js_sys::Function,
+    ) -> js_sys::Function {
+        let count = self.control.count();
+        // Created under the counter's owner, so unmounting also stops it
+        let effect = self.owner.with(|| {
+            Effect::new(move |previous: Option<i32>| {
+                let value = count.get();
+                if let Some(previous) = previous.filter(|previous| *previous != value) {
+                    _ = listener.call2(&JsValue::NULL, &value.into(), &previous.into());
+                }
+                value
+            })
+        });
+        Closure::once_into_js(move || effect.stop()).unchecked_into()
+    }
+}
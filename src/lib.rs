@@ -15,9 +15,66 @@
 ///
 /// READ THIS FILE FROM TOP TO BOTTOM to understand how everything connects.
 /// Each section builds on previous concepts.
+///
+/// RENDERING MODES (cargo features):
+/// - `csr` (default) - client-side rendering, the WASM builds the whole page (Trunk)
+/// - `hydrate` - the WASM "wakes up" HTML that the server already rendered
+/// - `ssr` - native build used by the `server` binary to render `App` to HTML
 use leptos::prelude::*;
+#[cfg(any(feature = "csr", feature = "hydrate"))]
 use wasm_bindgen::prelude::*;
 
+// Supporting modules (each file starts with its own explanation)
+pub mod animation;
+pub mod api;
+pub mod band;
+pub mod board;
+pub mod control;
+pub mod crdt;
+#[cfg(feature = "csr")]
+pub mod element;
+pub mod expr;
+pub mod formula;
+pub mod gesture;
+pub mod goal;
+pub mod history;
+#[cfg(feature = "csr")]
+pub mod js_api;
+pub mod notify;
+pub mod operation;
+#[cfg(any(test, feature = "test-probe"))]
+pub mod probe;
+pub mod pwa;
+pub mod rational;
+#[cfg(feature = "ssr")]
+pub mod routes;
+pub mod search;
+pub mod settings;
+pub mod sound;
+#[cfg(feature = "ssr")]
+pub mod store;
+pub mod sync;
+pub mod timer;
+pub mod units;
+
+use animation::Tween;
+use band::Band;
+use board::Board;
+use control::CounterControl;
+use formula::FormulaError;
+use gesture::{Swipe, SwipeTracker};
+use goal::{Goal, Pace};
+use history::{History, Milestone};
+use notify::Permission;
+use operation::{Bounds, Operation};
+#[cfg(any(test, feature = "test-probe"))]
+use probe::TestProbe;
+use rational::Rational;
+use settings::Settings;
+use sound::Sound;
+use timer::{Countdown, Ticker, Timeout, TimerState};
+use units::{Quantity, Unit};
+
 // ============================================================================
 // SECTION 1: CONFIGURATION & THEME CONSTANTS
 // ============================================================================
@@ -47,6 +104,21 @@ mod theme {
 
     /// Muted red/grey for secondary text
     pub const TEXT_MUTED: &str = "#e57373";
+
+    /// Gold - used only to celebrate a reached goal
+    pub const CELEBRATION: &str = "#ffd54f";
+
+    /// Icy blue - for counts below zero
+    pub const ICE_BLUE: &str = "#6bb6ff";
+
+    /// The color `CounterMessage` uses for a band
+    pub fn band(band: super::Band) -> &'static str {
+        match band {
+            super::Band::High => BRIGHT_RED,
+            super::Band::Negative => ICE_BLUE,
+            super::Band::Zero | super::Band::Normal => TEXT_MUTED,
+        }
+    }
 }
 
 // ============================================================================
@@ -83,10 +155,18 @@ mod theme {
 /// }
 /// ```
 ///
+/// `embedded=true` fills the element the app was mounted into (see
+/// `mountApp` in src/js_api.rs) instead of the whole screen.
+///
 /// LEARNING: This pattern (wrapping children) is how you build flexible layouts.
 ///           Think of it like HTML's <body> or <div> elements that can wrap anything.
 #[component]
-fn EvilBackground(children: Children) -> impl IntoView {
+pub fn EvilBackground(
+    /// Fill the parent element instead of the whole screen
+    #[prop(optional)]
+    embedded: bool,
+    children: Children,
+) -> impl IntoView {
     // Create a CSS gradient string that transitions from DARK_GREY to EVIL_RED
     // The 135deg angle creates a diagonal gradient (top-left to bottom-right)
     let bg_gradient = format!(
@@ -95,19 +175,30 @@ fn EvilBackground(children: Children) -> impl IntoView {
         theme::EVIL_RED
     );
 
+    // Full screen: at least the size of the viewport.
+    // Embedded: the size of the element we were mounted into.
+    let size = if embedded {
+        "min-height: 100%; width: 100%; box-sizing: border-box;"
+    } else {
+        "min-height: 100vh; width: 100%; box-sizing: border-box;"
+    };
+
     // The view! macro returns HTML-like syntax that Leptos converts to actual DOM elements
     // We'll explain this in detail below.
     view! {
         // A full-screen div with flexbox centering
         <div style=format!(
-            // min-height/min-width: Make it at least the full screen size
+            // size: Make it at least the full screen (or parent) size
             // background: Apply the gradient we created above
             // display: flex: Enable flexbox layout
             // justify-content: center: Center horizontally
             // align-items: center: Center vertically
-            // padding: 20px: Add some breathing room on mobile
-            "min-height: 100vh; min-width: 100vw; background: {}; display: flex; justify-content: center; align-items: center; padding: 20px;",
-            bg_gradient
+            // flex-wrap: wrap: On a narrow screen, stack the cards instead of squeezing them
+            // gap: Space between the cards
+            // padding: Breathing room that shrinks on small phones (clamp = min, preferred, max)
+            "{} background: {}; display: flex; flex-wrap: wrap; gap: 20px; justify-content: center; \
+             align-items: center; padding: clamp(8px, 3vw, 20px);",
+            size, bg_gradient
         )>
             // Render whatever was passed as children
             // This could be <Counter />, <h1>, etc.
@@ -127,6 +218,10 @@ fn EvilBackground(children: Children) -> impl IntoView {
 //
 // KEY CONCEPT: Components can take "props" (properties/parameters) that control
 //              how they look and behave. This makes them configurable.
+//
+// TESTING: Every component is `pub` so the tests in tests/components.rs and
+//          tests/snapshots.rs can render them to HTML without a browser.
+//          They click buttons through a `TestProbe` (see src/probe.rs).
 
 /// EvilButton - A reusable button with consistent styling
 ///
@@ -152,8 +247,21 @@ fn EvilBackground(children: Children) -> impl IntoView {
 /// ```
 ///
 /// LEARNING: The more configurable your components, the more you'll reuse them.
+///
+/// HOLD TO REPEAT (`repeat=true`):
+/// Holding the button down (mouse, finger, or Enter/Space while it has
+/// focus) calls `on_click` again and again - faster and faster - until it
+/// is released, with a small buzz each time (see `Hold` in src/gesture.rs).
+/// - The click that ends a hold is ignored, so a hold never does one step
+///   too many; a quick tap is still a single click.
+/// - Sliding off the button stops the repeats at once; coming back and
+///   releasing on it doesn't add a step after a hold either.
+/// - The keyboard's own auto-repeat is ignored: our schedule accelerates,
+///   the operating system's doesn't.
+/// Pointer and key events, unlike clicks, don't come with a `MouseEvent`,
+/// so repeats pass a fresh synthetic "click" event.
 #[component]
-fn EvilButton(
+pub fn EvilButton(
     /// The text to display on the button
     #[prop(into)]
     label: String,
@@ -161,6 +269,10 @@ fn EvilButton(
     /// The function to call when the button is clicked
     /// Takes a MouseEvent (browser event) as a parameter
     on_click: Box<dyn Fn(leptos::ev::MouseEvent) + 'static>,
+
+    /// Keep calling `on_click` while the button is held down
+    #[prop(optional)]
+    repeat: bool,
 ) -> impl IntoView {
     // Build the CSS style string that makes the button look "evil"
     let button_style = format!(
@@ -172,31 +284,181 @@ fn EvilButton(
         // border-radius: Rounded corners (6px = subtle rounding)
         // cursor: pointer: Show pointer cursor on hover (indicates clickable)
         // font-weight: 600: Semi-bold text
-        // min-width: 100px: Minimum button width (so small text doesn't make tiny buttons)
+        // min-width/min-height: At least 48px - a comfortable fingertip target
         // border-bottom: 3px solid BRIGHT_RED: A bottom border for 3D effect
         // box-shadow: Subtle shadow under the button
         // outline: 2px solid border around the button
         // transition: all 0.3s ease: Smooth animation for hover effects (see CSS)
+        // touch-action: manipulation: No double-tap zoom, so fast taps all count
+        // user-select: none: Holding the button doesn't select its label
         "padding: 12px 24px; margin: 5px; background: linear-gradient(90deg, {} 0%, {} 100%); \
          color: #fff; border: none; border-radius: 6px; cursor: pointer; \
-         font-weight: 600; min-width: 100px; border-bottom: 3px solid {}; \
-         box-shadow: 0 2px 8px #1a0000; outline: 2px solid #2d232b; transition: all 0.3s ease;",
+         font-weight: 600; min-width: 100px; min-height: 48px; border-bottom: 3px solid {}; \
+         box-shadow: 0 2px 8px #1a0000; outline: 2px solid #2d232b; transition: all 0.3s ease; \
+         touch-action: manipulation; user-select: none; -webkit-user-select: none;",
         theme::EVIL_RED,
         theme::DARK_GREY,
         theme::BRIGHT_RED
     );
 
+    let hold = HoldLoop {
+        pending: StoredValue::new_local(None),
+        repeats: StoredValue::new(None),
+        on_click: StoredValue::new_local(on_click),
+    };
+    // Set when a hold ends, so the click that follows it is ignored
+    let swallow_click = StoredValue::new(false);
+
+    let on_pointer_down = move |ev: leptos::ev::PointerEvent| {
+        // A hold released off the button never got its click: don't let it
+        // swallow this press's
+        swallow_click.set_value(false);
+        // Only the primary button (or a finger) repeats
+        if repeat && ev.is_primary() && ev.button() == 0 {
+            hold.press();
+        }
+    };
+    let on_pointer_up = move |_| swallow_click.set_value(hold.release());
+    let on_pointer_cancel = move |_| {
+        hold.release();
+    };
+    // Stop repeating, but remember the hold until the pointer comes back up
+    let on_pointer_leave = move |_| hold.stop();
+
+    // Enter and Space normally click the button themselves (Enter on every
+    // auto-repeated keydown, Space on keyup); with `repeat` we do it instead
+    let is_activation = |ev: &leptos::ev::KeyboardEvent| matches!(ev.key().as_str(), "Enter" | " ");
+    let on_key_down = move |ev: leptos::ev::KeyboardEvent| {
+        if repeat && is_activation(&ev) {
+            ev.prevent_default();
+            if !ev.repeat() {
+                hold.press();
+            }
+        }
+    };
+    let on_key_up = move |ev: leptos::ev::KeyboardEvent| {
+        if repeat && is_activation(&ev) {
+            ev.prevent_default();
+            // A quick press is an ordinary click
+            if hold.is_held() && !hold.release() {
+                hold.click();
+            }
+        }
+    };
+    // Focus left while the key was down: the keyup will go elsewhere
+    let on_blur = move |_| {
+        hold.release();
+    };
+
+    let on_click = move |ev: leptos::ev::MouseEvent| {
+        if swallow_click.get_value() {
+            swallow_click.set_value(false);
+        } else {
+            hold.on_click.with_value(|on_click| on_click(ev));
+        }
+    };
+    // Host tests have no DOM to click: they call the handler through the probe
+    #[cfg(any(test, feature = "test-probe"))]
+    if let Some(probe) = TestProbe::current() {
+        probe.register_button(label.clone(), Box::new(on_click));
+    }
+
     // Render a button element with the style and click handler
     view! {
         // The `on:click` attribute binds the on_click function to click events
         // Leptos automatically passes the MouseEvent to the handler
-        <button on:click=on_click style=button_style>
+        <button
+            on:click=on_click
+            on:pointerdown=on_pointer_down
+            on:pointerup=on_pointer_up
+            on:pointercancel=on_pointer_cancel
+            on:pointerleave=on_pointer_leave
+            on:keydown=on_key_down
+            on:keyup=on_key_up
+            on:blur=on_blur
+            // A long press would otherwise open the context menu on phones
+            on:contextmenu=move |ev| {
+                if repeat {
+                    ev.prevent_default();
+                }
+            }
+            style=button_style
+        >
             // Render the label text inside the button
             {label}
         </button>
     }
 }
 
+/// A held `EvilButton`'s repeats - all `Copy` handles, like `TimerLoop`, so
+/// `schedule` and `fire` can call each other from inside timeouts
+#[derive(Clone, Copy)]
+struct HoldLoop {
+    pending: StoredValue<Option<Timeout>, LocalStorage>,
+    /// Repeats so far (`None`: not held)
+    repeats: StoredValue<Option<u32>>,
+    on_click: StoredValue<Box<dyn Fn(leptos::ev::MouseEvent)>, LocalStorage>,
+}
+
+impl HoldLoop {
+    /// The button went down: start waiting for the first repeat
+    fn press(self) {
+        self.repeats.set_value(Some(0));
+        self.schedule();
+    }
+
+    /// Set a timeout for the next repeat (if still held)
+    fn schedule(self) {
+        let Some(repeat) = self.repeats.try_get_value().flatten() else {
+            return;
+        };
+        let wait = gesture::Hold::default().wait(repeat);
+        self.pending
+            .try_set_value(Timeout::new(wait, move || self.fire()));
+    }
+
+    /// Click once more, then schedule the next repeat
+    fn fire(self) {
+        // `None`: released meanwhile, or the component is gone
+        let held = self
+            .repeats
+            .try_update_value(|repeats| repeats.as_mut().map(|n| *n += 1))
+            .flatten();
+        if held.is_none() {
+            return;
+        }
+        self.click();
+        gesture::vibrate(10);
+        self.schedule();
+    }
+
+    /// Call `on_click` with a synthetic click event
+    fn click(self) {
+        if let Ok(ev) = leptos::ev::MouseEvent::new("click") {
+            self.on_click.with_value(|on_click| on_click(ev));
+        }
+    }
+
+    /// Whether the button is being held
+    fn is_held(self) -> bool {
+        self.repeats.try_get_value().flatten().is_some()
+    }
+
+    /// Stop repeating, but stay held (see `release`)
+    fn stop(self) {
+        self.pending.try_set_value(None);
+    }
+
+    /// Stop repeating; returns whether the hold repeated at all
+    fn release(self) -> bool {
+        self.stop();
+        self.repeats
+            .try_update_value(Option::take)
+            .flatten()
+            .is_some_and(|repeats| repeats > 0)
+    }
+}
+
 // ============================================================================
 // SECTION 4: FEATURE COMPONENTS (Counter Logic)
 // ============================================================================
@@ -225,6 +487,22 @@ fn EvilButton(
 ///
 /// LEARNING: Use closures for reactive values. Use plain values for static content.
 ///
+/// EXACT VALUES:
+/// Counters in exact mode also pass `exact` (see src/rational.rs), which is
+/// shown instead of `count` as a decimal with at most `precision` digits:
+/// 3/2 is "1.5". A value that had to be rounded, like 1/3, gets a "≈".
+///
+/// UNITS:
+/// `unit` (optional) is shown after the value, e.g. "Count: 1.5 l".
+///
+/// ROLLING NUMBERS:
+/// A whole count doesn't jump to its new value: it rolls there through the
+/// numbers in between (see `Tween` in src/animation.rs). Only what is SHOWN
+/// rolls - the count itself changed at once. People who ask their system
+/// for reduced motion see the new value straight away, and so does the
+/// server-rendered HTML (effects only run in the browser). Exact values
+/// don't roll: 1/3 has no numbers "in between" worth showing.
+///
 /// REACTIVITY IN ACTION:
 /// When the parent component's `count` signal changes:
 /// 1. Leptos detects the change
@@ -233,10 +511,47 @@ fn EvilButton(
 /// 4. Re-renders this component with the new display
 /// This happens automatically - we don't need to write update code.
 #[component]
-fn CounterDisplay(
+pub fn CounterDisplay(
     /// A closure that returns the current count value
     count: impl Fn() -> i32 + Send + Sync + 'static,
+
+    /// The exact value, shown instead of `count` when given
+    #[prop(optional, into)]
+    exact: MaybeProp<Rational>,
+
+    /// Most digits shown after the decimal point of an exact value
+    #[prop(into, default = Signal::stored(2))]
+    precision: Signal<usize>,
+
+    /// The unit the value is in
+    #[prop(optional, into)]
+    unit: MaybeProp<Unit>,
 ) -> impl IntoView {
+    // `Signal::derive` turns the closure into a `Copy` signal, so both the
+    // effect and the view below can use it
+    let count = Signal::derive(count);
+
+    // While rolling, the number to show instead of the count
+    let rolling = RwSignal::new(None::<i32>);
+    let roll = RollLoop {
+        tween: StoredValue::new(None),
+        pending: StoredValue::new_local(None),
+        rolling,
+    };
+    Effect::new(move |previous: Option<i32>| {
+        let value = count.get();
+        if let Some(previous) = previous.filter(|previous| *previous != value) {
+            roll.start(previous, value);
+        }
+        value
+    });
+
+    let shown = move || match (exact.get(), precision.get()) {
+        (Some(value), precision) if value.is_exact_at(precision) => value.to_decimal(precision),
+        (Some(value), precision) => format!("≈{}", value.to_decimal(precision)),
+        (None, _) => rolling.get().unwrap_or_else(|| count.get()).to_string(),
+    };
+
     view! {
         // A paragraph with muted color
         <p style=format!(
@@ -252,13 +567,174 @@ fn CounterDisplay(
                 theme::BRIGHT_RED
             )>
                 // Call the closure to get the current count
-                // Wrapping it in `move || count()` creates a closure that Leptos can track
-                {move || count()}
+                // Wrapping it in a `move ||` closure lets Leptos track it
+                {shown}
             </span>
+            {move || unit.get().map(|unit| format!(" {unit}"))}
         </p>
     }
 }
 
+/// A `CounterDisplay`'s rolling number - all `Copy` handles, like
+/// `TimerLoop`, so `frame` can schedule itself from inside a timeout
+#[derive(Clone, Copy)]
+struct RollLoop {
+    tween: StoredValue<Option<Tween>>,
+    pending: StoredValue<Option<Timeout>, LocalStorage>,
+    rolling: RwSignal<Option<i32>>,
+}
+
+impl RollLoop {
+    /// The count went from `previous` to `value`: roll there
+    fn start(self, previous: i32, value: i32) {
+        if animation::prefers_reduced_motion() {
+            self.rolling.set(None);
+            self.pending.set_value(None);
+            return;
+        }
+        // A change in the middle of rolling continues from what's shown
+        let from = self.rolling.get_untracked().unwrap_or(previous);
+        self.tween
+            .set_value(Some(Tween::new(from, value, timer::now())));
+        self.frame();
+    }
+
+    /// Show the tween's current number, then wait for the next frame
+    fn frame(self) {
+        // `None`: the component is gone (or the tween is over)
+        let Some(tween) = self.tween.try_get_value().flatten() else {
+            return;
+        };
+        let now = timer::now();
+        if tween.is_done(now) {
+            self.tween.set_value(None);
+            self.rolling.set(None);
+            return;
+        }
+        self.rolling.set(Some(tween.value_at(now)));
+        self.pending
+            .set_value(Timeout::new(Tween::FRAME, move || self.frame()));
+    }
+}
+
+/// UnitSelect - Picks the unit a measured counter is shown in
+///
+/// WHAT IT DOES:
+/// - Lists every unit `unit` converts to (ml and l for a volume, ...)
+/// - Calls `on_select` when another one is picked
+///
+/// Only how the value is SHOWN changes: the counter keeps counting in its
+/// own unit, so "+1" on a milliliter counter still adds 1 ml.
+#[component]
+pub fn UnitSelect(
+    /// The counter's own unit (selected at first)
+    unit: Unit,
+
+    /// Called with the picked unit
+    on_select: Box<dyn Fn(Unit) + 'static>,
+) -> impl IntoView {
+    view! {
+        <label style=format!("color: {}; font-size: 0.9em;", theme::TEXT_MUTED)>
+            "Show in "
+            <select
+                style=format!(
+                    "padding: 4px; background: {}; color: #fff; border: 1px solid {}; \
+                     border-radius: 6px;",
+                    theme::CARD_BG,
+                    theme::EVIL_RED
+                )
+                on:change=move |ev| {
+                    if let Ok(picked) = event_target_value(&ev).parse() {
+                        on_select(picked);
+                    }
+                }
+            >
+                {unit
+                    .compatible()
+                    .map(|option| {
+                        view! {
+                            <option value=option.symbol() selected=option == unit>
+                                {option.symbol()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+        </label>
+    }
+}
+
+/// GoalProgress - A progress bar towards the counter's goal
+///
+/// WHAT IT DOES:
+/// - Shows "42 / 100 (42%)" and a bar filled to the same percentage
+/// - Estimates how long is left at the recent pace (see `Pace` in src/goal.rs)
+///
+/// HOW THE PACE IS MEASURED:
+/// An Effect runs on every change of `count` and records (time, value) in a
+/// `Pace`. The projection is stored in a signal, so the text updates with it.
+/// Effects only run in the browser, so server-rendered HTML has no estimate.
+///
+/// ACCESSIBILITY: `role="progressbar"` with `aria-valuenow`/`aria-valuemax`
+/// lets screen readers announce the progress like a native progress bar.
+#[component]
+pub fn GoalProgress(
+    /// The counter's count
+    count: ReadSignal<i32>,
+
+    /// What the count should reach
+    goal: Goal,
+) -> impl IntoView {
+    let pace = StoredValue::new(Pace::default());
+    let (eta, set_eta) = signal(None::<f64>);
+    Effect::new(move |_| {
+        let value = count.get();
+        let seconds = timer::now() / 1000.0;
+        set_eta.set(
+            pace.try_update_value(|pace| {
+                pace.record(seconds, value);
+                pace.eta(goal, value)
+            })
+            .flatten(),
+        );
+    });
+
+    let percent = move || goal.percent(count.get());
+
+    view! {
+        <div style="margin: -12px auto 16px; max-width: 400px;">
+            <div
+                role="progressbar"
+                aria-valuemin="0"
+                aria-valuemax=goal.target.to_string()
+                aria-valuenow=move || count.get().to_string()
+                style=format!(
+                    "height: 14px; background: {}; border: 1px solid {}; \
+                     border-radius: 7px; overflow: hidden;",
+                    theme::DARK_GREY,
+                    theme::EVIL_RED
+                )
+            >
+                // The filled part; its width is the percentage
+                <div style=move || format!(
+                    "height: 100%; width: {}%; background: linear-gradient(90deg, {}, {}); \
+                     transition: width 0.3s;",
+                    percent(),
+                    theme::EVIL_RED,
+                    theme::BRIGHT_RED
+                ) />
+            </div>
+            <p style=format!("color: {}; font-size: 0.9em; margin: 6px 0 0;", theme::TEXT_MUTED)>
+                {move || format!("{} / {} ({}%)", count.get(), goal.target, percent())}
+                {move || {
+                    eta.get()
+                        .map(|seconds| format!(" · ≈ {} to go", goal::format_duration(seconds)))
+                }}
+            </p>
+        </div>
+    }
+}
+
 /// CounterMessage - Shows different messages based on the count value
 ///
 /// WHAT IT DOES:
@@ -267,7 +743,7 @@ fn CounterDisplay(
 ///   - "🔥 Count is HIGH! 75 is above 50!" when count > 50
 ///   - "❄️ Count is NEGATIVE! -5 is below zero!" when count < 0
 ///   - "😴 Count is ZERO! Reset complete!" when count == 0
-///   - "Count is normal (1-49)" otherwise
+///   - "Count is normal (1-50)" otherwise (up to `Band::HIGH_ABOVE`)
 ///
 /// KEY LEARNING: CONDITIONAL RENDERING WITH REACTIVITY
 /// ============================================================
@@ -328,8 +804,27 @@ fn CounterDisplay(
 /// When you have a signal (ReadSignal), you MUST access it inside a closure
 /// so Leptos can track the dependency. This is why we have `move || { ... }`.
 /// Without the closure, Leptos doesn't know "this component depends on that signal".
+///
+/// THE ALARM:
+/// `alarm` (optional) overrides the message: while it holds `Some(text)`, the
+/// text is shown as a loud red banner with `role="alert"`, so screen readers
+/// announce it too. The countdown uses it when it reaches its target.
+///
+/// THE CELEBRATION:
+/// With a `goal`, reaching it replaces the message with a gold "🎉 Goal
+/// reached!" banner (`role="status"`: announced, but politely).
 #[component]
-fn CounterMessage(count: ReadSignal<i32>) -> impl IntoView {
+pub fn CounterMessage(
+    count: ReadSignal<i32>,
+
+    /// An alarm to show instead of the normal message
+    #[prop(optional, into)]
+    alarm: MaybeProp<String>,
+
+    /// The counter's goal, celebrated once reached
+    #[prop(optional, into)]
+    goal: MaybeProp<i32>,
+) -> impl IntoView {
     view! {
         {
             // The outer braces {} in the view! macro let us write Rust code
@@ -346,27 +841,38 @@ fn CounterMessage(count: ReadSignal<i32>) -> impl IntoView {
 
                 // Use Rust if/else to COMPUTE the message text and color
                 // We're building Rust values here, NOT views yet
-                let (message, color) = if current > 50 {
-                    // HIGH: return a tuple of (message_text, color)
-                    (
-                        "🔥 Count is HIGH! ".to_string() + &current.to_string() + " is above 50!",
-                        theme::BRIGHT_RED,
-                    )
-                } else if current < 0 {
-                    // NEGATIVE: return a tuple of (message_text, color)
-                    (
-                        "❄️ Count is NEGATIVE! ".to_string() + &current.to_string() + " is below zero!",
-                        "#6bb6ff",
-                    )
-                } else if current == 0 {
-                    // ZERO: return a tuple of (message_text, color)
-                    (
-                        "😴 Count is ZERO! Reset complete!".to_string(),
-                        theme::TEXT_MUTED,
-                    )
+                // (an alarm beats every other message, then a reached goal)
+                let alarm = alarm.get();
+                let goal = goal.get().map(|target| Goal { target });
+                let celebrating = alarm.is_none() && goal.is_some_and(|goal| goal.is_reached(current));
+                let (message, color) = if let Some(alarm) = alarm.clone() {
+                    (alarm, "#fff")
+                } else if let Some(goal) = goal.filter(|_| celebrating) {
+                    (goal.message(), theme::CELEBRATION)
                 } else {
-                    // NORMAL: return a tuple of (message_text, color)
-                    ("Count is normal (1-49)".to_string(), theme::TEXT_MUTED)
+                    // Which band the count is in (see src/band.rs)
+                    match Band::of(current) {
+                        // HIGH: return a tuple of (message_text, color)
+                        Band::High => (
+                            format!("🔥 Count is HIGH! {current} is above {}!", Band::HIGH_ABOVE),
+                            theme::band(Band::High),
+                        ),
+                        // NEGATIVE: return a tuple of (message_text, color)
+                        Band::Negative => (
+                            "❄️ Count is NEGATIVE! ".to_string() + &current.to_string() + " is below zero!",
+                            theme::band(Band::Negative),
+                        ),
+                        // ZERO: return a tuple of (message_text, color)
+                        Band::Zero => (
+                            "😴 Count is ZERO! Reset complete!".to_string(),
+                            theme::band(Band::Zero),
+                        ),
+                        // NORMAL: return a tuple of (message_text, color)
+                        Band::Normal => (
+                            format!("Count is normal (1-{})", Band::HIGH_ABOVE),
+                            theme::band(Band::Normal),
+                        ),
+                    }
                 };
 
                 // Build the CSS style with the computed color
@@ -375,10 +881,32 @@ fn CounterMessage(count: ReadSignal<i32>) -> impl IntoView {
                     color
                 );
 
+                // The alarm gets a glowing red banner on top, and `role="alert"`;
+                // the celebration a gold frame (`None` leaves the attribute out)
+                let (style, role) = match alarm {
+                    None if celebrating => (
+                        format!(
+                            "{style} padding: 10px; border-radius: 8px; border: 2px solid {};",
+                            theme::CELEBRATION
+                        ),
+                        Some("status"),
+                    ),
+                    Some(_) => (
+                        format!(
+                            "{style} padding: 10px; border-radius: 8px; \
+                             background: {}; box-shadow: 0 0 20px {};",
+                            theme::EVIL_RED,
+                            theme::BRIGHT_RED
+                        ),
+                        Some("alert"),
+                    ),
+                    None => (style, None),
+                };
+
                 // NOW render the view with the computed values
                 // This is a SINGLE view structure, so no type conflicts
                 view! {
-                    <p style=style>
+                    <p style=style role=role>
                         // The message text will automatically update when count changes
                         // because this entire closure re-runs when count changes
                         {message}
@@ -407,10 +935,24 @@ fn CounterMessage(count: ReadSignal<i32>) -> impl IntoView {
 ///           The parent component decides what each button does.
 ///
 /// COMPOSITION IN ACTION:
-/// This component uses our `EvilButton` component 5 times.
+/// This component uses our `EvilButton` component 5 times (6 with "Undo").
 /// This shows how components can be composed (used inside other components).
+///
+/// `step` only changes the LABELS of the first two buttons ("-5" / "+5");
+/// what the buttons actually do is still decided by the parent's handlers.
+///
+/// `on_undo` is optional: the "Undo" button only appears when it is given.
+///
+/// Holding "-1" or "+1" repeats it (see `EvilButton`'s `repeat`). The other
+/// buttons don't: a few repeats of "*2" or "/2" reach the limits of an
+/// `i32` (or 0) in no time, "Reset" gains nothing from repeating, and
+/// holding "Undo" would quietly throw away a whole history.
 #[component]
-fn CounterButtons(
+pub fn CounterButtons(
+    /// How much the "+"/"-" buttons add/subtract (used for their labels)
+    #[prop(default = 1)]
+    step: i32,
+
     /// Callback when "-1" button is clicked
     on_decrement: Box<dyn Fn(leptos::ev::MouseEvent) + 'static>,
 
@@ -425,19 +967,474 @@ fn CounterButtons(
 
     /// Callback when "/2" button is clicked
     on_divide: Box<dyn Fn(leptos::ev::MouseEvent) + 'static>,
+
+    /// Callback when "Undo" button is clicked
+    #[prop(optional)]
+    on_undo: Option<Box<dyn Fn(leptos::ev::MouseEvent) + 'static>>,
 ) -> impl IntoView {
     view! {
         // Container for buttons with some spacing
-        <div style="margin: 20px 0;">
+        // flex-wrap: On a narrow phone the buttons flow onto several rows
+        <div style="margin: 20px 0; display: flex; flex-wrap: wrap; justify-content: center;">
             // Each EvilButton uses our reusable button component
             // We pass:
             // 1. label - what text to show on the button
             // 2. on_click - which handler to call when clicked
-            <EvilButton label="-1" on_click=on_decrement />
-            <EvilButton label="+1" on_click=on_increment />
+            // 3. repeat - hold the button to keep stepping
+            <EvilButton label=Operation::step_down(step).label() on_click=on_decrement repeat=true />
+            <EvilButton label=Operation::step(step).label() on_click=on_increment repeat=true />
             <EvilButton label="Reset" on_click=on_reset />
             <EvilButton label="*2" on_click=on_multiply />
             <EvilButton label="/2" on_click=on_divide />
+            // `Option<View>` renders nothing for `None`
+            {on_undo.map(|on_undo| view! { <EvilButton label="Undo" on_click=on_undo /> })}
+        </div>
+    }
+}
+
+/// ExpressionInput - Type `count * 3 + 7` and apply the result
+///
+/// WHAT IT DOES:
+/// - A text input for an expression (see src/expr.rs for the language)
+/// - While typing, shows the result ("= 27") or what's wrong with it
+/// - "Apply" (or Enter) sets the counter to the result with `Operation::Set`,
+///   so it goes through bounds, sync and undo exactly like a button
+///
+/// WHY EVALUATE WHILE TYPING?
+/// A `move ||` closure that reads both `input` and `count` re-runs whenever
+/// either changes, so the preview is always for the CURRENT count - even
+/// when a timer is changing it.
+#[component]
+pub fn ExpressionInput(
+    /// The counter's count (the `count` in expressions)
+    count: ReadSignal<i32>,
+
+    /// Called with `Set(result)` when a valid expression is applied
+    on_apply: Box<dyn Fn(Operation) + 'static>,
+) -> impl IntoView {
+    let (input, set_input) = signal(String::new());
+    let result = move || {
+        let input = input.get();
+        (!input.trim().is_empty()).then(|| expr::evaluate(&input, count.get()))
+    };
+
+    let on_apply = StoredValue::new_local(on_apply);
+    let apply = move || {
+        if let Some(Ok(value)) = untrack(result) {
+            on_apply.with_value(|on_apply| on_apply(Operation::Set(value)));
+        }
+    };
+
+    let field_style = format!("{} font-family: monospace; width: 220px;", field_style());
+
+    view! {
+        <div style="margin: 0 0 20px;">
+            <input
+                type="text"
+                placeholder="count * 3 + 7"
+                aria-label="Expression"
+                style=field_style
+                prop:value=move || input.get()
+                on:input=move |ev| set_input.set(event_target_value(&ev))
+                on:keydown=move |ev| {
+                    if ev.key() == "Enter" {
+                        apply();
+                    }
+                }
+            />
+            <EvilButton label="Apply" on_click=Box::new(move |_| apply()) />
+            <p style=move || format!(
+                "color: {}; font-size: 0.9em; margin: 4px 0 0; min-height: 1.2em; \
+                 font-family: monospace;",
+                match result() {
+                    Some(Err(_)) => theme::BRIGHT_RED,
+                    _ => theme::TEXT_MUTED,
+                }
+            )>
+                {move || match result() {
+                    Some(Ok(value)) => format!("= {value}"),
+                    Some(Err(err)) => format!("⚠️ {err}"),
+                    None => String::new(),
+                }}
+            </p>
+        </div>
+    }
+}
+
+/// MilestoneList - What happened to a counter, newest first
+///
+/// Shows the milestones remembered in the counter's history (see
+/// `Milestone` in src/history.rs) - finished countdowns and reached goals -
+/// each with the count at the time. Renders nothing until the first one.
+#[component]
+pub fn MilestoneList(
+    /// The milestones, oldest first
+    #[prop(into)]
+    milestones: Signal<Vec<Milestone>>,
+) -> impl IntoView {
+    let list_style = format!(
+        "list-style: none; padding: 0; margin: 10px 0 0; color: {}; font-size: 0.9em;",
+        theme::TEXT_MUTED
+    );
+
+    move || {
+        let milestones = milestones.get();
+        (!milestones.is_empty()).then(|| {
+            view! {
+                <ul aria-label="Milestones" style=list_style.clone()>
+                    {milestones
+                        .into_iter()
+                        .rev()
+                        .map(|milestone| {
+                            view! { <li>{format!("{} (at {})", milestone.message, milestone.value)}</li> }
+                        })
+                        .collect_view()}
+                </ul>
+            }
+        })
+    }
+}
+
+/// The style of the inputs and selects in the counter's control panels
+/// (`ExpressionInput`, `TimerControls`, `CountdownControls`)
+fn field_style() -> String {
+    format!(
+        "padding: 8px; margin: 5px; background: {}; color: #fff; \
+         border: 1px solid {}; border-radius: 6px;",
+        theme::CARD_BG,
+        theme::EVIL_RED
+    )
+}
+
+/// TimerControls - Applies an operation automatically, every N milliseconds
+///
+/// WHAT IT DOES:
+/// - Lets the user pick WHAT to do ("+1", "*2", ...) and HOW OFTEN (in ms)
+/// - "Start" / "Pause" / "Stop" drive a drift-corrected `Ticker` (see src/timer.rs)
+/// - Every tick calls `on_tick` with the chosen operation - exactly like a click
+///
+/// HOW THE LOOP WORKS:
+/// Each tick schedules the next one with a single `setTimeout` (see
+/// `TimerLoop` below). Pausing or stopping simply drops the pending timeout.
+///
+/// CLEANUP: `on_cleanup` runs when the component is removed from the page
+/// (e.g. the counter is unmounted). It drops the pending timeout, so no timer
+/// keeps firing into a counter that no longer exists.
+#[component]
+pub fn TimerControls(
+    /// Step size offered as "+n" / "-n"
+    #[prop(default = 1)]
+    step: i32,
+
+    /// Called with the chosen operation on every tick
+    on_tick: Box<dyn Fn(Operation) + 'static>,
+) -> impl IntoView {
+    // The operations the timer can repeat, in the order of the <select>
+    let choices = [
+        Operation::step(step),
+        Operation::step_down(step),
+        Operation::Double,
+        Operation::Halve,
+    ];
+    let (operation, set_operation) = signal(choices[0]);
+    let (interval, set_interval) = signal(1000u32);
+    let (state, set_state) = signal(TimerState::Stopped);
+
+    let timer = TimerLoop {
+        ticker: StoredValue::new(Ticker::new(1000.0)),
+        pending: StoredValue::new_local(None),
+        on_tick: StoredValue::new_local(on_tick),
+        operation,
+    };
+
+    on_cleanup(move || {
+        timer.pending.try_set_value(None);
+    });
+
+    let handle_start = move |_: leptos::ev::MouseEvent| {
+        let now = timer::now();
+        let started = timer.ticker.try_update_value(|ticker| {
+            match ticker.state() {
+                TimerState::Running => return false,
+                // A fresh start picks up the interval currently in the input
+                TimerState::Stopped => *ticker = Ticker::new(interval.get_untracked() as f64),
+                TimerState::Paused => {}
+            }
+            ticker.start(now);
+            true
+        });
+        if started == Some(true) {
+            set_state.set(TimerState::Running);
+            timer.schedule();
+        }
+    };
+
+    let handle_pause = move |_: leptos::ev::MouseEvent| {
+        timer
+            .ticker
+            .update_value(|ticker| ticker.pause(timer::now()));
+        timer.pending.set_value(None);
+        if state.get_untracked() == TimerState::Running {
+            set_state.set(TimerState::Paused);
+        }
+    };
+
+    let handle_stop = move |_: leptos::ev::MouseEvent| {
+        timer.ticker.update_value(Ticker::stop);
+        timer.pending.set_value(None);
+        set_state.set(TimerState::Stopped);
+    };
+
+    let field_style = field_style();
+
+    view! {
+        <div style="margin: 20px 0 0;">
+            <label style=format!("color: {};", theme::TEXT_MUTED)>
+                "Every "
+                <input
+                    type="number"
+                    min="10"
+                    step="100"
+                    style=field_style.clone()
+                    prop:value=move || interval.get().to_string()
+                    // The interval can only change while stopped
+                    disabled=move || state.get() != TimerState::Stopped
+                    on:change=move |ev| {
+                        if let Ok(ms) = event_target_value(&ev).parse::<u32>() {
+                            set_interval.set(ms.max(Ticker::MIN_INTERVAL as u32));
+                        }
+                    }
+                />
+                " ms apply "
+                <select
+                    style=field_style
+                    on:change=move |ev| {
+                        if let Some(op) = event_target_value(&ev)
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|i| choices.get(i))
+                        {
+                            set_operation.set(*op);
+                        }
+                    }
+                >
+                    {choices
+                        .iter()
+                        .enumerate()
+                        .map(|(i, op)| view! { <option value=i.to_string()>{op.label()}</option> })
+                        .collect_view()}
+                </select>
+            </label>
+            <div>
+                <EvilButton label="Start" on_click=Box::new(handle_start) />
+                <EvilButton label="Pause" on_click=Box::new(handle_pause) />
+                <EvilButton label="Stop" on_click=Box::new(handle_stop) />
+            </div>
+            <p style=format!("color: {}; font-size: 0.9em;", theme::TEXT_MUTED)>
+                {move || match state.get() {
+                    TimerState::Running => "⏱️ Timer running",
+                    TimerState::Paused => "⏸️ Timer paused",
+                    TimerState::Stopped => "Timer stopped",
+                }}
+            </p>
+        </div>
+    }
+}
+
+/// Everything one tick of `TimerControls` needs - all `Copy` handles, so
+/// `schedule` and `tick` can call each other from inside timeouts
+#[derive(Clone, Copy)]
+struct TimerLoop {
+    ticker: StoredValue<Ticker>,
+    pending: StoredValue<Option<Timeout>, LocalStorage>,
+    on_tick: StoredValue<Box<dyn Fn(Operation)>, LocalStorage>,
+    operation: ReadSignal<Operation>,
+}
+
+impl TimerLoop {
+    /// Set a timeout for the next tick (if the ticker is running)
+    fn schedule(self) {
+        let delay = self
+            .ticker
+            .try_with_value(|ticker| {
+                (ticker.state() == TimerState::Running)
+                    .then(|| ticker.delay_until_next(timer::now()))
+            })
+            .flatten();
+        if let Some(delay) = delay {
+            self.pending
+                .try_set_value(Timeout::new(delay, move || self.tick()));
+        }
+    }
+
+    /// Apply every tick that became due, then schedule the next one
+    fn tick(self) {
+        // `None`: the component is gone
+        let Some(due) = self
+            .ticker
+            .try_update_value(|ticker| ticker.poll(timer::now()))
+        else {
+            return;
+        };
+        let op = self.operation.get_untracked();
+        self.on_tick.with_value(|on_tick| {
+            for _ in 0..due {
+                // A tick may stop the ticker (the countdown does when it's done)
+                if self.ticker.with_value(Ticker::state) != TimerState::Running {
+                    break;
+                }
+                on_tick(op);
+            }
+        });
+        self.schedule();
+    }
+}
+
+/// CountdownControls - Counts down to zero (or up to a goal), then sounds an alarm
+///
+/// WHAT IT DOES:
+/// - The user picks a target and a direction: "from 10 down to 0" or
+///   "from 0 up to 10" (see `Countdown` in src/timer.rs)
+/// - "Start countdown" jumps the counter to the starting value, then ticks
+///   once per second through the same `TimerLoop` as `TimerControls`
+/// - When the target is reached the ticker stops, `on_finish` is called and,
+///   if "Sound" is ticked, the alarm beeps (see src/sound.rs)
+///
+/// WHY `count`? Ticks go through the counter's bounds, so we check the
+/// counter's real value after each tick instead of counting ticks ourselves.
+#[component]
+pub fn CountdownControls(
+    /// The counter's count, checked after every tick
+    count: ReadSignal<i32>,
+
+    /// Called with `Set(start)` when starting, then with every tick's operation
+    on_tick: Box<dyn Fn(Operation) + 'static>,
+
+    /// Called once the target is reached
+    on_finish: Box<dyn Fn(Countdown) + 'static>,
+) -> impl IntoView {
+    let (target, set_target) = signal(10);
+    let (counting_up, set_counting_up) = signal(false);
+    let (sound, set_sound) = signal(true);
+    let (state, set_state) = signal(TimerState::Stopped);
+    let (operation, set_operation) = signal(Operation::Decrement);
+    // The page-wide volume and mute (see src/settings.rs)
+    let settings = settings::use_settings();
+
+    let ticker = StoredValue::new(Ticker::new(1000.0));
+    let pending = StoredValue::new_local(None::<Timeout>);
+    let running = StoredValue::new(None::<Countdown>);
+    let on_tick = StoredValue::new_local(on_tick);
+    let on_finish = StoredValue::new_local(on_finish);
+
+    on_cleanup(move || {
+        pending.try_set_value(None);
+    });
+
+    let stop = move || {
+        ticker.update_value(Ticker::stop);
+        pending.set_value(None);
+        running.set_value(None);
+        set_state.set(TimerState::Stopped);
+    };
+
+    // Stop and sound the alarm
+    let finish = move |countdown: Countdown| {
+        stop();
+        on_finish.with_value(|on_finish| on_finish(countdown));
+        if sound.get_untracked() {
+            sound::play(
+                Sound::Alarm,
+                settings.with_untracked(Settings::alarm_volume),
+            );
+        }
+    };
+
+    // Every tick: apply the operation, then check whether we're there.
+    // A count that didn't move is stuck at the counter's min/max and would
+    // never get there, so the countdown just stops (without the alarm).
+    let timer = TimerLoop {
+        ticker,
+        pending,
+        on_tick: StoredValue::new_local(Box::new(move |op| {
+            let before = count.get_untracked();
+            on_tick.with_value(|on_tick| on_tick(op));
+            let after = count.get_untracked();
+            match running.get_value() {
+                Some(countdown) if countdown.is_finished(after) => finish(countdown),
+                Some(_) if after == before => stop(),
+                _ => {}
+            }
+        })),
+        operation,
+    };
+
+    let handle_start = move |_: leptos::ev::MouseEvent| {
+        if state.get_untracked() != TimerState::Stopped {
+            return;
+        }
+        let target = target.get_untracked();
+        let countdown = if counting_up.get_untracked() {
+            Countdown::Up { to: target }
+        } else {
+            Countdown::Down { from: target }
+        };
+        on_tick.with_value(|on_tick| on_tick(Operation::Set(countdown.start_value())));
+        if countdown.is_finished(count.get_untracked()) {
+            finish(countdown);
+            return;
+        }
+        running.set_value(Some(countdown));
+        set_operation.set(countdown.operation());
+        ticker.update_value(|ticker| {
+            *ticker = Ticker::new(1000.0);
+            ticker.start(timer::now());
+        });
+        set_state.set(TimerState::Running);
+        timer.schedule();
+    };
+
+    let handle_stop = move |_: leptos::ev::MouseEvent| stop();
+
+    let field_style = field_style();
+
+    view! {
+        <div style="margin: 20px 0 0;">
+            <label style=format!("color: {};", theme::TEXT_MUTED)>
+                "Count "
+                <select
+                    style=field_style.clone()
+                    disabled=move || state.get() != TimerState::Stopped
+                    on:change=move |ev| set_counting_up.set(event_target_value(&ev) == "up")
+                >
+                    <option value="down">"down from"</option>
+                    <option value="up">"up to"</option>
+                </select>
+                <input
+                    type="number"
+                    min="1"
+                    style=field_style
+                    prop:value=move || target.get().to_string()
+                    disabled=move || state.get() != TimerState::Stopped
+                    on:change=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse::<i32>() {
+                            set_target.set(value.max(1));
+                        }
+                    }
+                />
+            </label>
+            <label style=format!("color: {}; margin-left: 10px;", theme::TEXT_MUTED)>
+                <input
+                    type="checkbox"
+                    prop:checked=move || sound.get()
+                    on:change=move |ev| set_sound.set(event_target_checked(&ev))
+                />
+                " Sound"
+            </label>
+            <div>
+                <EvilButton label="Start countdown" on_click=Box::new(handle_start) />
+                <EvilButton label="Stop countdown" on_click=Box::new(handle_stop) />
+            </div>
         </div>
     }
 }
@@ -517,21 +1514,343 @@ fn CounterButtons(
 /// - CounterButtons gets the event handlers
 ///
 /// This separation of concerns makes each component easy to understand and test.
+///
+/// ON A PHONE:
+/// Swiping up on the count adds `step`, swiping down subtracts it - through
+/// `apply`, exactly like the buttons (see `SwipeTracker` in src/gesture.rs).
+/// `touch-action: pan-x` tells the browser that vertical drags there are
+/// ours, so it doesn't scroll the page instead.
+///
+/// OPTIONAL PROPS:
+/// ```rust
+/// <Counter />                                   // starts at 0, steps of 1
+/// <Counter initial=10 step=5 min=0 max=100 />   // everything configured
+/// ```
+/// - `initial`, `step`: where the count starts and what "+"/"-" add
+/// - `min`, `max`: the count is clamped to stay inside these bounds
+/// - `on_change`: a `Callback` called with `(previous, value)` after every change
+///   (the `<evil-counter>` custom element uses it to fire DOM events)
+/// - `timer`: show `TimerControls`, which click a button automatically
+/// - `countdown`: show `CountdownControls`; reaching the target raises an
+///   alarm in `CounterMessage` and is listed in a `MilestoneList`
+/// - `exact`: keep the count as an exact fraction, so "/2" on 3 gives 1.5
+///   (shown with `precision` decimals). Exact counters are saved on the
+///   server but not shared live (see `ExactLink` in src/sync.rs).
+/// - `unit`: what the count measures (see src/units.rs); shown after the
+///   count, with a `UnitSelect` to show it converted (1500 ml as 1.5 l)
+/// - `expression`: show `ExpressionInput` under the buttons
+/// - `goal`: show `GoalProgress` under the count, and celebrate in
+///   `CounterMessage` once the goal is reached (also listed in the `MilestoneList`)
+/// - `local`: never share the count through the server, even when the page
+///   is connected (the counters of a `CounterBoard` are local)
+/// - `name`: used in desktop notifications (see src/notify.rs), which the
+///   user turns on per band in `NotificationControls`
 #[component]
-fn Counter() -> impl IntoView {
+pub fn Counter(
+    /// The starting count
+    #[prop(default = 0)]
+    initial: i32,
+
+    /// How much the "+"/"-" buttons add/subtract
+    #[prop(default = 1)]
+    step: i32,
+
+    /// Lowest allowed count (`MaybeProp` accepts both `min=0` and an `Option`)
+    #[prop(optional, into)]
+    min: MaybeProp<i32>,
+
+    /// Highest allowed count
+    #[prop(optional, into)]
+    max: MaybeProp<i32>,
+
+    /// Called with `(previous, value)` whenever the count changes
+    #[prop(optional)]
+    on_change: Option<Callback<(i32, i32)>>,
+
+    /// Lets outside code read the count and apply operations (see src/control.rs)
+    #[prop(optional)]
+    control: Option<CounterControl>,
+
+    /// Keep an exact fraction instead of a whole number (see src/rational.rs);
+    /// a `Signal` switches modes, but a synced counter keeps the mode it
+    /// connected with
+    #[prop(optional, into)]
+    exact: Signal<bool>,
+
+    /// Decimals shown for exact values
+    #[prop(into, default = Signal::stored(2))]
+    precision: Signal<usize>,
+
+    /// What the count measures
+    #[prop(optional, into)]
+    unit: MaybeProp<Unit>,
+
+    /// Show the expression input
+    #[prop(optional)]
+    expression: bool,
+
+    /// Show the auto-increment timer
+    #[prop(optional)]
+    timer: bool,
+
+    /// Show the countdown (with its alarm); a `Signal` turns it on and off
+    #[prop(optional, into)]
+    countdown: Signal<bool>,
+
+    /// A target to track progress towards (a `Signal` can change it)
+    #[prop(optional, into)]
+    goal: MaybeProp<i32>,
+
+    /// Keep the count in this page (no server sync)
+    #[prop(optional)]
+    local: bool,
+
+    /// What notifications call this counter (plain "Count" without one)
+    #[prop(optional, into)]
+    name: MaybeProp<String>,
+) -> impl IntoView {
+    let bounds = move || Bounds {
+        min: min.get_untracked(),
+        max: max.get_untracked(),
+    };
+
     // ========================================================================
     // STATE MANAGEMENT
     // ========================================================================
     // Create a reactive signal for the count
     // - `count`: Read the current value with count.get()
     // - `set_count`: Update the value with set_count.set() or set_count.update()
-    // - Initial value: 0
-    let (count, set_count) = signal(0);
+    // - Initial value: `initial`, moved inside the bounds
+    // The signal lives in the `control`, so outside code can read it too
+    let control = control.unwrap_or_default();
+    let (count, set_count) = control.signal(bounds().clamp(initial));
+
+    // Exact mode: `count` is only the whole part (what bands, goals and
+    // outside code see) of this exact value
+    let (exact_value, set_exact_value) = signal(Rational::from(count.get_untracked()));
+    // The exact value, or the count when the count moved on without it
+    // (changed while exact mode was off)
+    let current_exact = move || {
+        let (value, count) = (exact_value.get(), count.get());
+        if value.trunc() == count {
+            value
+        } else {
+            count.into()
+        }
+    };
+
+    // Measured counters can be shown in another unit of the same kind
+    let unit = unit.get_untracked();
+    let (shown_unit, set_shown_unit) = signal(unit);
+
+    // Whether sound effects play, and how loud (see src/settings.rs)
+    let settings = settings::use_settings();
+
+    // Tell the parent about every change.
+    // An Effect receives the value it returned last time (`None` on the
+    // first run), which is exactly the "previous" count we need.
+    if let Some(on_change) = on_change {
+        Effect::new(move |previous: Option<i32>| {
+            let value = count.get();
+            if let Some(previous) = previous.filter(|previous| *previous != value) {
+                on_change.run((previous, value));
+            }
+            value
+        });
+    }
+
+    // Moving into another band (see src/band.rs) plays its tune (if sound
+    // effects are on) and shakes the card, flashing the new band's color -
+    // unless the user asked for reduced motion. In a background tab, it can
+    // also raise a notification (if the user turned that band's on).
+    let card = NodeRef::<leptos::html::Div>::new();
+    Effect::new(move |previous: Option<Band>| {
+        let value = count.get();
+        let band = Band::of(value);
+        if previous.is_some_and(|previous| previous != band) {
+            sound::play(
+                Sound::Band(band),
+                settings.with_untracked(Settings::effects_volume),
+            );
+            if settings.with_untracked(|settings| settings.notifies(band)) && notify::page_hidden()
+            {
+                let name = name.get_untracked();
+                let (title, body) = notify::message(band, value, name.as_deref());
+                // One notification per counter and band: a new one replaces it
+                let tag = format!("webtest-{}-{band}", name.as_deref().unwrap_or("count"));
+                notify::show(&title, &body, &tag);
+            }
+            if !animation::prefers_reduced_motion() {
+                if let Some(card) = card.get_untracked() {
+                    animation::shake(&card, theme::band(band));
+                }
+            }
+        }
+        band
+    });
+
+    // ========================================================================
+    // SERVER SYNC (optional)
+    // ========================================================================
+    // When the page has a `<meta name="webtest-api">` tag (see src/api.rs),
+    // the counter is shared live with every other browser through the
+    // `server` binary (see src/sync.rs).
+    //
+    // `StoredValue` holds a plain (non-reactive) value that closures can share.
+    // It starts as `None` and becomes `Some(link)` once we're connected.
+    // `new_local` because the link uses browser-only types that can't be
+    // sent between threads.
+    let link = StoredValue::new_local(None::<sync::CounterLink>);
+    // Exact counters only load and save their exact value
+    let exact_link = StoredValue::new_local(None::<sync::ExactLink>);
+
+    // Effects only run in the browser (never during server rendering), which
+    // is exactly where we can read <meta> tags and open connections.
+    Effect::new(move |_| {
+        let Some(config) = api::ApiConfig::from_page().filter(|_| !local) else {
+            return;
+        };
+        if exact.get_untracked() {
+            leptos::task::spawn_local(async move {
+                match sync::ExactLink::connect(&config).await {
+                    Ok((connected, value)) => {
+                        let value = bounds().clamp_exact(value);
+                        set_exact_value.set(value);
+                        set_count.set(value.trunc());
+                        exact_link.set_value(Some(connected));
+                    }
+                    Err(err) => leptos::logging::warn!("counter API unavailable: {err}"),
+                }
+            });
+            return;
+        }
+        leptos::task::spawn_local(async move {
+            // Every value the server (or our own optimistic update) produces
+            // is written into the count signal
+            match sync::CounterLink::connect(&config, move |value| set_count.set(value)).await {
+                Ok(connected) => link.set_value(Some(connected)),
+                Err(err) => leptos::logging::warn!("counter API unavailable: {err}"),
+            }
+        });
+    });
 
     // ========================================================================
     // EVENT HANDLERS
     // ========================================================================
-    // Each handler is a closure that captures `set_count` and updates the count
+    // Every button performs one `Operation` (see src/operation.rs). The new
+    // count is clamped to the bounds either way.
+    // Connected: the link updates the count right away and tells the server.
+    //            An operation that would leave the bounds is sent as a
+    //            `Set` to the clamped value, so everyone sees the same count.
+    // Not connected: we simply update the count ourselves.
+    // Returns the new count.
+    let perform = move |op: Operation| {
+        let (bounds, value) = (bounds(), count.get_untracked());
+        let next = bounds.apply(op, value);
+        let op = bounds.operation(op, value);
+        let sent = link.with_value(|link| link.as_ref().map(|link| link.apply(op)));
+        if sent.is_none() {
+            set_count.set(next);
+        }
+        next
+    };
+
+    // Exact mode never goes through the link: jump to the exact value (kept
+    // inside the bounds), let the count follow with its whole part, and save
+    // it when connected. `None` when the fraction grew too big to represent.
+    let set_exact = move |value: Rational| {
+        set_exact_value.set(value);
+        set_count.set(value.trunc());
+        exact_link.with_value(|link| {
+            if let Some(link) = link {
+                link.save(value);
+            }
+        });
+    };
+    let perform_exact = move |op: Operation| {
+        let next = bounds().clamp_exact(op.apply_exact(untrack(current_exact))?);
+        set_exact(next);
+        Some(next)
+    };
+
+    // Every change is remembered (see src/history.rs) so "Undo" can put the
+    // previous count back exactly - even after "Reset" or "/2". Values are
+    // remembered as `Rational`s so exact mode gets its fractions back too.
+    let history = StoredValue::new(History::<Rational>::new());
+
+    // The countdown's alarm text; any new change silences it
+    let (alarm, set_alarm) = signal(None::<String>);
+
+    let apply = move |op: Operation| {
+        if alarm.get_untracked().is_some() {
+            set_alarm.set(None);
+        }
+        let (before, after) = if exact.get_untracked() {
+            (untrack(current_exact), perform_exact(op))
+        } else {
+            let before = count.get_untracked();
+            (before.into(), Some(perform(op).into()))
+        };
+        if let Some(after) = after {
+            history.update_value(|history| history.record(op, before, after));
+            sound::play(
+                Sound::Operation(op),
+                settings.with_untracked(Settings::effects_volume),
+            );
+        }
+    };
+
+    // Undo jumps back with `Operation::Set`, so it syncs like any other click
+    // (and isn't recorded itself)
+    let undo = move || {
+        let Some(entry) = history.try_update_value(History::undo).flatten() else {
+            return;
+        };
+        if exact.get_untracked() {
+            set_exact(entry.before);
+        } else {
+            perform(Operation::Set(entry.before.trunc()));
+        }
+    };
+    control.attach(apply, undo);
+
+    // Milestones are remembered in the history too; the signal holds a copy
+    // for `MilestoneList`, since the history itself isn't reactive
+    let (milestones, set_milestones) = signal(Vec::<Milestone>::new());
+    let mark = move |milestone: Milestone| {
+        history.update_value(|history| history.mark(milestone));
+        set_milestones.set(history.with_value(|history| history.milestones().cloned().collect()));
+    };
+
+    // A finished countdown is a milestone, and rings the alarm
+    let finish_countdown = move |countdown: Countdown| {
+        let message = countdown.message();
+        mark(Milestone {
+            message: message.clone(),
+            value: count.get_untracked(),
+        });
+        set_alarm.set(Some(message));
+    };
+
+    // Reaching the goal is a milestone too. The Effect remembers the goal
+    // and whether it was reached on its last run, so only the crossing is
+    // recorded - not a new goal the count is already past.
+    let goal = Memo::new(move |_| goal.get().map(|target| Goal { target }));
+    Effect::new(move |previous: Option<(Option<Goal>, bool)>| {
+        let (current, value) = (goal.get(), count.get());
+        let reached = current.is_some_and(|goal| goal.is_reached(value));
+        if let Some(goal) = current.filter(|_| reached) {
+            if previous == Some((current, false)) {
+                mark(Milestone {
+                    message: goal.message(),
+                    value,
+                });
+            }
+        }
+        (current, reached)
+    });
+
+    // Each handler is a closure that ignores the MouseEvent and applies one operation
     //
     // The |_: leptos::ev::MouseEvent| syntax means:
     // - | | - start of closure
@@ -539,20 +1858,43 @@ fn Counter() -> impl IntoView {
     // - : leptos::ev::MouseEvent - the parameter TYPE
     // - | - end of closure parameters
 
-    // Decrement: subtract 1 from count
-    let handle_decrement = move |_: leptos::ev::MouseEvent| set_count.update(|c| *c -= 1);
+    // Decrement: subtract `step` from count
+    let handle_decrement = move |_: leptos::ev::MouseEvent| apply(Operation::step_down(step));
 
-    // Increment: add 1 to count
-    let handle_increment = move |_: leptos::ev::MouseEvent| set_count.update(|c| *c += 1);
+    // Increment: add `step` to count
+    let handle_increment = move |_: leptos::ev::MouseEvent| apply(Operation::step(step));
 
     // Reset: set count to 0
-    let handle_reset = move |_: leptos::ev::MouseEvent| set_count.set(0);
+    let handle_reset = move |_: leptos::ev::MouseEvent| apply(Operation::Reset);
 
     // Multiply: double the count (multiply by 2)
-    let handle_multiply = move |_: leptos::ev::MouseEvent| set_count.update(|c| *c *= 2);
+    let handle_multiply = move |_: leptos::ev::MouseEvent| apply(Operation::Double);
 
     // Divide: halve the count (integer division by 2)
-    let handle_divide = move |_: leptos::ev::MouseEvent| set_count.update(|c| *c /= 2);
+    let handle_divide = move |_: leptos::ev::MouseEvent| apply(Operation::Halve);
+
+    // Undo: go back to the count before the last change
+    let handle_undo = move |_: leptos::ev::MouseEvent| undo();
+
+    // Swipe up/down on the count: +step/-step
+    let swipe = StoredValue::new(SwipeTracker::default());
+    let on_swipe_start = move |ev: leptos::ev::PointerEvent| {
+        if ev.is_primary() {
+            let (x, y) = (f64::from(ev.client_x()), f64::from(ev.client_y()));
+            swipe.update_value(|swipe| swipe.start(x, y, timer::now()));
+        }
+    };
+    let on_swipe_end = move |ev: leptos::ev::PointerEvent| {
+        let (x, y) = (f64::from(ev.client_x()), f64::from(ev.client_y()));
+        let op = match swipe.try_update_value(|swipe| swipe.end(x, y, timer::now())) {
+            Some(Some(Swipe::Up)) => Operation::step(step),
+            Some(Some(Swipe::Down)) => Operation::step_down(step),
+            _ => return,
+        };
+        apply(op);
+        gesture::vibrate(15);
+    };
+    let on_swipe_cancel = move |_| swipe.update_value(SwipeTracker::cancel);
 
     // ========================================================================
     // STYLING
@@ -564,11 +1906,12 @@ fn Counter() -> impl IntoView {
         // text-align: center: Center-align text
         // background: Card background color (from theme)
         // border-radius: Rounded corners
-        // max-width: Don't let it get too wide on large screens
+        // width/max-width: Fill a phone screen, but don't get too wide on large screens
         // border: A red border to define the edge
         // box-shadow: A subtle shadow for depth
-        "padding: 32px; text-align: center; background: {}; border-radius: 12px; \
-         max-width: 600px; border: 2px solid {}; box-shadow: 0 10px 40px rgba(139,0,0,0.4);",
+        "padding: clamp(16px, 5vw, 32px); text-align: center; background: {}; border-radius: 12px; \
+         width: 100%; max-width: 600px; box-sizing: border-box; \
+         border: 2px solid {}; box-shadow: 0 10px 40px rgba(139,0,0,0.4);",
         theme::CARD_BG,
         theme::EVIL_RED
     );
@@ -576,32 +1919,927 @@ fn Counter() -> impl IntoView {
     // ========================================================================
     // RENDERING
     // ========================================================================
+    // A closure, so a host test's `TestProbe` can render this same counter
+    // again after clicking it (see src/probe.rs)
+    let render = move || {
+        view! {
+            // The main container for the counter UI
+            <div style=container_style.clone() node_ref=card>
+                // PART 1: Display the current count
+                // Pass a closure so it updates reactively when count changes
+                // (exact mode also passes the exact value, as a `Signal`; so does
+                // a measured counter shown in another unit, since 1500 ml is 1.5 l)
+                // (wrapped in the area that recognises swipes)
+                <div
+                    style="touch-action: pan-x; user-select: none; -webkit-user-select: none;"
+                    on:pointerdown=on_swipe_start
+                    on:pointerup=on_swipe_end
+                    on:pointercancel=on_swipe_cancel
+                >
+                    <CounterDisplay
+                        count=move || count.get()
+                        exact=Signal::derive(move || {
+                            let value = if exact.get() { current_exact() } else { count.get().into() };
+                            match (unit, shown_unit.get()) {
+                                (Some(unit), Some(shown)) if shown != unit => {
+                                    unit.convert(value, shown).ok().flatten()
+                                }
+                                _ => exact.get().then_some(value),
+                            }
+                        })
+                        precision=precision
+                        unit=shown_unit
+                    />
+                </div>
+                {unit.map(|unit| {
+                    view! {
+                        <UnitSelect unit=unit on_select=Box::new(move |picked| set_shown_unit.set(Some(picked))) />
+                    }
+                })}
+
+                // PART 1b (optional): progress towards the goal
+                {move || goal.get().map(|goal| view! { <GoalProgress count=count goal=goal /> })}
+
+                // PART 2: Show a message based on the count value
+                // Pass the signal directly (not a closure)
+                // CounterMessage will wrap it in a closure itself
+                <CounterMessage
+                    count=count
+                    alarm=alarm
+                    goal=Signal::derive(move || goal.get().map(|goal| goal.target))
+                />
+
+                // PART 2b: finished countdowns and reached goals, newest first
+                {move || {
+                    (countdown.get() || goal.with(Option::is_some))
+                        .then(|| view! { <MilestoneList milestones=milestones /> })
+                }}
+
+                // PART 3: Render the action buttons
+                // Pass each handler as a Box (pointer) to the function
+                // When a button is clicked, its handler is called, which updates set_count
+                <CounterButtons
+                    step=step
+                    on_decrement=Box::new(handle_decrement)
+                    on_increment=Box::new(handle_increment)
+                    on_reset=Box::new(handle_reset)
+                    on_multiply=Box::new(handle_multiply)
+                    on_divide=Box::new(handle_divide)
+                    on_undo=Box::new(handle_undo)
+                />
+
+                // PART 3b (optional): type an expression instead of clicking
+                {expression.then(|| view! { <ExpressionInput count=count on_apply=Box::new(apply) /> })}
+
+                // PART 4 (optional): the timer, which "clicks" for us
+                {timer.then(|| view! { <TimerControls step=step on_tick=Box::new(apply) /> })}
+
+                // PART 5 (optional): the countdown, which rings when it's done
+                {move || countdown.get().then(|| view! {
+                    <CountdownControls
+                        count=count
+                        on_tick=Box::new(apply)
+                        on_finish=Box::new(finish_countdown)
+                    />
+                })}
+            </div>
+        }
+    };
+    #[cfg(any(test, feature = "test-probe"))]
+    if let Some(probe) = TestProbe::current() {
+        let render = render.clone();
+        probe.register_view(move || render().into_any());
+    }
+    render()
+}
+
+/// FormulaDisplay - Shows one formula and its current value
+///
+/// WHAT IT DOES:
+/// - Shows the definition (`total = door_a + door_b`) and the value under it,
+///   with its unit when it has one
+/// - When the formula has no value, shows why instead, in red - for example
+///   "circular formula: total → total" for a formula that uses itself
+///
+/// `value` is a `Signal`, so the display updates whenever the value does.
+#[component]
+pub fn FormulaDisplay(
+    /// The formula's name
+    name: String,
+
+    /// The expression it computes
+    source: String,
+
+    /// Its current value, or why it has none
+    #[prop(into)]
+    value: Signal<Result<Quantity, FormulaError>>,
+) -> impl IntoView {
+    let card_style = format!(
+        "padding: 12px 20px; background: {}; border-radius: 8px; border: 1px solid {};",
+        theme::CARD_BG,
+        theme::EVIL_RED
+    );
+
+    view! {
+        <div style=card_style>
+            <p style=format!(
+                "color: {}; font-family: monospace; margin: 0 0 6px;",
+                theme::TEXT_MUTED
+            )>
+                {format!("{name} = {source}")}
+            </p>
+            // One paragraph either way; only its style, role and text change
+            <p
+                style=move || match value.get() {
+                    Ok(_) => format!(
+                        "font-size: 1.8em; font-weight: bold; color: {}; margin: 0;",
+                        theme::BRIGHT_RED
+                    ),
+                    Err(_) => format!("color: {}; margin: 0;", theme::BRIGHT_RED),
+                }
+                role=move || value.with(Result::is_err).then_some("alert")
+            >
+                {move || match value.get() {
+                    Ok(value) => match value.unit {
+                        Some(unit) => format!("{} {unit}", value.value),
+                        None => value.value.to_string(),
+                    },
+                    Err(err) => format!("⚠️ {err}"),
+                }}
+            </p>
+        </div>
+    }
+}
+
+/// TagEditor - The tags of one board counter, as removable chips
+///
+/// WHAT IT DOES:
+/// - Shows each tag as a "#tag ×" chip; clicking a chip removes the tag
+/// - Typing a word in the small field and pressing Enter adds it
+///
+/// The tags are read from the board inside `move ||`, so the chips update
+/// as soon as a tag is added or removed.
+#[component]
+pub fn TagEditor(
+    /// The board the counter is on
+    board: Board,
+
+    /// The counter's id
+    id: u64,
+) -> impl IntoView {
+    let (input, set_input) = signal(String::new());
+    let add_tag = move || {
+        if board.add_tag(id, &input.get_untracked()).is_ok() {
+            set_input.set(String::new());
+        }
+    };
+
+    let chip_style = format!(
+        "margin: 2px; padding: 2px 8px; background: {}; color: #fff; border: 1px solid {}; \
+         border-radius: 12px; cursor: pointer; font-size: 0.85em;",
+        theme::DARK_GREY,
+        theme::EVIL_RED
+    );
+
+    view! {
+        <div style="margin: 6px 0;">
+            {move || {
+                board
+                    .tags(id)
+                    .into_iter()
+                    .map(|tag| {
+                        let label = format!("#{tag} ×");
+                        let aria_label = format!("Remove tag {tag}");
+                        view! {
+                            <button
+                                style=chip_style.clone()
+                                aria-label=aria_label
+                                on:click=move |_| board.remove_tag(id, &tag)
+                            >
+                                {label}
+                            </button>
+                        }
+                    })
+                    .collect_view()
+            }}
+            <input
+                type="text"
+                placeholder="+ tag"
+                aria-label="Add tag"
+                style=format!(
+                    "width: 70px; padding: 2px 6px; background: {}; color: #fff; \
+                     border: 1px solid {}; border-radius: 12px; font-size: 0.85em;",
+                    theme::CARD_BG,
+                    theme::EVIL_RED
+                )
+                prop:value=move || input.get()
+                on:input=move |ev| set_input.set(event_target_value(&ev))
+                on:keydown=move |ev| {
+                    if ev.key() == "Enter" {
+                        add_tag();
+                    }
+                }
+            />
+        </div>
+    }
+}
+
+/// Move the counter dragged in `ev` into `group` (see `CounterGroup`)
+fn drop_counter(board: Board, group: Option<&str>, ev: leptos::ev::DragEvent) {
+    ev.prevent_default();
+    // The innermost target decides; the board around it mustn't move it again
+    ev.stop_propagation();
+    let id = ev
+        .data_transfer()
+        .and_then(|data| data.get_data("text/plain").ok())
+        .and_then(|id| id.parse().ok());
+    if let Some(id) = id {
+        _ = board.move_counter(id, group);
+    }
+}
+
+/// CounterGroup - The header of one folder of a `CounterBoard`
+///
+/// WHAT IT DOES:
+/// - Shows the folder's name, its total (see `Board::group_total`) and
+///   "Reset group", which clicks "Reset" on every counter in it
+/// - Clicking the name collapses or expands the folder
+/// - Counters dragged onto it move into the folder
+///
+/// WHERE ARE THE COUNTERS? Not inside: every counter of the board is a
+/// `BoardCard` in ONE keyed list, next to the folder headers. CSS `order`
+/// puts each card after its folder's header (see `Board::group_position`).
+/// Moving a counter to another folder only changes its `order`, so its
+/// `Counter` stays on the page with its undo history and running timers -
+/// a list per folder would remove it from one and create it anew in the other.
+#[component]
+pub fn CounterGroup(
+    /// The board the folder is on
+    board: Board,
+
+    /// The folder's name
+    group: String,
+) -> impl IntoView {
+    let total = {
+        let group = group.clone();
+        Memo::new(move |_| board.group_total(Some(&group)))
+    };
+    let collapsed = {
+        let group = group.clone();
+        Signal::derive(move || board.is_collapsed(&group))
+    };
+    let order = {
+        let group = group.clone();
+        move || (2 * board.group_position(Some(&group))).to_string()
+    };
+    let (toggle, drop_into, reset) = (group.clone(), group.clone(), group.clone());
+
+    view! {
+        <div
+            style=format!(
+                "flex-basis: 100%; display: flex; align-items: center; \
+                 justify-content: space-between; gap: 12px; margin-top: 12px; \
+                 padding: 12px 12px 0; border-top: 1px dashed {};",
+                theme::EVIL_RED
+            )
+            style:order=order
+            on:dragover=move |ev: leptos::ev::DragEvent| ev.prevent_default()
+            on:drop=move |ev| drop_counter(board, Some(&drop_into), ev)
+        >
+            <button
+                aria-expanded=move || (!collapsed.get()).to_string()
+                on:click=move |_| board.set_collapsed(&toggle, !collapsed.get_untracked())
+                style=format!(
+                    "background: none; border: none; cursor: pointer; color: {}; \
+                     font-size: 1.2em; font-weight: bold;",
+                    theme::TEXT_MUTED
+                )
+            >
+                {move || if collapsed.get() { "▸ " } else { "▾ " }}
+                {group}
+            </button>
+            <span style=move || format!(
+                "color: {}; font-family: monospace;",
+                match total.get() {
+                    Ok(_) => theme::TEXT_MUTED,
+                    Err(_) => theme::BRIGHT_RED,
+                }
+            )>
+                {move || match total.get() {
+                    Ok(total) => match total.unit {
+                        Some(unit) => format!("Total: {} {unit}", total.value.to_decimal(2)),
+                        None => format!("Total: {}", total.value.to_decimal(2)),
+                    },
+                    Err(err) => format!("⚠️ {err}"),
+                }}
+            </span>
+            <EvilButton
+                label="Reset group"
+                on_click=Box::new(move |_| board.reset_group(Some(&reset)))
+            />
+        </div>
+    }
+}
+
+/// BoardCard - One counter of a `CounterBoard`, with its name, tags and folder
+///
+/// WHAT IT DOES:
+/// - Shows the counter's name, its `TagEditor`, a "Folder" select (which
+///   moves it from the keyboard too) and the `Counter` itself
+/// - A "Countdown" checkbox adds the countdown to the counter (see
+///   `CountdownControls`), and a "Goal" field its progress towards a goal
+///   (see `GoalProgress`; empty for none). "Exact" keeps fractions, shown
+///   with "Decimals" digits. The board remembers all of them per counter
+/// - Can be dragged onto a folder's header; dropped onto another card, it
+///   joins that card's folder
+/// - With `shown` (the board's search results), hides itself when it isn't
+///   one of them
+/// - In a collapsed folder, hides itself - unless the board is searching,
+///   so that no result is hidden
+///
+/// DRAG AND DROP:
+/// The browser's built-in drag and drop needs three events: `dragstart` on
+/// the dragged element (we put the counter's id into the drag's data),
+/// `dragover` on the target (calling `prevent_default()` there is what
+/// allows dropping at all), and `drop`, where we read the id back.
+///
+/// WHY HIDE INSTEAD OF REMOVE? Collapsing only sets the `hidden` attribute
+/// (the browser's `display: none`). Removing the counter from the page
+/// would drop its state (undo history, running timers); hidden, it keeps
+/// counting.
+#[component]
+pub fn BoardCard(
+    /// The board the counter is on
+    board: Board,
+
+    /// The counter to show
+    counter: board::BoardCounter,
+
+    /// Ids of the counters to show (all of them when unset)
+    #[prop(optional, into)]
+    shown: MaybeProp<Vec<u64>>,
+) -> impl IntoView {
+    let id = counter.id;
+    let handle_dragstart = move |ev: leptos::ev::DragEvent| {
+        if let Some(data) = ev.data_transfer() {
+            _ = data.set_data("text/plain", &id.to_string());
+        }
+    };
+    let handle_drop = move |ev| drop_counter(board, board.group_of(id).as_deref(), ev);
+    let handle_move = move |ev: leptos::ev::Event| {
+        let group = event_target_value(&ev);
+        _ = board.move_counter(id, (!group.is_empty()).then_some(group.as_str()));
+    };
+
+    // Right after its folder's header
+    let order = move || (2 * board.group_position(board.group_of(id).as_deref()) + 1).to_string();
+    // Hidden cards are skipped by `visible_results` (the arrow keys) too
+    let hidden = move || match shown.get() {
+        Some(shown) => !shown.contains(&id),
+        None => board
+            .group_of(id)
+            .is_some_and(|group| board.is_collapsed(&group)),
+    };
+
+    // Changing these keeps the same `Counter` (and its count)
+    let countdown = Memo::new(move |_| board.has_countdown(id));
+    let goal = Memo::new(move |_| board.goal_of(id));
+    let exact = Memo::new(move |_| board.is_exact(id));
+    let precision = Memo::new(move |_| board.precision_of(id));
+
+    let name = counter.name.clone();
+    // `data-board-result` and `tabindex` let `CounterBoard`'s
+    // arrow keys move the focus from result to result
+    view! {
+        <div
+            draggable="true"
+            on:dragstart=handle_dragstart
+            on:dragover=move |ev: leptos::ev::DragEvent| ev.prevent_default()
+            on:drop=handle_drop
+            style="cursor: grab; border-radius: 12px;"
+            style:order=order
+            hidden=hidden
+            tabindex="0"
+            data-board-result=""
+            aria-label=counter.name.clone()
+        >
+            <h3 style=format!(
+                "color: {}; font-family: monospace; margin: 0 0 8px;",
+                theme::TEXT_MUTED
+            )>{counter.name.clone()}</h3>
+            <TagEditor board=board id=id />
+            <label style=format!("color: {}; font-size: 0.9em;", theme::TEXT_MUTED)>
+                "Folder "
+                <select on:change=handle_move>
+                    <option value="" selected=move || board.group_of(id).is_none()>"(none)"</option>
+                    {move || {
+                        let current = board.group_of(id);
+                        board
+                            .groups()
+                            .get()
+                            .into_iter()
+                            .map(|group| {
+                                let selected = current.as_ref() == Some(&group);
+                                let label = group.clone();
+                                view! {
+                                    <option value=group selected=selected>{label}</option>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </select>
+            </label>
+            <label style=format!("color: {}; font-size: 0.9em; margin-left: 10px;", theme::TEXT_MUTED)>
+                <input
+                    type="checkbox"
+                    prop:checked=countdown
+                    on:change=move |ev| board.set_countdown(id, event_target_checked(&ev))
+                />
+                " Countdown"
+            </label>
+            <label style=format!("color: {}; font-size: 0.9em; margin-left: 10px;", theme::TEXT_MUTED)>
+                "Goal "
+                <input
+                    type="number"
+                    placeholder="none"
+                    style=format!("{} width: 80px;", field_style())
+                    prop:value=move || goal.get().map(|goal| goal.to_string()).unwrap_or_default()
+                    on:change=move |ev| board.set_goal(id, event_target_value(&ev).trim().parse().ok())
+                />
+            </label>
+            <label style=format!("color: {}; font-size: 0.9em; margin-left: 10px;", theme::TEXT_MUTED)>
+                <input
+                    type="checkbox"
+                    prop:checked=exact
+                    on:change=move |ev| board.set_exact(id, event_target_checked(&ev))
+                />
+                " Exact"
+            </label>
+            <label style=format!("color: {}; font-size: 0.9em; margin-left: 10px;", theme::TEXT_MUTED)>
+                "Decimals "
+                <input
+                    type="number"
+                    min="0"
+                    max="10"
+                    style=format!("{} width: 60px;", field_style())
+                    prop:value=move || precision.get().to_string()
+                    disabled=move || !exact.get()
+                    on:change=move |ev| {
+                        if let Ok(precision) = event_target_value(&ev).trim().parse::<usize>() {
+                            board.set_precision(id, precision.min(10));
+                        }
+                    }
+                />
+            </label>
+            <Counter
+                control=counter.control
+                initial=counter.initial
+                unit=counter.unit
+                countdown=countdown
+                goal=goal
+                exact=exact
+                precision=precision
+                local=true
+                name=name
+            />
+        </div>
+    }
+}
+
+/// CounterBoard - Several named counters, plus formulas computed from them
+///
+/// WHAT IT DOES:
+/// - Shows every counter of a `Board` (see src/board.rs) under its name
+/// - Shows every formula with `FormulaDisplay`
+/// - One text field adds everything: a name (`door_a`) adds a counter,
+///   a name and a unit (`water ml`) a measured counter, and a definition
+///   (`total = door_a + door_b`) a formula. "Add folder" adds a folder
+///   (`CounterGroup`) with that name instead. `#words` tag the counter
+/// - A search bar above the counters hides the ones that don't match (see
+///   src/search.rs for what can be typed: names, `#tags`, `>50`, ...)
+///
+/// KEYBOARD NAVIGATION:
+/// In the search bar, ↓ (or Enter) focuses the first result; on a result,
+/// ↓/↑ move to the next/previous one, Home/End to the first/last, and
+/// Escape (or ↑ on the first) goes back to the search bar. Instead of a
+/// handler on every counter, ONE `on:keydown` on the list handles the keys
+/// of all of them: key events "bubble up" from the focused element through
+/// its parents, and `ev.target()` tells which counter it came from.
+///
+/// MEMOS:
+/// `Memo::new` is like a derived signal that remembers its result. The
+/// board's memo evaluates every formula (see src/formula.rs) and re-runs
+/// only when a count or the formula list changes. Each formula then gets
+/// its own small memo picking its value out of that result: a `Memo` only
+/// notifies its readers when its value actually CHANGES, so "+1" on
+/// `door_a` doesn't re-render a formula that doesn't use it.
+///
+/// `<For>` renders a list, using `key` to tell which items are new - so
+/// adding a counter doesn't re-create (and reset) the existing ones.
+#[component]
+pub fn CounterBoard(
+    /// The board to show (a new, empty one by default)
+    #[prop(optional)]
+    board: Option<Board>,
+) -> impl IntoView {
+    let board = board.unwrap_or_default();
+    let values = Memo::new(move |_| board.values());
+
+    let (input, set_input) = signal(String::new());
+    let (error, set_error) = signal(None::<String>);
+    let finish = move |added: Result<(), board::BoardError>| match added {
+        Ok(()) => {
+            set_input.set(String::new());
+            set_error.set(None);
+        }
+        Err(err) => set_error.set(Some(err.to_string())),
+    };
+    let add = move || finish(board.add_from_input(&input.get_untracked()));
+    let add_folder = move |_| finish(board.add_group(&input.get_untracked()));
+
+    // The search: parsed once per keystroke, then matched against every
+    // counter. `None` while the bar is empty (or invalid): show everything.
+    let (search, set_search) = signal(String::new());
+    let query = Memo::new(move |_| search.get().parse::<search::Query>());
+    let results = Memo::new(move |_| match query.get() {
+        Ok(query) if !query.is_empty() => Some(board.search(&query)),
+        _ => None,
+    });
+
+    let search_ref = NodeRef::<leptos::html::Input>::new();
+    let results_ref = NodeRef::<leptos::html::Div>::new();
+    let focus_search = move || {
+        if let Some(input) = search_ref.get() {
+            _ = input.focus();
+        }
+    };
+    let focus_result = move |pick: &dyn Fn(usize) -> Option<usize>| {
+        let Some(list) = results_ref.get() else {
+            return;
+        };
+        let results = visible_results(&list);
+        if let Some(result) = pick(results.len()).and_then(|index| results.get(index)) {
+            _ = result.focus();
+        }
+    };
+    let handle_search_key = move |ev: leptos::ev::KeyboardEvent| {
+        if matches!(ev.key().as_str(), "ArrowDown" | "Enter") {
+            ev.prevent_default();
+            focus_result(&|_| Some(0));
+        }
+    };
+    let handle_result_key = move |ev: leptos::ev::KeyboardEvent| {
+        use wasm_bindgen::JsCast;
+        // Only keys pressed on a result itself, not on the buttons inside it
+        let Some(result) = ev
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok())
+            .filter(|element| element.has_attribute("data-board-result"))
+        else {
+            return;
+        };
+        let index = results_ref
+            .get()
+            .and_then(|list| visible_results(&list).iter().position(|r| *r == result));
+        let Some(index) = index else {
+            return;
+        };
+        match ev.key().as_str() {
+            "ArrowDown" => focus_result(&|_| Some(index + 1)),
+            "ArrowUp" if index == 0 => focus_search(),
+            "ArrowUp" => focus_result(&|_| Some(index - 1)),
+            "Home" => focus_result(&|_| Some(0)),
+            "End" => focus_result(&|count| count.checked_sub(1)),
+            "Escape" => focus_search(),
+            _ => return,
+        }
+        ev.prevent_default();
+    };
+
+    let input_style = format!("{} font-family: monospace; width: 260px;", field_style());
+    let note_style = format!(
+        "font-size: 0.9em; margin: 4px 0 0; min-height: 1.2em; color: {};",
+        theme::TEXT_MUTED
+    );
+
     view! {
-        // The main container for the counter UI
-        <div style=container_style>
-            // PART 1: Display the current count
-            // Pass a closure so it updates reactively when count changes
-            <CounterDisplay count=move || count.get() />
-
-            // PART 2: Show a message based on the count value
-            // Pass the signal directly (not a closure)
-            // CounterMessage will wrap it in a closure itself
-            <CounterMessage count=count />
-
-            // PART 3: Render the action buttons
-            // Pass each handler as a Box (pointer) to the function
-            // When a button is clicked, its handler is called, which updates set_count
-            <CounterButtons
-                on_decrement=Box::new(handle_decrement)
-                on_increment=Box::new(handle_increment)
-                on_reset=Box::new(handle_reset)
-                on_multiply=Box::new(handle_multiply)
-                on_divide=Box::new(handle_divide)
+        <section style="margin-top: 32px; text-align: center;" aria-label="Counter board">
+            <input
+                type="search"
+                node_ref=search_ref
+                placeholder="Search: door  #kitchen  >50  band:high"
+                aria-label="Search counters"
+                style=input_style.clone()
+                prop:value=move || search.get()
+                on:input=move |ev| set_search.set(event_target_value(&ev))
+                on:keydown=handle_search_key
             />
+            // `aria-live` makes screen readers announce the new count of results
+            <p style=note_style aria-live="polite">
+                {move || match (query.get(), results.get()) {
+                    (Err(err), _) => Some(format!("⚠️ {err}")),
+                    (_, Some(found)) => Some(format!(
+                        "{} of {} counters match",
+                        found.len(),
+                        board.counters().with(Vec::len)
+                    )),
+                    _ => None,
+                }}
+            </p>
+            // Folder headers and counters side by side in one list, so moving a
+            // counter never re-creates it (see `CounterGroup`). Dropped on
+            // the list itself, a counter leaves its folder.
+            <div
+                node_ref=results_ref
+                on:keydown=handle_result_key
+                on:dragover=move |ev: leptos::ev::DragEvent| ev.prevent_default()
+                on:drop=move |ev| drop_counter(board, None, ev)
+                style="display: flex; flex-wrap: wrap; gap: 16px; justify-content: center; min-height: 40px;"
+            >
+                <For
+                    each=move || board.groups().get()
+                    key=|group| group.clone()
+                    children=move |group| view! { <CounterGroup board=board group=group /> }
+                />
+                // `into_any()` hides the (very long) type of a whole `Counter`
+                // view; nested in a list, it would be too deep for the compiler
+                <For
+                    each=move || board.counters().get()
+                    key=|counter| counter.id
+                    children=move |counter| {
+                        view! { <BoardCard board=board counter=counter shown=results /> }.into_any()
+                    }
+                />
+            </div>
+            <div style="display: flex; flex-wrap: wrap; gap: 16px; justify-content: center; margin: 16px 0;">
+                <For
+                    each=move || board.formulas().get()
+                    key=|formula| formula.name.clone()
+                    children=move |formula| {
+                        let name = formula.name.clone();
+                        let value = Memo::new(move |_| {
+                            values.with(|values| {
+                                values.get(&name).cloned().unwrap_or_else(|| {
+                                    Err(FormulaError::Expr(expr::ExprError::UnknownName(name.clone())))
+                                })
+                            })
+                        });
+                        view! { <FormulaDisplay name=formula.name source=formula.source value=value /> }
+                    }
+                />
+            </div>
+            <input
+                type="text"
+                placeholder="door_a  or  total = door_a + door_b"
+                aria-label="New counter, formula or folder"
+                style=input_style
+                prop:value=move || input.get()
+                on:input=move |ev| set_input.set(event_target_value(&ev))
+                on:keydown=move |ev| {
+                    if ev.key() == "Enter" {
+                        add();
+                    }
+                }
+            />
+            <EvilButton label="Add" on_click=Box::new(move |_| add()) />
+            <EvilButton label="Add folder" on_click=Box::new(add_folder) />
+            <p style=format!(
+                "color: {}; font-size: 0.9em; margin: 4px 0 0; min-height: 1.2em;",
+                theme::BRIGHT_RED
+            )>
+                {move || error.get().map(|err| format!("⚠️ {err}"))}
+            </p>
+        </section>
+    }
+}
+
+/// The board's search results that are showing, in page order
+///
+/// The page shows them by their CSS `order` (their folder), not in the
+/// order they are in the list; the sort is stable, so each folder keeps
+/// its own order.
+fn visible_results(list: &web_sys::Element) -> Vec<web_sys::HtmlElement> {
+    use wasm_bindgen::JsCast;
+    let Ok(nodes) = list.query_selector_all("[data-board-result]:not([hidden])") else {
+        return Vec::new();
+    };
+    let mut results: Vec<web_sys::HtmlElement> = (0..nodes.length())
+        .filter_map(|i| nodes.item(i)?.dyn_into().ok())
+        .collect();
+    results.sort_by_key(|result| {
+        let order = result.style().get_property_value("order").ok();
+        order.and_then(|order| order.parse::<usize>().ok())
+    });
+    results
+}
+
+/// UpdateBanner - Tells the user a new version of the app is ready
+///
+/// WHAT IT DOES:
+/// - Renders nothing while `show` is false
+/// - Once a new build has been downloaded (see src/pwa.rs), shows a bar
+///   pinned to the top of the screen with a "Reload" button
+///
+/// WHY AN Option:
+/// `move || show.get().then(|| view! { ... })` returns `Option<View>`.
+/// Leptos renders `None` as nothing and `Some(view)` as the view, so this is
+/// the simplest way to show something only sometimes.
+#[component]
+pub fn UpdateBanner(
+    /// Whether a new version is waiting
+    show: ReadSignal<bool>,
+
+    /// Called when the user clicks "Reload"
+    on_reload: Box<dyn Fn(leptos::ev::MouseEvent) + 'static>,
+) -> impl IntoView {
+    // `on_reload` is only used inside the closure below, which may run many
+    // times - so we share it through a StoredValue instead of moving it in
+    let on_reload = StoredValue::new_local(on_reload);
+
+    let banner_style = format!(
+        "position: fixed; top: 0; left: 0; right: 0; z-index: 10; display: flex; \
+         justify-content: center; align-items: center; gap: 12px; padding: 8px; \
+         background: {}; color: #fff; border-bottom: 2px solid {};",
+        theme::CARD_BG,
+        theme::BRIGHT_RED
+    );
+
+    move || {
+        show.get().then(|| {
+            view! {
+                <div style=banner_style.clone() role="status">
+                    "A new version is available."
+                    <EvilButton
+                        label="Reload"
+                        on_click=Box::new(move |ev| on_reload.with_value(|f| f(ev)))
+                    />
+                </div>
+            }
+        })
+    }
+}
+
+/// SoundControls - Mute, volume and the optional sound effects
+///
+/// WHAT IT DOES:
+/// - A mute button that silences every sound on the page (the countdown
+///   alarm too); `aria-pressed` tells screen readers whether it's on
+/// - A volume slider (0-100%)
+/// - A "Sound effects" checkbox: a tone for every operation and a tune when
+///   a count moves into another band (see src/sound.rs)
+///
+/// Every change is saved right away (see src/settings.rs), so the choice
+/// survives a reload. `settings` is the page's shared signal: muting here
+/// silences every counter at once.
+#[component]
+pub fn SoundControls(
+    /// The page's settings (see `settings::use_settings`)
+    settings: RwSignal<Settings>,
+) -> impl IntoView {
+    let change = move |f: &dyn Fn(&mut Settings)| {
+        settings.update(|settings| f(settings));
+        settings.with_untracked(Settings::save);
+    };
+    let muted = move || settings.with(|settings| settings.muted);
+
+    let bar_style = format!(
+        "width: 100%; display: flex; flex-wrap: wrap; justify-content: center; \
+         align-items: center; gap: 12px; color: {};",
+        theme::TEXT_MUTED
+    );
+    let mute_style = format!(
+        "min-height: 48px; padding: 8px 16px; background: {}; color: #fff; \
+         border: 1px solid {}; border-radius: 6px; cursor: pointer;",
+        theme::CARD_BG,
+        theme::EVIL_RED
+    );
+
+    view! {
+        <div style=bar_style>
+            <button
+                style=mute_style
+                aria-pressed=move || muted().to_string()
+                on:click=move |_| change(&|settings| settings.muted = !settings.muted)
+            >
+                {move || if muted() { "🔇 Sound off" } else { "🔊 Sound on" }}
+            </button>
+            <label>
+                "Volume "
+                <input
+                    type="range"
+                    min="0"
+                    max="100"
+                    prop:value=move || (settings.with(|settings| settings.volume) * 100.0).round().to_string()
+                    prop:disabled=muted
+                    on:input=move |ev| {
+                        if let Ok(percent) = event_target_value(&ev).parse::<f32>() {
+                            change(&|settings| settings.volume = percent / 100.0);
+                        }
+                    }
+                />
+            </label>
+            <label>
+                <input
+                    type="checkbox"
+                    prop:checked=move || settings.with(|settings| settings.sound_effects)
+                    on:change=move |ev| {
+                        let on = event_target_checked(&ev);
+                        change(&|settings| settings.sound_effects = on);
+                    }
+                />
+                " Sound effects"
+            </label>
         </div>
     }
 }
 
+/// NotificationControls - Which thresholds raise a desktop notification
+///
+/// WHAT IT DOES:
+/// - One checkbox per band of `CounterMessage` (see src/band.rs): "Count
+///   goes HIGH", "Count goes NEGATIVE", ...
+/// - Ticking the first one asks the browser for permission; if the user
+///   refuses, the box unticks itself and the rules are disabled with a hint
+///   on how to allow them again
+/// - Notifications are only sent while the tab is in the background (see
+///   src/notify.rs), which the hint under the rules says
+///
+/// WHY `Option<Permission>`?
+/// Only the browser knows the permission, and effects only run there. The
+/// server-rendered HTML shows `None` ("not known yet") until the effect runs.
+#[component]
+pub fn NotificationControls(
+    /// The page's settings (see `settings::use_settings`)
+    settings: RwSignal<Settings>,
+) -> impl IntoView {
+    let (permission, set_permission) = signal(None::<Permission>);
+    Effect::new(move |_| set_permission.set(Some(notify::permission())));
+
+    let change = move |band: Band, on: bool| {
+        // `update` even when nothing changes, so a refused box unticks itself
+        settings.update(|settings| settings.set_notify(band, on));
+        settings.with_untracked(Settings::save);
+    };
+    let toggle = move |band: Band, on: bool| {
+        if !on {
+            change(band, false);
+            return;
+        }
+        // Start asking now, while the click is still being handled
+        let answer = notify::request_permission();
+        leptos::task::spawn_local(async move {
+            let answer = answer.await;
+            set_permission.set(Some(answer));
+            change(band, answer == Permission::Granted);
+        });
+    };
+    let blocked = move || {
+        matches!(
+            permission.get(),
+            Some(Permission::Denied | Permission::Unsupported)
+        )
+    };
+
+    let rules = Band::ALL.into_iter().rev().map(|band| {
+        let label = match band {
+            Band::High => format!("Count goes HIGH (above {})", Band::HIGH_ABOVE),
+            Band::Normal => "Count is back to normal".to_string(),
+            Band::Zero => "Count hits ZERO".to_string(),
+            Band::Negative => "Count goes NEGATIVE".to_string(),
+        };
+        view! {
+            <label style="display: block; margin: 4px 0;">
+                <input
+                    type="checkbox"
+                    prop:checked=move || settings.with(|settings| settings.notifies(band))
+                    prop:disabled=blocked
+                    on:change=move |ev| toggle(band, event_target_checked(&ev))
+                />
+                " "
+                {label}
+            </label>
+        }
+    });
+
+    let hint = move || match permission.get() {
+        Some(Permission::Unsupported) => "This browser can't show notifications.",
+        Some(Permission::Denied) => {
+            "Notifications are blocked for this page - allow them in the browser's site settings."
+        }
+        _ => "Sent only while this tab is in the background.",
+    };
+
+    view! {
+        <details style=format!("width: 100%; text-align: center; color: {};", theme::TEXT_MUTED)>
+            <summary style="cursor: pointer; min-height: 48px; line-height: 48px;">"🔔 Notifications"</summary>
+            <div style="display: inline-block; text-align: left;">
+                {rules.collect_view()}
+                <p style="font-size: 0.9em; margin: 6px 0 0;">{hint}</p>
+            </div>
+        </details>
+    }
+}
+
 // ============================================================================
 // SECTION 5: APPLICATION STRUCTURE
 // ============================================================================
@@ -620,19 +2858,29 @@ fn Counter() -> impl IntoView {
 /// - This is the entry point for the entire UI
 /// - Everything else is nested inside this component
 ///
+/// - Registers the service worker and shows `UpdateBanner` when a new build is ready
+///   (not when `embedded`: then the host page owns the service worker, and the
+///   app only fills the element it was mounted into)
+///
 /// COMPONENT HIERARCHY:
 /// ```
 /// App
 /// ├── EvilBackground (layout)
-/// │   └── Counter (feature)
-/// │       ├── CounterDisplay
-/// │       ├── CounterMessage
-/// │       └── CounterButtons
-/// │           ├── EvilButton
-/// │           ├── EvilButton
-/// │           ├── EvilButton
-/// │           ├── EvilButton
-/// │           └── EvilButton
+/// │   ├── UpdateBanner (only when an update is waiting)
+/// │   ├── SoundControls (mute, volume, sound effects - saved in settings)
+/// │   ├── NotificationControls (which thresholds notify - saved too)
+/// │   ├── Counter (feature)
+/// │   │   ├── CounterDisplay
+/// │   │   ├── CounterMessage
+/// │   │   ├── CounterButtons
+/// │   │   │   ├── EvilButton
+/// │   │   │   ├── EvilButton
+/// │   │   │   ├── EvilButton
+/// │   │   │   ├── EvilButton
+/// │   │   │   ├── EvilButton
+/// │   │   │   └── EvilButton (Undo)
+/// │   │   └── TimerControls (Start / Pause / Stop)
+/// │   └── CounterBoard (named counters, plus FormulaDisplay per formula)
 /// ```
 ///
 /// LEARNING: This tree structure is how you build complex UIs.
@@ -640,13 +2888,51 @@ fn Counter() -> impl IntoView {
 ///           compose them into feature components (CounterButtons),
 ///           then combine features into pages (Counter),
 ///           then combine pages into the app (App).
+///
+/// `App` is `pub` so the `server` binary (see `src/bin/server.rs`) can render it
+/// to HTML with the `ssr` feature.
 #[component]
-fn App() -> impl IntoView {
+pub fn App(
+    /// Mounted into an element of another page rather than owning the whole page
+    #[prop(optional)]
+    embedded: bool,
+) -> impl IntoView {
+    // The new service worker waiting to take over (browser-only type, hence `new_local`)
+    let waiting_worker = StoredValue::new_local(None::<web_sys::ServiceWorker>);
+    let (update_ready, set_update_ready) = signal(false);
+
+    // Register the service worker once, in the browser only
+    Effect::new(move |_| {
+        if embedded {
+            return;
+        }
+        pwa::register(move |worker| {
+            waiting_worker.set_value(Some(worker));
+            set_update_ready.set(true);
+        });
+    });
+
+    // Sound settings for every counter on the page (see src/settings.rs)
+    let settings = settings::provide_settings();
+
+    let handle_reload = move |_: leptos::ev::MouseEvent| {
+        waiting_worker.with_value(|worker| {
+            if let Some(worker) = worker {
+                pwa::activate_update(worker);
+            }
+        });
+    };
+
     view! {
         // The entire app is wrapped in the background layout
-        <EvilBackground>
+        <EvilBackground embedded=embedded>
+            <UpdateBanner show=update_ready on_reload=Box::new(handle_reload) />
+            <SoundControls settings=settings />
+            <NotificationControls settings=settings />
             // Inside the background, we render the counter feature
-            <Counter />
+            <Counter expression=true timer=true />
+            // More counters, and formulas over them
+            <CounterBoard />
         </EvilBackground>
     }
 }
@@ -697,15 +2983,65 @@ fn App() -> impl IntoView {
 ///           and Leptos renders your components to actual DOM elements.
 /// - pub fn main() is called, which mounts the app
 /// - Leptos takes over, rendering the app and managing updates
+///
+/// Only compiled with the `csr` feature (the default, used by Trunk).
+///
+/// WHERE THE APP GOES:
+/// - Every element marked `<div data-webtest-app></div>` gets its own,
+///   independent copy of the app (see `mountApp` in src/js_api.rs)
+/// - No such element: the app takes over <body>, as before
+/// - `globalThis.__webtestNoAutoMount = true` (set before loading the WASM):
+///   nothing is mounted; the page calls `mountApp`/`mountCounter` itself or
+///   only uses the `<evil-counter>` element (see src/element.rs)
+#[cfg(feature = "csr")]
 #[wasm_bindgen(start)]
 pub fn main() {
     use leptos::mount::mount_to_body;
 
+    let no_auto_mount = js_sys::Reflect::get(&js_sys::global(), &"__webtestNoAutoMount".into())
+        .is_ok_and(|flag| flag.is_truthy());
+    if no_auto_mount {
+        return;
+    }
+
+    let targets = js_api::auto_mount_targets();
+    if !targets.is_empty() {
+        for target in targets {
+            // These live as long as the page, so their handles are never dropped
+            js_api::mount_app_into(target).forget();
+        }
+        return;
+    }
+
     // Mount the App component to the <body> element
     // This renders the entire application and starts the reactivity system
     mount_to_body(|| view! { <App /> });
 }
 
+/// hydrate - The entry point when the page was already rendered by the server
+///
+/// WHAT IT DOES:
+/// - The `server` binary sends HTML that already contains the counter,
+///   so the user sees it before any WASM has loaded (fast first paint)
+/// - This function walks that existing HTML and attaches the signals and
+///   event handlers to it, instead of building new DOM elements
+///
+/// HOW IT WORKS:
+/// - Compiled only with the `hydrate` feature
+/// - NOT marked `start`: the page served by the server imports the WASM
+///   module and calls `hydrate()` itself once the module is initialised
+///
+/// LEARNING: The server and the client MUST render the same `App`,
+///           otherwise hydration finds HTML it doesn't expect and panics.
+#[cfg(feature = "hydrate")]
+#[wasm_bindgen]
+pub fn hydrate() {
+    use leptos::mount::hydrate_body;
+
+    // Take over the server-rendered <body> instead of replacing it
+    hydrate_body(|| view! { <App /> });
+}
+
 // ============================================================================
 // COMPREHENSIVE LEARNING GUIDE: UNDERSTANDING LEPTOS REACTIVITY
 // ============================================================================
@@ -885,6 +3221,7 @@ pub fn main() {
 // ✓ Styling (inline CSS with Rust)
 // ✓ The view! macro (HTML-like syntax)
 // ✓ Entry points and mounting (#[wasm_bindgen(start)])
+// ✓ Server-side rendering and hydration (`ssr` / `hydrate` features)
 //
 // NEXT STEPS:
 // 1. Run the app and play with it: trunk serve --open
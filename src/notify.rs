@@ -0,0 +1,110 @@
+// ============================================================================
+// NOTIFY - DESKTOP NOTIFICATIONS WHEN A COUNT CROSSES A THRESHOLD
+// ============================================================================
+//
+// WHAT: The thresholds `CounterMessage` talks about (see src/band.rs) can
+//       also pop up a system notification - "🔥 Count is HIGH: 51" - for
+//       people who are busy in another tab while a timer or another device
+//       changes the count. Each band is its own rule, switched on in
+//       `NotificationControls` (and saved in src/settings.rs).
+//
+// WHY ONLY IN THE BACKGROUND? While the tab is visible, the message under
+//      the counter already says it. A notification on top would just be
+//      noise, so `Counter` only sends one while `page_hidden()`.
+//
+// PERMISSION: A page must ask before showing notifications, and browsers
+//      only let it ask in response to a click. So nothing is asked until
+//      the user ticks a rule; if they refuse, the browser remembers, and all
+//      we can do is say how to change it in the site settings.
+//
+// LEARNING: `Notification` doesn't exist in every browser (older iOS Safari
+//           has none), so `permission` checks for it first - calling a
+//           missing constructor from Rust would throw.
+
+use crate::band::Band;
+use std::future::Future;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+/// Whether this page may show notifications
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Permission {
+    /// The browser has no notifications
+    Unsupported,
+    /// Not asked yet
+    Default,
+    Granted,
+    Denied,
+}
+
+/// The notification for a count that just moved into `band`: (title, body)
+///
+/// `name` is the counter's name, for counters on the board.
+pub fn message(band: Band, value: i32, name: Option<&str>) -> (String, String) {
+    let subject = name.map_or_else(|| "Count".to_string(), |name| format!("`{name}`"));
+    let title = match band {
+        Band::High => format!("🔥 {subject} is HIGH"),
+        Band::Negative => format!("❄️ {subject} is NEGATIVE"),
+        Band::Zero => format!("😴 {subject} is ZERO"),
+        Band::Normal => format!("{subject} is back to normal"),
+    };
+    let body = match band {
+        Band::High => format!("{value} is above {}", Band::HIGH_ABOVE),
+        Band::Negative => format!("{value} is below zero"),
+        Band::Zero | Band::Normal => format!("It is now {value}"),
+    };
+    (title, body)
+}
+
+/// What the user said about notifications so far
+pub fn permission() -> Permission {
+    let supported = web_sys::window().is_some_and(|window| {
+        js_sys::Reflect::has(&window, &JsValue::from_str("Notification")).unwrap_or(false)
+    });
+    if !supported {
+        return Permission::Unsupported;
+    }
+    match Notification::permission() {
+        NotificationPermission::Granted => Permission::Granted,
+        NotificationPermission::Denied => Permission::Denied,
+        _ => Permission::Default,
+    }
+}
+
+/// Ask for permission, resolving to the answer
+///
+/// Call it from a click handler: the browser's prompt is opened right away,
+/// while the click is still being handled (later, browsers refuse to ask).
+pub fn request_permission() -> impl Future<Output = Permission> {
+    let prompt = (permission() == Permission::Default)
+        .then(Notification::request_permission)
+        .and_then(Result::ok);
+    async move {
+        if let Some(prompt) = prompt {
+            let _ = JsFuture::from(prompt).await;
+        }
+        permission()
+    }
+}
+
+/// Whether the page is in a background tab (or minimized)
+pub fn page_hidden() -> bool {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .is_some_and(|document| document.hidden())
+}
+
+/// Show a notification, if allowed
+///
+/// `tag` groups notifications: a new one with the same tag replaces the old
+/// one instead of piling up. Errors are ignored, like a missing beep.
+pub fn show(title: &str, body: &str, tag: &str) {
+    if permission() != Permission::Granted {
+        return;
+    }
+    let options = NotificationOptions::new();
+    options.set_body(body);
+    options.set_tag(tag);
+    let _ = Notification::new_with_options(title, &options);
+}
@@ -0,0 +1,171 @@
+// ============================================================================
+// COUNTER OPERATIONS
+// ============================================================================
+//
+// WHAT: Every button in `CounterButtons` performs one of these operations.
+//
+// WHY: Giving each operation a name (instead of an anonymous closure) lets us
+//      send it over the network, store it in a log, and replay it later.
+//      The browser and the server both use THIS enum, so they always agree
+//      on what "+1" or "/2" means.
+//
+// LEARNING: An `enum` with a method is Rust's way of saying
+//           "one of these five things, and here's how each one behaves".
+
+use crate::rational::Rational;
+use serde::{Deserialize, Serialize};
+
+/// One action that can be applied to a counter value
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// "+1"
+    Increment,
+    /// "-1"
+    Decrement,
+    /// "+n" / "-n" for counters with a custom step (see `Operation::step`)
+    Add(i32),
+    /// "Reset" - back to zero
+    Reset,
+    /// "*2"
+    Double,
+    /// "/2" - integer division, rounds toward zero (exactly 1.5 in exact mode)
+    Halve,
+    /// Jump straight to a value (`setCount` in the JavaScript API)
+    Set(i32),
+}
+
+impl Operation {
+    /// All operations, in the order `CounterButtons` shows them
+    pub const ALL: [Operation; 5] = [
+        Operation::Decrement,
+        Operation::Increment,
+        Operation::Reset,
+        Operation::Double,
+        Operation::Halve,
+    ];
+
+    /// The operation a "+" or "-" button performs for a given step size
+    ///
+    /// A step of `1` / `-1` stays `Increment` / `Decrement`; anything else is `Add`.
+    pub fn step(amount: i32) -> Operation {
+        match amount {
+            1 => Operation::Increment,
+            -1 => Operation::Decrement,
+            n => Operation::Add(n),
+        }
+    }
+
+    /// The operation a "-" button performs for a given step size
+    ///
+    /// The opposite of `step`, saturating: a step of `i32::MIN` subtracts
+    /// `i32::MAX` instead of overflowing.
+    pub fn step_down(amount: i32) -> Operation {
+        Operation::step(amount.saturating_neg())
+    }
+
+    /// Whether this only adds to the count ("+1", "-1", "+n")
+    ///
+    /// Additive operations commute, so they can be replayed in any order.
+    pub fn is_additive(self) -> bool {
+        matches!(
+            self,
+            Operation::Increment | Operation::Decrement | Operation::Add(_)
+        )
+    }
+
+    /// Apply this operation to `value` and return the new value
+    ///
+    /// Uses saturating arithmetic: doubling `i32::MAX` stays at `i32::MAX`
+    /// instead of panicking (debug builds) or wrapping to a negative number.
+    pub fn apply(self, value: i32) -> i32 {
+        match self {
+            Operation::Increment => value.saturating_add(1),
+            Operation::Decrement => value.saturating_sub(1),
+            Operation::Add(n) => value.saturating_add(n),
+            Operation::Reset => 0,
+            Operation::Double => value.saturating_mul(2),
+            Operation::Halve => value / 2,
+            Operation::Set(n) => n,
+        }
+    }
+
+    /// Apply this operation to an exact value (see src/rational.rs)
+    ///
+    /// Unlike `apply`, "/2" keeps the fraction. Returns `None` instead of
+    /// saturating when the fraction no longer fits.
+    pub fn apply_exact(self, value: Rational) -> Option<Rational> {
+        let two = Rational::from(2);
+        match self {
+            Operation::Increment => value.checked_add(1.into()),
+            Operation::Decrement => value.checked_add((-1).into()),
+            Operation::Add(n) => value.checked_add(n.into()),
+            Operation::Reset => Some(0.into()),
+            Operation::Double => value.checked_mul(two),
+            Operation::Halve => value.checked_div(two),
+            Operation::Set(n) => Some(n.into()),
+        }
+    }
+
+    /// The text shown on this operation's button
+    pub fn label(self) -> String {
+        match self {
+            Operation::Increment => "+1".to_string(),
+            Operation::Decrement => "-1".to_string(),
+            Operation::Add(n) if n < 0 => n.to_string(),
+            Operation::Add(n) => format!("+{n}"),
+            Operation::Reset => "Reset".to_string(),
+            Operation::Double => "*2".to_string(),
+            Operation::Halve => "/2".to_string(),
+            Operation::Set(n) => format!("={n}"),
+        }
+    }
+}
+
+/// Optional lowest/highest values a counter may take
+///
+/// If `min` is greater than `max`, `min` wins.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+}
+
+impl Bounds {
+    /// Move `value` inside the bounds (unchanged if already inside)
+    pub fn clamp(self, value: i32) -> i32 {
+        let value = self.max.map_or(value, |max| value.min(max));
+        self.min.map_or(value, |min| value.max(min))
+    }
+
+    /// Whether `value` is inside the bounds
+    pub fn contains(self, value: i32) -> bool {
+        self.clamp(value) == value
+    }
+
+    /// Apply `op` to `value`, keeping the result inside the bounds
+    pub fn apply(self, op: Operation, value: i32) -> i32 {
+        self.clamp(op.apply(value))
+    }
+
+    /// The operation that takes `value` where `apply` would: `op` itself,
+    /// or a `Set` to the clamped result when `op` would leave the bounds
+    ///
+    /// Connected counters send this, so every browser ends up at the same
+    /// clamped count.
+    pub fn operation(self, op: Operation, value: i32) -> Operation {
+        let next = self.apply(op, value);
+        if next == op.apply(value) {
+            op
+        } else {
+            Operation::Set(next)
+        }
+    }
+
+    /// `clamp` for exact values; also keeps them inside the i32 range
+    pub fn clamp_exact(self, value: Rational) -> Rational {
+        let max = Rational::from(self.max.unwrap_or(i32::MAX));
+        let min = Rational::from(self.min.unwrap_or(i32::MIN));
+        value.min(max).max(min)
+    }
+}
@@ -0,0 +1,103 @@
+// ============================================================================
+// TEST PROBE - CLICKING AND RE-RENDERING COMPONENTS WITHOUT A BROWSER
+// ============================================================================
+//
+// WHAT: A handle that host tests (tests/common/mod.rs) provide as CONTEXT
+//       around a component, to reach what a browser would reach through the
+//       DOM:
+//       - every `EvilButton` rendered below it registers its label and its
+//         real click handler, so a test can "click" it, and
+//       - `Counter` registers a function that renders it again from its
+//         LIVE state (count, undo history, alarm...).
+//
+// WHY: Outside the browser there is no DOM. `to_html` renders a view once,
+//      and nothing can be clicked. Without the probe a test could only
+//      guess what a button does, and render a brand new counter to look at.
+//
+// HOW: Both are kept in `StoredValue`s. `live_view` builds a fresh view of
+//      the registered counter; the buttons it renders replace the ones
+//      registered before, so `click` always uses the latest render. The
+//      module only exists with the `test-probe` feature, which the crate
+//      turns on for its own tests (see Cargo.toml), so the app never ships it.
+
+use leptos::prelude::*;
+
+/// A button's click handler, as `EvilButton` calls it
+type ClickFn = Box<dyn Fn(leptos::ev::MouseEvent)>;
+
+/// Renders a counter again from its live state
+type RenderFn = Box<dyn Fn() -> AnyView>;
+
+/// Lets host tests click buttons and re-render a live counter (see the module comment)
+#[derive(Clone, Copy)]
+pub struct TestProbe {
+    buttons: StoredValue<Vec<(String, ClickFn)>, LocalStorage>,
+    render: StoredValue<Option<RenderFn>, LocalStorage>,
+}
+
+impl Default for TestProbe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestProbe {
+    /// A probe that has seen nothing yet
+    pub fn new() -> Self {
+        Self {
+            buttons: StoredValue::new_local(Vec::new()),
+            render: StoredValue::new_local(None),
+        }
+    }
+
+    /// Make this probe see every component rendered below the current owner
+    pub fn provide(self) {
+        provide_context(self);
+    }
+
+    /// The probe provided above the current component, if any
+    pub(crate) fn current() -> Option<Self> {
+        use_context()
+    }
+
+    /// Used by `EvilButton`: remember a button and what clicking it does
+    pub(crate) fn register_button(self, label: String, on_click: ClickFn) {
+        self.buttons
+            .update_value(|buttons| buttons.push((label, on_click)));
+    }
+
+    /// Used by `Counter`: remember how to render it again (the first one wins)
+    pub(crate) fn register_view(self, render: impl Fn() -> AnyView + 'static) {
+        if self.render.with_value(Option::is_none) {
+            self.render.set_value(Some(Box::new(render)));
+        }
+    }
+
+    /// A fresh view of the registered counter, showing its current state
+    ///
+    /// Its buttons replace the ones registered so far.
+    pub fn live_view(self) -> Option<AnyView> {
+        self.buttons.update_value(Vec::clear);
+        self.render
+            .with_value(|render| render.as_ref().map(|render| render()))
+    }
+
+    /// The labels of the registered buttons, in the order they were rendered
+    pub fn labels(self) -> Vec<String> {
+        self.buttons
+            .with_value(|buttons| buttons.iter().map(|(label, _)| label.clone()).collect())
+    }
+
+    /// Call the click handler of the first button labelled `label`
+    ///
+    /// Returns `false` when there is no such button.
+    pub fn click(self, label: &str, ev: leptos::ev::MouseEvent) -> bool {
+        self.buttons.with_value(|buttons| {
+            buttons
+                .iter()
+                .find(|(button, _)| button == label)
+                .map(|(_, on_click)| on_click(ev))
+                .is_some()
+        })
+    }
+}
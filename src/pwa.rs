@@ -0,0 +1,100 @@
+// ============================================================================
+// PWA - OFFLINE SUPPORT AND "UPDATE AVAILABLE"
+// ============================================================================
+//
+// WHAT: Registers the service worker (pwa/sw.js) that caches the app so it
+//       works offline, and tells the app when a NEW build is ready.
+//
+// HOW UPDATES WORK:
+// 1. Every Trunk build stamps sw.js with a new version (pwa/stamp-sw.sh).
+// 2. The browser notices sw.js changed and installs the new worker in the
+//    background. It then WAITS, because the old one still controls the page.
+// 3. We call `on_update` so `App` can show a banner.
+// 4. Clicking "Reload" calls `activate_update`: the waiting worker takes
+//    over ("SKIP_WAITING") and the page reloads with the new build.
+//
+// LEARNING: Browser APIs that take callbacks need a `Closure` - a Rust
+//           closure wrapped so JavaScript can call it. `.forget()` keeps it
+//           alive forever, which is fine for handlers registered once.
+
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    ServiceWorker, ServiceWorkerContainer, ServiceWorkerRegistration, ServiceWorkerState,
+};
+
+/// The browser's service worker container, if this page may use one
+///
+/// Missing on plain `http://` pages other than localhost, and in old browsers.
+fn container() -> Option<ServiceWorkerContainer> {
+    let navigator = web_sys::window()?.navigator();
+    let supported =
+        js_sys::Reflect::has(&navigator, &JsValue::from_str("serviceWorker")).unwrap_or(false);
+    supported.then(|| navigator.service_worker())
+}
+
+/// Register `sw.js` and call `on_update` with the new worker whenever a new
+/// build has been downloaded and is waiting to take over
+///
+/// Browser only: call it from an `Effect`.
+pub fn register(on_update: impl Fn(ServiceWorker) + 'static) {
+    let Some(container) = container() else {
+        return;
+    };
+    let on_update = Rc::new(on_update);
+
+    leptos::task::spawn_local(async move {
+        let registration: ServiceWorkerRegistration =
+            match JsFuture::from(container.register("./sw.js")).await {
+                Ok(registration) => registration.unchecked_into(),
+                Err(err) => {
+                    leptos::logging::warn!("service worker registration failed: {err:?}");
+                    return;
+                }
+            };
+
+        // No controller means this is the very first install, not an update
+        let is_update = move || container.controller().is_some();
+
+        // A new build was downloaded while the page was closed
+        if let Some(waiting) = registration.waiting() {
+            if is_update() {
+                on_update(waiting);
+            }
+        }
+
+        // A new build is found while the page is open
+        let watched = registration.clone();
+        let on_update_found = Closure::<dyn Fn()>::new(move || {
+            let Some(installing) = watched.installing() else {
+                return;
+            };
+            let worker = installing.clone();
+            let (on_update, is_update) = (on_update.clone(), is_update.clone());
+            let on_state_change = Closure::<dyn Fn()>::new(move || {
+                if worker.state() == ServiceWorkerState::Installed && is_update() {
+                    on_update(worker.clone());
+                }
+            });
+            installing.set_onstatechange(Some(on_state_change.as_ref().unchecked_ref()));
+            on_state_change.forget();
+        });
+        registration.set_onupdatefound(Some(on_update_found.as_ref().unchecked_ref()));
+        on_update_found.forget();
+    });
+}
+
+/// Switch to the waiting worker and reload once it has taken control
+pub fn activate_update(worker: &ServiceWorker) {
+    if let Some(container) = container() {
+        let reload = Closure::<dyn Fn()>::new(|| {
+            if let Some(window) = web_sys::window() {
+                _ = window.location().reload();
+            }
+        });
+        container.set_oncontrollerchange(Some(reload.as_ref().unchecked_ref()));
+        reload.forget();
+    }
+    _ = worker.post_message(&JsValue::from_str("SKIP_WAITING"));
+}
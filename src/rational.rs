@@ -0,0 +1,235 @@
+// ============================================================================
+// RATIONAL - EXACT FRACTIONS FOR THE COUNTER'S EXACT MODE
+// ============================================================================
+//
+// WHAT: A number stored as a fraction `numerator / denominator`, so "/2" on 3
+//       gives exactly 3/2 (shown as 1.5) instead of truncating to 1.
+//
+// WHY NOT f64? Floats can't store most decimals exactly (0.1 + 0.2 is
+//      0.30000000000000004), so a value saved and loaded, or halved and
+//      doubled back, could drift. Fractions never do.
+//
+// HOW: Every value is kept in lowest terms with a positive denominator, so
+//      equal numbers always have the same representation (`PartialEq` just
+//      compares fields). Arithmetic is checked: `None` instead of overflow.
+//
+// PERSISTENCE: Serialized as a string like "3/2" (or "-7" for whole
+//      numbers) - never as a float - so saving and loading round-trips
+//      exactly. Parsing also accepts decimals like "1.25".
+//
+// LEARNING: `FromStr` + `Display` give us `"3/2".parse()` and `to_string()`
+//           for free; serde then only needs to call them.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// An exact fraction (see the module comment)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Rational {
+    /// Most digits `to_decimal` shows after the point
+    pub const MAX_PRECISION: usize = 12;
+
+    /// `numerator / denominator` in lowest terms (`None` for a zero denominator)
+    pub fn new(numerator: i64, denominator: i64) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator, denominator);
+        let (mut numerator, mut denominator) = (numerator / divisor, denominator / divisor);
+        if denominator < 0 {
+            numerator = numerator.checked_neg()?;
+            denominator = denominator.checked_neg()?;
+        }
+        Some(Self {
+            numerator,
+            denominator,
+        })
+    }
+
+    /// The top of the fraction (carries the sign)
+    pub fn numerator(self) -> i64 {
+        self.numerator
+    }
+
+    /// The bottom of the fraction (always positive)
+    pub fn denominator(self) -> i64 {
+        self.denominator
+    }
+
+    /// Whether there is no fractional part
+    pub fn is_whole(self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The whole part, rounded toward zero like i32 division (saturating)
+    pub fn trunc(self) -> i32 {
+        let whole = self.numerator / self.denominator;
+        whole.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let numerator = self
+            .numerator
+            .checked_mul(other.denominator)?
+            .checked_add(other.numerator.checked_mul(self.denominator)?)?;
+        Self::new(numerator, self.denominator.checked_mul(other.denominator)?)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.numerator.checked_mul(other.numerator)?,
+            self.denominator.checked_mul(other.denominator)?,
+        )
+    }
+
+    /// `None` when dividing by zero or on overflow
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        self.checked_mul(Self::new(other.denominator, other.numerator)?)
+    }
+
+    /// Whether `to_decimal(precision)` shows the value without rounding
+    pub fn is_exact_at(self, precision: usize) -> bool {
+        let scale = 10i128.pow(precision.min(Self::MAX_PRECISION) as u32);
+        (i128::from(self.numerator) * scale) % i128::from(self.denominator) == 0
+    }
+
+    /// The value as a decimal with at most `precision` digits after the point
+    ///
+    /// Rounds half away from zero and drops trailing zeros: 3/2 is "1.5",
+    /// 1/3 is "0.33" at precision 2, and 7 is just "7".
+    pub fn to_decimal(self, precision: usize) -> String {
+        let precision = precision.min(Self::MAX_PRECISION);
+        let scale = 10i128.pow(precision as u32);
+        let (numerator, denominator) = (i128::from(self.numerator), i128::from(self.denominator));
+        // Round |value| * scale to the nearest integer, halves away from zero
+        let scaled = (numerator.abs() * scale * 2 + denominator) / (denominator * 2);
+        let sign = if numerator < 0 && scaled != 0 {
+            "-"
+        } else {
+            ""
+        };
+        let (whole, fraction) = (scaled / scale, scaled % scale);
+        let fraction = format!("{fraction:0precision$}");
+        let fraction = fraction.trim_end_matches('0');
+        match fraction.is_empty() {
+            true => format!("{sign}{whole}"),
+            false => format!("{sign}{whole}.{fraction}"),
+        }
+    }
+}
+
+/// Greatest common divisor (never 0 for a non-zero `b`)
+fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // Only `gcd(i64::MIN, i64::MIN)` doesn't fit; dividing by 1 is still correct
+    i64::try_from(a).unwrap_or(1)
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::from(0)
+    }
+}
+
+impl From<i32> for Rational {
+    fn from(value: i32) -> Self {
+        Self {
+            numerator: i64::from(value),
+            denominator: 1,
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive, so cross-multiplying keeps the order
+        (i128::from(self.numerator) * i128::from(other.denominator))
+            .cmp(&(i128::from(other.numerator) * i128::from(self.denominator)))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// "3/2", or "-7" for whole numbers (exact; what persistence stores)
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            denominator => write!(f, "{}/{denominator}", self.numerator),
+        }
+    }
+}
+
+/// Why a string isn't a `Rational`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRationalError(String);
+
+impl fmt::Display for ParseRationalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not an exact number: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+impl FromStr for Rational {
+    type Err = ParseRationalError;
+
+    /// Accepts "3/2", "-7" and decimals like "1.25"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseRationalError(s.to_string());
+        let s = s.trim();
+        let parse = |part: &str| part.trim().parse::<i64>().map_err(|_| error());
+        if let Some((numerator, denominator)) = s.split_once('/') {
+            return Self::new(parse(numerator)?, parse(denominator)?).ok_or_else(error);
+        }
+        if let Some((whole, fraction)) = s.split_once('.') {
+            if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(error());
+            }
+            let scale = 10i64.checked_pow(fraction.len() as u32).ok_or_else(error)?;
+            let (negative, whole) = match whole.strip_prefix('-') {
+                Some(whole) => (true, whole),
+                None => (false, whole),
+            };
+            if whole.starts_with(['-', '+']) {
+                return Err(error());
+            }
+            let magnitude = parse(whole)?
+                .checked_mul(scale)
+                .and_then(|whole| whole.checked_add(parse(fraction).ok()?))
+                .ok_or_else(error)?;
+            let numerator = if negative { -magnitude } else { magnitude };
+            return Self::new(numerator, scale).ok_or_else(error);
+        }
+        Self::new(parse(s)?, 1).ok_or_else(error)
+    }
+}
+
+impl Serialize for Rational {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rational {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
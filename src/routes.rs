@@ -0,0 +1,400 @@
+// ============================================================================
+// API ROUTES - THE SERVER SIDE OF THE REST API AND LIVE SYNC (`ssr` only)
+// ============================================================================
+//
+// WHAT: The axum handlers behind `/api/counters` (see src/api.rs for the
+//       endpoints) and the `/api/ws` live-sync WebSocket (see src/sync.rs
+//       for the protocol).
+//
+// WHY A LIBRARY MODULE: The `server` binary only adds page rendering and
+//                       `main`. Keeping the routes here lets tests drive
+//                       them in-process against a temporary `FileStore`,
+//                       without opening a port:
+//
+//     let app = api_routes(AppState::new(FileStore::open(path)?));
+//     let response = app.oneshot(request).await?;
+
+use crate::api::{AppendOperation, CounterRecord, CounterUpdate, NewCounter, NewReplica};
+use crate::crdt::CrdtCounter;
+use crate::operation::Operation;
+use crate::store::FileStore;
+use crate::sync::{ClientMessage, ServerMessage};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        FromRef, Path, State,
+    },
+    http::StatusCode,
+    response::Response,
+    routing::{get, post},
+    Json, Router,
+};
+use futures::{SinkExt, StreamExt};
+use std::collections::HashSet;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+use tokio::sync::broadcast;
+
+/// The store is shared by every request handler, so it lives behind a Mutex
+type SharedStore = Arc<Mutex<FileStore>>;
+
+/// A change the store has made, broadcast to every WebSocket session
+#[derive(Clone, Debug)]
+enum Event {
+    /// An operation was applied
+    Applied {
+        counter_id: u64,
+        op: Operation,
+        value: i32,
+        /// `(session id, seq)` when it came from a WebSocket, `None` for REST
+        origin: Option<(u64, u64)>,
+    },
+    /// The value changed some other way (e.g. a CRDT merge)
+    Changed { counter_id: u64, value: i32 },
+}
+
+impl Event {
+    fn counter_id(&self) -> u64 {
+        match self {
+            Event::Applied { counter_id, .. } | Event::Changed { counter_id, .. } => *counter_id,
+        }
+    }
+}
+
+/// Everything the handlers share
+#[derive(Clone)]
+pub struct AppState {
+    store: SharedStore,
+    events: broadcast::Sender<Event>,
+    next_session: Arc<AtomicU64>,
+}
+
+impl AppState {
+    /// Serve the counters kept in `store`
+    pub fn new(store: FileStore) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            events: broadcast::channel(256).0,
+            next_session: Arc::new(AtomicU64::new(1)),
+        }
+    }
+}
+
+/// Lets REST handlers that only need the store ask for `State<SharedStore>`
+impl FromRef<AppState> for SharedStore {
+    fn from_ref(state: &AppState) -> Self {
+        state.store.clone()
+    }
+}
+
+/// What a handler returns: JSON on success, a bare status code on failure
+type ApiResult<T> = Result<T, StatusCode>;
+
+// ----------------------------------------------------------------------------
+// REST API HANDLERS
+// ----------------------------------------------------------------------------
+//
+// Each handler locks the store, does one thing, and maps "no such counter"
+// to 404 and disk errors to 500.
+
+/// A failed write to disk is logged and reported as 500
+fn internal_error(err: std::io::Error) -> StatusCode {
+    eprintln!("store error: {err}");
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// Lock the store (a panicked handler must not take the whole API down)
+fn lock(store: &SharedStore) -> std::sync::MutexGuard<'_, FileStore> {
+    store
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Run `change` on the store in tokio's blocking pool
+///
+/// Every change rewrites the file with blocking `std::fs` calls, which must
+/// not stall the async workers that serve the other requests.
+async fn write<T: Send + 'static>(
+    store: &SharedStore,
+    change: impl FnOnce(&mut FileStore) -> T + Send + 'static,
+) -> T {
+    let store = Arc::clone(store);
+    tokio::task::spawn_blocking(move || change(&mut lock(&store)))
+        .await
+        .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()))
+}
+
+/// GET /api/counters
+async fn list_counters(State(store): State<SharedStore>) -> Json<Vec<CounterRecord>> {
+    Json(lock(&store).list())
+}
+
+/// POST /api/counters
+async fn create_counter(
+    State(store): State<SharedStore>,
+    Json(new): Json<NewCounter>,
+) -> ApiResult<(StatusCode, Json<CounterRecord>)> {
+    let record = write(&store, |store| store.create(new))
+        .await
+        .map_err(internal_error)?;
+    Ok((StatusCode::CREATED, Json(record)))
+}
+
+/// GET /api/counters/{id}
+async fn get_counter(
+    State(store): State<SharedStore>,
+    Path(id): Path<u64>,
+) -> ApiResult<Json<CounterRecord>> {
+    lock(&store).get(id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+/// PUT /api/counters/{id}
+async fn update_counter(
+    State(store): State<SharedStore>,
+    Path(id): Path<u64>,
+    Json(update): Json<CounterUpdate>,
+) -> ApiResult<Json<CounterRecord>> {
+    write(&store, move |store| store.update(id, update))
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// DELETE /api/counters/{id}
+async fn delete_counter(
+    State(store): State<SharedStore>,
+    Path(id): Path<u64>,
+) -> ApiResult<StatusCode> {
+    match write(&store, move |store| store.delete(id))
+        .await
+        .map_err(internal_error)?
+    {
+        true => Ok(StatusCode::NO_CONTENT),
+        false => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+/// GET /api/counters/{id}/operations
+async fn list_operations(
+    State(store): State<SharedStore>,
+    Path(id): Path<u64>,
+) -> ApiResult<Json<Vec<Operation>>> {
+    lock(&store)
+        .operations(id)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// POST /api/counters/{id}/operations
+///
+/// Also broadcast to WebSocket sessions, so live browsers see REST changes.
+async fn append_operation(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    Json(AppendOperation { op }): Json<AppendOperation>,
+) -> ApiResult<Json<CounterRecord>> {
+    // Send the event while still holding the lock, so events go out in the
+    // same order the store applied them (as `sync_session` does)
+    let events = state.events.clone();
+    let record = write(&state.store, move |store| {
+        let record = store.append(id, op)?;
+        if let Some(record) = &record {
+            // An error only means nobody is listening right now
+            _ = events.send(Event::Applied {
+                counter_id: id,
+                op,
+                value: record.value,
+                origin: None,
+            });
+        }
+        Ok(record)
+    })
+    .await
+    .map_err(internal_error)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(record))
+}
+
+/// GET /api/counters/{id}/crdt
+async fn get_crdt(
+    State(store): State<SharedStore>,
+    Path(id): Path<u64>,
+) -> ApiResult<Json<CrdtCounter>> {
+    lock(&store).crdt(id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+/// POST /api/counters/{id}/crdt - merge a replica's (possibly offline) state
+async fn merge_crdt(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    Json(replica): Json<CrdtCounter>,
+) -> ApiResult<Json<CrdtCounter>> {
+    if !replica.is_valid() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let events = state.events.clone();
+    let merged = write(&state.store, move |store| {
+        let merged = store.merge(id, &replica)?;
+        if let Some(merged) = &merged {
+            _ = events.send(Event::Changed {
+                counter_id: id,
+                value: merged.value(),
+            });
+        }
+        Ok(merged)
+    })
+    .await
+    .map_err(internal_error)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(merged))
+}
+
+/// POST /api/replicas - a fresh id for a browser's CRDT replica
+async fn allocate_replica(
+    State(store): State<SharedStore>,
+) -> ApiResult<(StatusCode, Json<NewReplica>)> {
+    let replica = write(&store, FileStore::allocate_replica)
+        .await
+        .map_err(internal_error)?;
+    Ok((StatusCode::CREATED, Json(NewReplica { replica })))
+}
+
+// ----------------------------------------------------------------------------
+// LIVE SYNC (WebSocket)
+// ----------------------------------------------------------------------------
+//
+// Each browser gets its own session task. The task listens to two things
+// at once (`tokio::select!`): messages from its browser, and operations
+// broadcast by every other session (or the REST API).
+
+/// GET /api/ws - upgrade to a WebSocket and run a sync session
+async fn ws_upgrade(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    ws.on_upgrade(move |socket| sync_session(socket, state))
+}
+
+/// Serialize and send one message; `false` once the browser has gone away
+async fn send(
+    socket: &mut futures::stream::SplitSink<WebSocket, Message>,
+    message: &ServerMessage,
+) -> bool {
+    let json = serde_json::to_string(message).expect("server messages always serialize");
+    socket.send(Message::Text(json.into())).await.is_ok()
+}
+
+/// The current value of a counter as a `Snapshot`, if it exists
+fn snapshot(store: &SharedStore, counter_id: u64) -> Option<ServerMessage> {
+    let record = lock(store).get(counter_id)?;
+    Some(ServerMessage::Snapshot {
+        counter_id,
+        value: record.value,
+    })
+}
+
+/// Handle one browser's messages and forward broadcasts it subscribed to
+async fn sync_session(socket: WebSocket, state: AppState) {
+    let session = state.next_session.fetch_add(1, Ordering::Relaxed);
+    let (mut outgoing, mut incoming) = socket.split();
+    let mut events = state.events.subscribe();
+    let mut subscribed = HashSet::new();
+
+    loop {
+        tokio::select! {
+            message = incoming.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                let Ok(message) = serde_json::from_str::<ClientMessage>(&text) else {
+                    continue;
+                };
+                let reply = match message {
+                    ClientMessage::Subscribe { counter_id } => {
+                        subscribed.insert(counter_id);
+                        snapshot(&state.store, counter_id)
+                    }
+                    ClientMessage::Apply { counter_id, op, seq } => {
+                        let events = state.events.clone();
+                        let applied = write(&state.store, move |store| {
+                            let record = store.append(counter_id, op)?;
+                            if let Some(record) = &record {
+                                // Our own copy comes back through `events` with the ack
+                                _ = events.send(Event::Applied {
+                                    counter_id,
+                                    op,
+                                    value: record.value,
+                                    origin: Some((session, seq)),
+                                });
+                            }
+                            Ok::<_, std::io::Error>(record)
+                        });
+                        match applied.await {
+                            Ok(Some(_)) => None,
+                            Ok(None) => Some(ServerMessage::Rejected {
+                                counter_id,
+                                seq,
+                                reason: format!("counter {counter_id} does not exist"),
+                            }),
+                            Err(err) => Some(ServerMessage::Rejected {
+                                counter_id,
+                                seq,
+                                reason: format!("could not save: {err}"),
+                            }),
+                        }
+                    }
+                };
+                if let Some(reply) = reply {
+                    if !send(&mut outgoing, &reply).await {
+                        break;
+                    }
+                }
+            }
+            event = events.recv() => {
+                let messages = match event {
+                    Ok(event) if !subscribed.contains(&event.counter_id()) => continue,
+                    Ok(Event::Applied { counter_id, op, value, origin }) => {
+                        let ack = origin
+                            .filter(|(from, _)| *from == session)
+                            .map(|(_, seq)| seq);
+                        vec![ServerMessage::Applied { counter_id, op, value, ack }]
+                    }
+                    Ok(Event::Changed { counter_id, value }) => {
+                        vec![ServerMessage::Snapshot { counter_id, value }]
+                    }
+                    // We fell behind and missed events: resend fresh snapshots
+                    Err(broadcast::error::RecvError::Lagged(_)) => subscribed
+                        .iter()
+                        .filter_map(|id| snapshot(&state.store, *id))
+                        .collect(),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                for message in &messages {
+                    if !send(&mut outgoing, message).await {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// All `/api` routes
+pub fn api_routes(state: AppState) -> Router {
+    Router::new()
+        .route("/api/ws", get(ws_upgrade))
+        .route("/api/counters", get(list_counters).post(create_counter))
+        .route(
+            "/api/counters/{id}",
+            get(get_counter).put(update_counter).delete(delete_counter),
+        )
+        .route(
+            "/api/counters/{id}/operations",
+            get(list_operations).post(append_operation),
+        )
+        .route("/api/counters/{id}/crdt", get(get_crdt).post(merge_crdt))
+        .route("/api/replicas", post(allocate_replica))
+        .with_state(state)
+}
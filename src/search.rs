@@ -0,0 +1,159 @@
+// ============================================================================
+// SEARCH - FINDING COUNTERS ON A BOARD
+// ============================================================================
+//
+// WHAT: The query typed into the board's search bar. It is split into
+//       words, and a counter is shown only if it matches EVERY word:
+//
+//           door            name or a tag contains "door" (any case)
+//           #kitchen        has the tag "kitchen"
+//           >50  <=0  =7    the count compared with a number
+//           10..20          the count is between 10 and 20 (inclusive)
+//           band:high       the count is in a `CounterMessage` band
+//                           (high, normal, zero, negative - see src/band.rs)
+//
+//       `door >50` finds the doors with more than 50.
+//
+// WHY PARSE FIRST? The query is parsed once into `Term`s when it is typed,
+//      not again for every counter. A mistake like `>abc` becomes a
+//      `SearchError` shown under the bar instead of silently matching nothing.
+//
+// LEARNING: `strip_prefix` returns `Some(rest)` only when the prefix is
+//           there, which makes a tidy chain of `if let`s for each syntax.
+
+use crate::band::Band;
+use std::fmt;
+use std::str::FromStr;
+
+/// How a count is compared with a number
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+/// One word of a query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Term {
+    /// Part of the name or of a tag (lowercase)
+    Text(String),
+    /// A whole tag (lowercase, without `#`)
+    Tag(String),
+    Compare(Comparison, i32),
+    /// Inclusive range
+    Range(i32, i32),
+    Band(Band),
+}
+
+impl Term {
+    /// Whether a counter called `name`, with `tags`, counting `value` matches
+    pub fn matches(&self, name: &str, tags: &[String], value: i32) -> bool {
+        match self {
+            Term::Text(text) => {
+                name.to_lowercase().contains(text)
+                    || tags.iter().any(|tag| tag.to_lowercase().contains(text))
+            }
+            Term::Tag(wanted) => tags.iter().any(|tag| tag.to_lowercase() == *wanted),
+            Term::Compare(comparison, n) => match comparison {
+                Comparison::Less => value < *n,
+                Comparison::LessOrEqual => value <= *n,
+                Comparison::Equal => value == *n,
+                Comparison::GreaterOrEqual => value >= *n,
+                Comparison::Greater => value > *n,
+            },
+            Term::Range(low, high) => (*low..=*high).contains(&value),
+            Term::Band(band) => Band::of(value) == *band,
+        }
+    }
+}
+
+/// Why a query couldn't be understood
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchError {
+    /// A comparison or range without a valid whole number, like `>abc`
+    InvalidNumber(String),
+    /// `band:` followed by something other than a band name
+    UnknownBand(String),
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::InvalidNumber(term) => write!(f, "`{term}` needs a whole number"),
+            SearchError::UnknownBand(band) => write!(
+                f,
+                "unknown band `{band}` (try high, normal, zero or negative)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+/// A parsed search (see the module comment); empty matches everything
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    pub terms: Vec<Term>,
+}
+
+impl Query {
+    /// Whether the query has no terms (matches every counter)
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether a counter matches every term
+    pub fn matches(&self, name: &str, tags: &[String], value: i32) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.matches(name, tags, value))
+    }
+}
+
+impl FromStr for Query {
+    type Err = SearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_whitespace()
+            .map(parse_term)
+            .collect::<Result<_, _>>()
+            .map(|terms| Query { terms })
+    }
+}
+
+fn parse_term(word: &str) -> Result<Term, SearchError> {
+    let number = |n: &str| {
+        n.parse::<i32>()
+            .map_err(|_| SearchError::InvalidNumber(word.to_string()))
+    };
+    // Longest operators first, so `>=5` isn't read as `>` and `=5`
+    let comparisons = [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        ("=", Comparison::Equal),
+    ];
+    for (operator, comparison) in comparisons {
+        if let Some(n) = word.strip_prefix(operator) {
+            return Ok(Term::Compare(comparison, number(n)?));
+        }
+    }
+    if let Some(band) = word.strip_prefix("band:") {
+        return band
+            .parse()
+            .map(Term::Band)
+            .map_err(SearchError::UnknownBand);
+    }
+    if let Some(tag) = word.strip_prefix('#') {
+        return Ok(Term::Tag(tag.to_lowercase()));
+    }
+    // `-5..5`: split at the `..`, so a leading minus stays with its number
+    if let Some((low, high)) = word.split_once("..") {
+        return Ok(Term::Range(number(low)?, number(high)?));
+    }
+    Ok(Term::Text(word.to_lowercase()))
+}
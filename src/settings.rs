@@ -0,0 +1,134 @@
+// ============================================================================
+// SETTINGS - PREFERENCES THAT SURVIVE A RELOAD
+// ============================================================================
+//
+// WHAT: The user's preferences for the whole page: whether it makes sounds,
+//       and how loud (see `SoundControls` in src/lib.rs and src/sound.rs),
+//       and which thresholds raise a desktop notification (see
+//       `NotificationControls` and src/notify.rs).
+//
+// WHY ONE SIGNAL FOR THE PAGE? Muting is global: one click must silence the
+//      main counter and every counter on the board. `App` creates a single
+//      `RwSignal<Settings>` and shares it through Leptos' CONTEXT, so any
+//      component below it can `use_settings()` without the signal being
+//      passed down as a prop through every layer in between.
+//
+// HOW: The settings are kept as JSON in the browser's `localStorage` under
+//      `STORAGE_KEY`. Reading them happens in an `Effect` (browser-only, like
+//      every effect), so server rendering always uses the defaults. Unknown or
+//      broken JSON falls back to the defaults instead of failing - a settings
+//      file written by an older version of the app still loads.
+//
+// LEARNING: `#[serde(default)]` on the struct fills every missing field from
+//           `Default`, so adding a setting later never breaks stored ones.
+
+use crate::band::Band;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The page's preferences (see the module comment)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Silence every sound, the countdown alarm included
+    pub muted: bool,
+    /// How loud sounds are, from 0.0 (silent) to 1.0
+    pub volume: f32,
+    /// Play a tone for every operation and band change (the alarm rings
+    /// either way)
+    pub sound_effects: bool,
+    /// The bands whose crossing raises a notification (none until opted in)
+    pub notify: Vec<Band>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            muted: false,
+            volume: 0.7,
+            // Optional: a page that beeps at every click should be asked for
+            sound_effects: false,
+            notify: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Where the settings are kept in `localStorage`
+    pub const STORAGE_KEY: &str = "webtest-settings";
+
+    /// How loud the countdown alarm plays (0.0 when muted)
+    pub fn alarm_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume.clamp(0.0, 1.0)
+        }
+    }
+
+    /// How loud sound effects play (0.0 when muted or turned off)
+    pub fn effects_volume(&self) -> f32 {
+        if self.sound_effects {
+            self.alarm_volume()
+        } else {
+            0.0
+        }
+    }
+
+    /// Whether moving into `band` raises a notification
+    pub fn notifies(&self, band: Band) -> bool {
+        self.notify.contains(&band)
+    }
+
+    /// Turn the notification for `band` on or off
+    pub fn set_notify(&mut self, band: Band, on: bool) {
+        self.notify.retain(|b| *b != band);
+        if on {
+            self.notify.push(band);
+        }
+    }
+
+    /// Settings from stored JSON (the defaults if it can't be read)
+    pub fn from_json(json: &str) -> Self {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    /// The JSON `from_json` reads
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("settings are always valid JSON")
+    }
+
+    /// The stored settings (the defaults outside a browser, or if none)
+    pub fn load() -> Self {
+        storage()
+            .and_then(|storage| storage.get_item(Self::STORAGE_KEY).ok().flatten())
+            .map_or_else(Self::default, |json| Self::from_json(&json))
+    }
+
+    /// Store these settings; ignored where storage is unavailable (some
+    /// private browsing modes)
+    pub fn save(&self) {
+        if let Some(storage) = storage() {
+            let _ = storage.set_item(Self::STORAGE_KEY, &self.to_json());
+        }
+    }
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+/// Create the page's settings signal, load the stored settings into it (in
+/// the browser), and share it with every component below
+pub fn provide_settings() -> RwSignal<Settings> {
+    let settings = RwSignal::new(Settings::default());
+    Effect::new(move |_| settings.set(Settings::load()));
+    provide_context(settings);
+    settings
+}
+
+/// The settings `App` provides - or, for a counter used on its own (like
+/// `<evil-counter>`), its own copy loaded from the same storage
+pub fn use_settings() -> RwSignal<Settings> {
+    use_context().unwrap_or_else(provide_settings)
+}
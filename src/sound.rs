@@ -0,0 +1,164 @@
+// ============================================================================
+// SOUND - SHORT BEEPS WITH THE WEB AUDIO API
+// ============================================================================
+//
+// WHAT: Every sound the counter makes:
+//
+//       - the alarm the countdown plays when it reaches its target
+//       - (optional) a short tone for each operation - high for "+", low
+//         for "-", rising for "*2", falling for "/2" - and a little tune when
+//         the count moves into another band (see src/band.rs)
+//
+//       Whether they play, and how loud, is up to the user's `Settings`
+//       (src/settings.rs): sound effects are off until turned on, and
+//       "mute" silences everything.
+//
+// WHY WEB AUDIO? No sound files to download: an `OscillatorNode` generates a
+//      tone, and a `GainNode` in front of the speakers fades it in and out so
+//      it doesn't click.
+//
+// HOW: oscillator -> gain -> speakers (`destination`). Both nodes are told
+//      WHEN to start and stop on the audio clock (`current_time`, in seconds),
+//      so a whole beep pattern is scheduled at once and plays on its own.
+//      `Sound::notes` (plain Rust, checked by tests/sound.rs) says which
+//      tones make up each sound; `play` schedules them.
+//
+// LEARNING: Browsers only allow sound after the user has interacted with the
+//           page. An `AudioContext` created before that starts "suspended";
+//           `resume()` wakes it up once a click has happened. Every error is
+//           ignored - a missing beep must never break the counter.
+
+use crate::band::Band;
+use crate::operation::Operation;
+use web_sys::{AudioContext, OscillatorType};
+
+/// Loudest a sound gets (at volume 1.0); square waves are loud
+const MAX_GAIN: f32 = 0.3;
+
+/// One tone of a sound
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Note {
+    /// Pitch in Hz
+    pub frequency: f32,
+    /// When it starts, in seconds after the sound starts
+    pub delay: f64,
+    /// How long it lasts, in seconds
+    pub duration: f64,
+}
+
+/// Something worth hearing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sound {
+    /// An operation was applied
+    Operation(Operation),
+    /// The count moved into this band
+    Band(Band),
+    /// The countdown reached its target
+    Alarm,
+}
+
+/// `frequencies` played one after another, each `duration` seconds long
+fn melody(frequencies: &[f32], duration: f64) -> Vec<Note> {
+    frequencies
+        .iter()
+        .enumerate()
+        .map(|(i, &frequency)| Note {
+            frequency,
+            delay: i as f64 * duration,
+            duration,
+        })
+        .collect()
+}
+
+impl Sound {
+    /// The tones that make up this sound, in order
+    pub fn notes(self) -> Vec<Note> {
+        // Short enough that a held "+1" repeating quickly stays a patter
+        const TICK: f64 = 0.05;
+        match self {
+            Sound::Operation(op) => match op {
+                Operation::Increment => melody(&[660.0], TICK),
+                Operation::Add(n) if n > 0 => melody(&[660.0], TICK),
+                Operation::Decrement | Operation::Add(_) => melody(&[440.0], TICK),
+                Operation::Reset => melody(&[330.0, 220.0], TICK),
+                Operation::Double => melody(&[523.0, 784.0], TICK),
+                Operation::Halve => melody(&[784.0, 523.0], TICK),
+                Operation::Set(_) => melody(&[587.0], TICK),
+            },
+            // Up the scale into HIGH, down into NEGATIVE
+            Sound::Band(band) => match band {
+                Band::High => melody(&[523.0, 659.0, 784.0, 1047.0], 0.08),
+                Band::Normal => melody(&[523.0, 659.0], 0.08),
+                Band::Zero => melody(&[392.0], 0.15),
+                Band::Negative => melody(&[392.0, 330.0, 262.0], 0.08),
+            },
+            // Three short high beeps
+            Sound::Alarm => (0..3)
+                .map(|i| Note {
+                    frequency: 880.0,
+                    delay: f64::from(i) * 0.25,
+                    duration: 0.15,
+                })
+                .collect(),
+        }
+    }
+
+    /// The alarm is harsh on purpose; effects are softer
+    fn wave(self) -> OscillatorType {
+        match self {
+            Sound::Alarm => OscillatorType::Square,
+            _ => OscillatorType::Triangle,
+        }
+    }
+}
+
+/// One context for the whole page (browsers limit how many can exist)
+fn context() -> Option<AudioContext> {
+    thread_local! {
+        static CONTEXT: Option<AudioContext> = AudioContext::new().ok();
+    }
+    CONTEXT.with(Clone::clone)
+}
+
+/// Schedule one note, `gain` loud, with `wave` as its timbre
+fn beep(context: &AudioContext, note: Note, wave: OscillatorType, gain: f32) -> Option<()> {
+    let oscillator = context.create_oscillator().ok()?;
+    let gain_node = context.create_gain().ok()?;
+    oscillator.set_type(wave);
+    oscillator.frequency().set_value(note.frequency);
+
+    // Quick fade in, hold, quick fade out
+    let start = context.current_time() + note.delay;
+    let volume = gain_node.gain();
+    volume.set_value_at_time(0.0, start).ok()?;
+    volume
+        .linear_ramp_to_value_at_time(gain, start + 0.01)
+        .ok()?;
+    volume
+        .linear_ramp_to_value_at_time(0.0, start + note.duration)
+        .ok()?;
+
+    oscillator.connect_with_audio_node(&gain_node).ok()?;
+    gain_node
+        .connect_with_audio_node(&context.destination())
+        .ok()?;
+    oscillator.start_with_when(start).ok()?;
+    oscillator.stop_with_when(start + note.duration).ok()
+}
+
+/// Play `sound` at `volume` (0.0 to 1.0; 0.0 plays nothing)
+pub fn play(sound: Sound, volume: f32) {
+    if volume <= 0.0 {
+        return;
+    }
+    let Some(context) = context() else {
+        return;
+    };
+    if context.state() == web_sys::AudioContextState::Suspended {
+        let _ = context.resume();
+    }
+    let gain = MAX_GAIN * volume.min(1.0);
+    for note in sound.notes() {
+        beep(&context, note, sound.wave(), gain);
+    }
+}
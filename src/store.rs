@@ -0,0 +1,297 @@
+// ============================================================================
+// FILE STORE - WHERE THE SERVER KEEPS COUNTERS (`ssr` feature only)
+// ============================================================================
+//
+// WHAT: Keeps every counter, and the log of operations applied to it, in a
+//       single JSON file on disk.
+//
+// WHY A FILE: No database to install; `cat counters.json` shows you the
+//             whole state. Every change rewrites the file, which is plenty
+//             fast for a handful of team counters.
+//
+// HOW: The data lives in memory and is written to a temporary file that is
+//      then renamed over the real one, so a crash mid-write never leaves a
+//      half-written (corrupt) file behind. Every change is made to a copy
+//      first (of just the one counter, for a click) and only kept once it
+//      is on disk, so a failed write never leaves memory and the file
+//      disagreeing. The store itself is synchronous: src/routes.rs calls
+//      the methods that write from tokio's blocking pool.
+//
+// The operation log only keeps each counter's latest `MAX_OPERATIONS`, so
+// the file stops growing with every click; the value lives in the CRDT.
+//
+// Each counter's value is backed by a `CrdtCounter` (see src/crdt.rs), so
+// states edited offline on other devices can be merged in without losing
+// or double-counting clicks. The server edits as `SERVER_REPLICA`; every
+// browser gets its own replica id from `allocate_replica`.
+//
+// Exact counters (see src/rational.rs) also keep `record.exact`: operations
+// are applied to it exactly, and the CRDT follows with the whole part. The
+// file stores it as a string like "3/2", so it round-trips exactly.
+
+use crate::api::{CounterRecord, CounterUpdate, NewCounter};
+use crate::crdt::{CrdtCounter, ReplicaId, SERVER_REPLICA};
+use crate::operation::Operation;
+use crate::rational::Rational;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How many operations each counter's log keeps (the oldest are dropped)
+pub const MAX_OPERATIONS: usize = 1000;
+
+/// One counter plus its operation log, as saved in the JSON file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredCounter {
+    #[serde(flatten)]
+    record: CounterRecord,
+    #[serde(default)]
+    operations: Vec<Operation>,
+    /// Missing in files written before CRDT support; filled in by `open`
+    #[serde(default)]
+    crdt: Option<CrdtCounter>,
+}
+
+impl StoredCounter {
+    /// The CRDT state, creating it from the plain value for old files
+    fn crdt_mut(&mut self) -> &mut CrdtCounter {
+        let value = self.record.value;
+        self.crdt.get_or_insert_with(|| crdt_with_value(value))
+    }
+
+    /// Copy the CRDT's value into the record after a change
+    ///
+    /// An exact value whose whole part no longer matches (another replica
+    /// changed the count) is replaced by the new whole value.
+    fn sync_value(&mut self) {
+        let value = self.crdt_mut().value();
+        self.record.value = value;
+        if let Some(exact) = &mut self.record.exact {
+            if exact.trunc() != value {
+                *exact = value.into();
+            }
+        }
+    }
+
+    /// Set an exact counter to `exact` (and the CRDT to its whole part)
+    fn set_exact(&mut self, exact: Rational) {
+        self.record.exact = Some(exact);
+        if self.crdt_mut().value() != exact.trunc() {
+            self.crdt_mut().set(SERVER_REPLICA, exact.trunc());
+        }
+        self.sync_value();
+    }
+}
+
+/// A fresh CRDT holding `value`
+fn crdt_with_value(value: i32) -> CrdtCounter {
+    let mut crdt = CrdtCounter::new();
+    if value != 0 {
+        crdt.set(SERVER_REPLICA, value);
+    }
+    crdt
+}
+
+/// Everything in the JSON file
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct StoreData {
+    next_id: u64,
+    /// The last replica id handed out (missing in files written before)
+    #[serde(default)]
+    next_replica: ReplicaId,
+    counters: Vec<StoredCounter>,
+}
+
+impl StoreData {
+    fn find(&self, id: u64) -> Option<&StoredCounter> {
+        self.counters.iter().find(|c| c.record.id == id)
+    }
+}
+
+/// A JSON-file-backed collection of counters
+#[derive(Debug)]
+pub struct FileStore {
+    path: PathBuf,
+    data: StoreData,
+}
+
+impl FileStore {
+    /// Load the store from `path`, starting empty when the file doesn't exist
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut data: StoreData = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => StoreData::default(),
+            Err(e) => return Err(e),
+        };
+        for counter in &mut data.counters {
+            counter.crdt_mut();
+        }
+        Ok(Self { path, data })
+    }
+
+    /// All counters, oldest first
+    pub fn list(&self) -> Vec<CounterRecord> {
+        self.data
+            .counters
+            .iter()
+            .map(|c| c.record.clone())
+            .collect()
+    }
+
+    /// One counter by id
+    pub fn get(&self, id: u64) -> Option<CounterRecord> {
+        self.find(id).map(|c| c.record.clone())
+    }
+
+    /// The latest operations applied to a counter (at most
+    /// `MAX_OPERATIONS`), oldest first
+    pub fn operations(&self, id: u64) -> Option<Vec<Operation>> {
+        self.find(id).map(|c| c.operations.clone())
+    }
+
+    /// A counter's CRDT state, for replicas that want to merge with it
+    pub fn crdt(&self, id: u64) -> Option<CrdtCounter> {
+        self.find(id).and_then(|c| c.crdt.clone())
+    }
+
+    /// Add a new counter and return it with its freshly assigned id
+    pub fn create(&mut self, new: NewCounter) -> io::Result<CounterRecord> {
+        self.commit(|data| {
+            data.next_id += 1;
+            let record = CounterRecord {
+                id: data.next_id,
+                name: new.name,
+                value: new.value,
+                exact: new.exact.then(|| new.value.into()),
+            };
+            data.counters.push(StoredCounter {
+                record: record.clone(),
+                operations: Vec::new(),
+                crdt: Some(crdt_with_value(new.value)),
+            });
+            record
+        })
+    }
+
+    /// Rename a counter and/or overwrite its value
+    ///
+    /// Returns `Ok(None)` when there is no counter with that id.
+    pub fn update(&mut self, id: u64, update: CounterUpdate) -> io::Result<Option<CounterRecord>> {
+        self.commit_counter(id, |counter| {
+            if let Some(name) = update.name {
+                counter.record.name = name;
+            }
+            match (update.exact, update.value) {
+                (Some(exact), _) if counter.record.exact.is_some() => counter.set_exact(exact),
+                (_, Some(value)) if counter.record.exact.is_some() => {
+                    counter.set_exact(value.into())
+                }
+                (_, Some(value)) => {
+                    counter.crdt_mut().set(SERVER_REPLICA, value);
+                    counter.sync_value();
+                }
+                _ => {}
+            }
+            counter.record.clone()
+        })
+    }
+
+    /// Remove a counter; returns `Ok(false)` when it didn't exist
+    pub fn delete(&mut self, id: u64) -> io::Result<bool> {
+        if self.data.find(id).is_none() {
+            return Ok(false);
+        }
+        self.commit(|data| data.counters.retain(|c| c.record.id != id))?;
+        Ok(true)
+    }
+
+    /// Apply `op` to a counter, log it, and return the updated counter
+    ///
+    /// Returns `Ok(None)` when there is no counter with that id.
+    pub fn append(&mut self, id: u64, op: Operation) -> io::Result<Option<CounterRecord>> {
+        self.commit_counter(id, |counter| {
+            let exact = counter.record.exact.and_then(|exact| op.apply_exact(exact));
+            counter.crdt_mut().apply(SERVER_REPLICA, op);
+            match exact {
+                // The CRDT only needs a correction when the whole parts differ
+                // ("/2" then "*2" on 3 gives 3 exactly, but 2 in whole numbers)
+                Some(next) => counter.set_exact(next),
+                // Plain counters, and fractions too big to represent
+                None => counter.sync_value(),
+            }
+            counter.operations.push(op);
+            let dropped = counter.operations.len().saturating_sub(MAX_OPERATIONS);
+            counter.operations.drain(..dropped);
+            counter.record.clone()
+        })
+    }
+
+    /// Merge another replica's CRDT state into a counter
+    ///
+    /// Returns the merged state (which the replica should adopt), or
+    /// `Ok(None)` when there is no counter with that id.
+    pub fn merge(&mut self, id: u64, other: &CrdtCounter) -> io::Result<Option<CrdtCounter>> {
+        self.commit_counter(id, |counter| {
+            counter.crdt_mut().merge(other);
+            counter.sync_value();
+            counter.crdt_mut().clone()
+        })
+    }
+
+    /// A replica id no browser has used before (never `SERVER_REPLICA`)
+    ///
+    /// Saved like any other change, so ids stay unique across restarts.
+    pub fn allocate_replica(&mut self) -> io::Result<ReplicaId> {
+        self.commit(|data| {
+            // Starts at 0 = `SERVER_REPLICA`, so the first browser gets 1
+            data.next_replica += 1;
+            data.next_replica
+        })
+    }
+
+    fn find(&self, id: u64) -> Option<&StoredCounter> {
+        self.data.find(id)
+    }
+
+    /// Make `change` to a copy of the data and keep it only once it is saved
+    fn commit<T>(&mut self, change: impl FnOnce(&mut StoreData) -> T) -> io::Result<T> {
+        let mut data = self.data.clone();
+        let result = change(&mut data);
+        self.save(&data)?;
+        self.data = data;
+        Ok(result)
+    }
+
+    /// `commit` a change to one counter; `Ok(None)` when it doesn't exist
+    ///
+    /// Only that counter is copied, not the whole store.
+    fn commit_counter<T>(
+        &mut self,
+        id: u64,
+        change: impl FnOnce(&mut StoredCounter) -> T,
+    ) -> io::Result<Option<T>> {
+        let Some(index) = self.data.counters.iter().position(|c| c.record.id == id) else {
+            return Ok(None);
+        };
+        let mut counter = self.data.counters[index].clone();
+        let result = change(&mut counter);
+        let old = std::mem::replace(&mut self.data.counters[index], counter);
+        if let Err(err) = self.save(&self.data) {
+            self.data.counters[index] = old;
+            return Err(err);
+        }
+        Ok(Some(result))
+    }
+
+    /// Write to `<path>.tmp`, then atomically rename it over `<path>`
+    fn save(&self, data: &StoreData) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
@@ -0,0 +1,616 @@
+// ============================================================================
+// LIVE SYNC - SHARING ONE COUNTER BETWEEN MANY BROWSERS
+// ============================================================================
+//
+// WHAT: Keeps a counter in step across every browser that has it open
+//       (e.g. two people counting attendees at two doors).
+//
+// HOW IT WORKS:
+// 1. The browser opens a WebSocket to `/api/ws` on the `server` binary and
+//    subscribes to one counter; the server answers with a `Snapshot`.
+// 2. When you click a button, the new value is shown IMMEDIATELY
+//    ("optimistic update") and the operation is sent to the server.
+// 3. The server applies it, saves it, and broadcasts `Applied` with its
+//    authoritative value to EVERY subscribed browser.
+// 4. Each browser "reconciles": it takes the server's value and replays
+//    the operations it sent that the server hasn't confirmed yet.
+//
+// If the WebSocket can't be opened (or drops), operations fall back to
+// REST. Each tab then edits its own CRDT replica (see src/crdt.rs) under a
+// replica id the server allocated, and sends the whole state to be merged:
+//
+// 5. A click is recorded on the tab's `Replica` and merged with
+//    `POST /api/counters/{id}/crdt`; the tab adopts the merged state.
+// 6. OFFLINE, the merge fails: the click stays on screen (still pending)
+//    and the replica is saved to `localStorage`.
+// 7. Back online (the next click, the browser's `online` event, or the next
+//    visit) the saved replica is merged. Merging is idempotent, so a click
+//    is counted exactly once however often its state is sent.
+//
+// EXACT COUNTERS (see src/rational.rs) don't take part: the CRDT and the
+// WebSocket only carry whole numbers. An `ExactLink` loads the exact value
+// when the page opens and saves every change with `PUT`, so a reload (or
+// another browser's next visit) gets the fraction back.
+//
+// LEARNING: Optimistic updates make the UI feel instant; reconciliation
+//           makes sure everyone still ends up with the SAME number.
+
+use crate::api::{self, ApiConfig, ApiError, CounterUpdate};
+use crate::crdt::{CrdtCounter, ReplicaId, SERVER_REPLICA};
+use crate::operation::Operation;
+use crate::rational::Rational;
+use futures::future::{AbortHandle, Abortable};
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use gloo_net::websocket::{futures::WebSocket, Message};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Browser -> server
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Start receiving updates for a counter (answered with `Snapshot`)
+    Subscribe { counter_id: u64 },
+    /// Apply an operation; `seq` is echoed back as `ack` to the sender
+    Apply {
+        counter_id: u64,
+        op: Operation,
+        seq: u64,
+    },
+}
+
+/// Server -> browser
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// The counter's current value (sent after `Subscribe`)
+    Snapshot { counter_id: u64, value: i32 },
+    /// Someone applied `op`; `value` is the result on the server.
+    /// `ack` is the sender's `seq` - only set in the copy sent to the sender.
+    Applied {
+        counter_id: u64,
+        op: Operation,
+        value: i32,
+        ack: Option<u64>,
+    },
+    /// The server could not apply operation `seq` (e.g. counter deleted)
+    Rejected {
+        counter_id: u64,
+        seq: u64,
+        reason: String,
+    },
+}
+
+/// Combines the server's value with our not-yet-confirmed operations
+///
+/// `confirmed` is the last value the server told us about; `pending` are
+/// operations we've shown locally but the server hasn't acknowledged.
+/// The value to display is `confirmed` with `pending` replayed on top.
+///
+/// REST responses can arrive out of order, so an ack older than one already
+/// applied is ignored: its value is staler than `confirmed`.
+#[derive(Clone, Debug, Default)]
+pub struct Reconciler {
+    confirmed: i32,
+    pending: VecDeque<(u64, Operation)>,
+    next_seq: u64,
+    /// The highest `seq` the server has acknowledged
+    acked: u64,
+}
+
+impl Reconciler {
+    /// Start from a value the server reported
+    pub fn new(confirmed: i32) -> Self {
+        Self {
+            confirmed,
+            ..Self::default()
+        }
+    }
+
+    /// The value to show: the server's value plus our pending operations
+    pub fn value(&self) -> i32 {
+        self.pending
+            .iter()
+            .fold(self.confirmed, |value, (_, op)| op.apply(value))
+    }
+
+    /// Record an optimistic local operation and return its sequence number
+    pub fn local(&mut self, op: Operation) -> u64 {
+        self.next_seq += 1;
+        self.pending.push_back((self.next_seq, op));
+        self.next_seq
+    }
+
+    /// The server reported its value; `ack` confirms our operations up to that seq
+    pub fn remote(&mut self, value: i32, ack: Option<u64>) {
+        if let Some(seq) = ack {
+            if seq <= self.acked {
+                return;
+            }
+            self.acked = seq;
+            self.pending.retain(|(pending, _)| *pending > seq);
+        }
+        self.confirmed = value;
+    }
+
+    /// The server refused operation `seq`; stop replaying it
+    pub fn reject(&mut self, seq: u64) {
+        self.pending.retain(|(pending, _)| *pending != seq);
+    }
+
+    /// The sequence number of the newest local operation
+    pub fn last_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Start over from a fresh server value, forgetting all pending operations
+    ///
+    /// Used after a disconnect: we can't know which pending operations the
+    /// server applied, so the freshly fetched value is the only safe truth.
+    pub fn resync(&mut self, value: i32) {
+        self.confirmed = value;
+        self.pending.clear();
+    }
+}
+
+/// This tab's copy of the counter's CRDT state (see src/crdt.rs)
+///
+/// Over the WebSocket it only FOLLOWS the server: additive operations are
+/// replayed as the server applied them, anything else means fetching the
+/// server's state. Over REST, clicks are RECORDED under this tab's own
+/// replica id and the whole state is merged on the server.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replica {
+    id: ReplicaId,
+    crdt: CrdtCounter,
+    /// Operations recorded so far
+    recorded: u64,
+    /// How many of them the server has merged
+    merged: u64,
+}
+
+impl Replica {
+    /// Edit `crdt` as replica `id` (allocated by the server, never `SERVER_REPLICA`)
+    pub fn new(id: ReplicaId, crdt: CrdtCounter) -> Self {
+        Self {
+            id,
+            crdt,
+            recorded: 0,
+            merged: 0,
+        }
+    }
+
+    /// The replica id this tab edits under
+    pub fn id(&self) -> ReplicaId {
+        self.id
+    }
+
+    /// The current state
+    pub fn crdt(&self) -> &CrdtCounter {
+        &self.crdt
+    }
+
+    /// Apply a local operation under our own replica id
+    pub fn record(&mut self, op: Operation) {
+        self.crdt.apply(self.id, op);
+        self.recorded += 1;
+    }
+
+    /// The state to send for merging, and the mark to pass to `adopt` once merged
+    pub fn outgoing(&self) -> (CrdtCounter, u64) {
+        (self.crdt.clone(), self.recorded)
+    }
+
+    /// The server merged the state from `outgoing` (with mark `sent`) into `merged`
+    ///
+    /// `merged` is merged in rather than copied, so operations recorded
+    /// while the request was on its way are kept.
+    pub fn adopt(&mut self, merged: &CrdtCounter, sent: u64) {
+        self.crdt.merge(merged);
+        self.merged = self.merged.max(sent);
+    }
+
+    /// Whether some recorded operations have not reached the server yet
+    pub fn has_unmerged(&self) -> bool {
+        self.recorded > self.merged
+    }
+
+    /// The server applied `op` (as `SERVER_REPLICA`) and now has `value`
+    ///
+    /// Returns `false` when our state can't follow by itself (after "*2" or
+    /// a merge); then fetch the server's state and `adopt` it.
+    pub fn follow(&mut self, op: Operation, value: i32) -> bool {
+        if op.is_additive() {
+            self.crdt.apply(SERVER_REPLICA, op);
+        }
+        self.crdt.value() == value
+    }
+
+    /// Read a replica saved with `to_json` (`None` if it can't be read)
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+
+    /// The JSON `from_json` reads
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("replicas are always valid JSON")
+    }
+}
+
+/// Where a tab keeps its unmerged replica: one key per replica, so two tabs
+/// offline at once never overwrite each other
+fn storage_prefix(base_url: &str, counter_id: u64) -> String {
+    format!("webtest-replica:{base_url}:{counter_id}:")
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+/// Save `replica` while it has unmerged operations, forget it once merged
+fn save_replica(prefix: &str, replica: &Replica) {
+    let Some(storage) = storage() else {
+        return;
+    };
+    let key = format!("{prefix}{}", replica.id());
+    if replica.has_unmerged() {
+        let _ = storage.set_item(&key, &replica.to_json());
+    } else {
+        let _ = storage.remove_item(&key);
+    }
+}
+
+/// Every replica saved under `prefix` (by this or an earlier visit)
+fn saved_replicas(prefix: &str) -> Vec<(String, Replica)> {
+    let Some(storage) = storage() else {
+        return Vec::new();
+    };
+    let keys: Vec<String> = (0..storage.length().unwrap_or(0))
+        .filter_map(|i| storage.key(i).ok().flatten())
+        .filter(|key| key.starts_with(prefix))
+        .collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let replica = Replica::from_json(&storage.get_item(&key).ok().flatten()?)?;
+            Some((key, replica))
+        })
+        .collect()
+}
+
+/// How operations reach the server
+enum Transport {
+    /// Live WebSocket; messages are queued here and written by a background task.
+    /// The `AbortHandle` stops the task reading from the socket.
+    Socket(mpsc::UnboundedSender<ClientMessage>, AbortHandle),
+    /// No WebSocket: every operation merges our `Replica` over REST
+    Rest,
+}
+
+/// What a REST merge needs, cloned into each request's task
+#[derive(Clone)]
+struct Merger {
+    base_url: String,
+    counter_id: u64,
+    storage_prefix: String,
+    state: Rc<RefCell<Reconciler>>,
+    replica: Rc<RefCell<Replica>>,
+    on_value: Rc<dyn Fn(i32)>,
+}
+
+impl Merger {
+    /// Merge our replica on the server; `ack` is the newest operation it contains
+    ///
+    /// Offline, the operations stay pending (and saved) for the next try.
+    async fn merge(self, ack: u64) {
+        let (crdt, sent) = self.replica.borrow().outgoing();
+        match api::merge_counter(&self.base_url, self.counter_id, &crdt).await {
+            Ok(merged) => {
+                self.replica.borrow_mut().adopt(&merged, sent);
+                self.state.borrow_mut().remote(merged.value(), Some(ack));
+            }
+            Err(ApiError::Network(err)) => {
+                leptos::logging::warn!("offline, keeping changes to merge later: {err}");
+            }
+            Err(err) => {
+                leptos::logging::warn!("could not save changes: {err}");
+                self.state.borrow_mut().reject(ack);
+            }
+        }
+        save_replica(&self.storage_prefix, &self.replica.borrow());
+        (self.on_value)(self.state.borrow().value());
+    }
+
+    /// Merge again if anything is still waiting (e.g. when back online)
+    fn retry(&self) {
+        if self.replica.borrow().has_unmerged() {
+            let ack = self.state.borrow().last_seq();
+            leptos::task::spawn_local(self.clone().merge(ack));
+        }
+    }
+}
+
+/// A counter that is kept in sync with the server
+///
+/// Created by [`CounterLink::connect`]; call [`CounterLink::apply`] for each
+/// button click. Every time the value to display changes, `on_value` is called.
+pub struct CounterLink {
+    merger: Merger,
+    transport: Transport,
+    /// Retries the merge when the browser comes back online
+    on_online: Option<Closure<dyn FnMut()>>,
+}
+
+impl CounterLink {
+    /// Find the configured counter and open a live connection for it
+    ///
+    /// Falls back to REST-only when the WebSocket can't be opened.
+    pub async fn connect(
+        config: &ApiConfig,
+        on_value: impl Fn(i32) + 'static,
+    ) -> Result<Self, ApiError> {
+        let base_url = &config.base_url;
+        let record = api::resolve_counter(config, false).await?;
+        let id = api::allocate_replica(base_url).await?;
+        let mut crdt = api::get_crdt(base_url, record.id).await?;
+        let mut value = record.value;
+
+        // Changes made offline in an earlier visit (or another tab) that never
+        // reached the server. Merging twice is harmless, so merge them all.
+        let storage_prefix = storage_prefix(base_url, record.id);
+        for (key, saved) in saved_replicas(&storage_prefix) {
+            match api::merge_counter(base_url, record.id, saved.crdt()).await {
+                Ok(merged) => {
+                    value = merged.value();
+                    crdt = merged;
+                    if let Some(storage) = storage() {
+                        let _ = storage.remove_item(&key);
+                    }
+                }
+                Err(err) => leptos::logging::warn!("could not merge saved changes: {err}"),
+            }
+        }
+
+        let merger = Merger {
+            base_url: base_url.clone(),
+            counter_id: record.id,
+            storage_prefix,
+            state: Rc::new(RefCell::new(Reconciler::new(value))),
+            replica: Rc::new(RefCell::new(Replica::new(id, crdt))),
+            on_value: Rc::new(on_value),
+        };
+
+        let transport = match WebSocket::open(&ws_url(base_url)) {
+            Ok(socket) => {
+                let (tx, reader) = spawn_socket_tasks(socket, merger.clone());
+                // Queued until the socket is open, then sent first
+                _ = tx.unbounded_send(ClientMessage::Subscribe {
+                    counter_id: record.id,
+                });
+                Transport::Socket(tx, reader)
+            }
+            Err(err) => {
+                leptos::logging::warn!("live sync unavailable, using REST: {err}");
+                Transport::Rest
+            }
+        };
+
+        let on_online = web_sys::window().map(|window| {
+            let merger = merger.clone();
+            let on_online = Closure::<dyn FnMut()>::new(move || merger.retry());
+            _ = window
+                .add_event_listener_with_callback("online", on_online.as_ref().unchecked_ref());
+            on_online
+        });
+
+        (merger.on_value)(value);
+        Ok(Self {
+            merger,
+            transport,
+            on_online,
+        })
+    }
+
+    /// Apply `op` locally right away and send it to the server
+    pub fn apply(&self, op: Operation) {
+        let merger = &self.merger;
+        let seq = merger.state.borrow_mut().local(op);
+        (merger.on_value)(merger.state.borrow().value());
+
+        if let Transport::Socket(tx, _) = &self.transport {
+            let message = ClientMessage::Apply {
+                counter_id: merger.counter_id,
+                op,
+                seq,
+            };
+            // Fails only once the socket has closed; then fall through to REST
+            if tx.unbounded_send(message).is_ok() {
+                return;
+            }
+        }
+
+        merger.replica.borrow_mut().record(op);
+        save_replica(&merger.storage_prefix, &merger.replica.borrow());
+        leptos::task::spawn_local(merger.clone().merge(seq));
+    }
+}
+
+/// Unmounting a counter drops its link: stop both socket tasks, which drops
+/// the socket, closing it and removing its event listeners
+impl Drop for CounterLink {
+    fn drop(&mut self) {
+        if let Transport::Socket(tx, reader) = &self.transport {
+            tx.close_channel();
+            reader.abort();
+        }
+        if let (Some(window), Some(on_online)) = (web_sys::window(), &self.on_online) {
+            _ = window
+                .remove_event_listener_with_callback("online", on_online.as_ref().unchecked_ref());
+        }
+    }
+}
+
+/// `http://host:3000` -> `ws://host:3000/api/ws` (and `https` -> `wss`)
+fn ws_url(base_url: &str) -> String {
+    let base = base_url
+        .strip_prefix("http")
+        .map(|rest| format!("ws{rest}"))
+        .unwrap_or_else(|| base_url.to_string());
+    format!("{base}/api/ws")
+}
+
+/// Fetch the server's CRDT state and merge it into our replica
+async fn refresh_replica(merger: &Merger) {
+    match api::get_crdt(&merger.base_url, merger.counter_id).await {
+        Ok(crdt) => merger.replica.borrow_mut().adopt(&crdt, 0),
+        Err(err) => leptos::logging::warn!("could not read the counter's state: {err}"),
+    }
+}
+
+/// Start the writer and reader tasks for an open socket
+///
+/// Returns the queue the writer drains and a handle that aborts the reader.
+/// When the reader stops (socket closed) it closes that queue, which makes
+/// `CounterLink::apply` switch to REST, and re-reads the counter so
+/// unconfirmed operations aren't counted twice.
+fn spawn_socket_tasks(
+    socket: WebSocket,
+    merger: Merger,
+) -> (mpsc::UnboundedSender<ClientMessage>, AbortHandle) {
+    let counter_id = merger.counter_id;
+    let Merger {
+        base_url,
+        state,
+        on_value,
+        ..
+    } = merger.clone();
+    let (mut write, mut read) = socket.split();
+    let (tx, mut rx) = mpsc::unbounded::<ClientMessage>();
+
+    // Writer: queue -> socket
+    leptos::task::spawn_local(async move {
+        while let Some(message) = rx.next().await {
+            let Ok(json) = serde_json::to_string(&message) else {
+                continue;
+            };
+            if write.send(Message::Text(json)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // Reader: socket -> reconciler -> on_value
+    let queue = tx.clone();
+    let (abort, registration) = AbortHandle::new_pair();
+    let reader = async move {
+        // Binary frames are skipped; only a close, an error or the end of
+        // the stream ends the session
+        while let Some(Ok(frame)) = read.next().await {
+            let Message::Text(json) = frame else {
+                continue;
+            };
+            let Ok(message) = serde_json::from_str::<ServerMessage>(&json) else {
+                continue;
+            };
+            match message {
+                // A merge (or missed events): our replica can't follow by itself
+                ServerMessage::Snapshot {
+                    counter_id: id,
+                    value,
+                } if id == counter_id => {
+                    state.borrow_mut().remote(value, None);
+                    refresh_replica(&merger).await;
+                }
+                ServerMessage::Applied {
+                    counter_id: id,
+                    op,
+                    value,
+                    ack,
+                } if id == counter_id => {
+                    state.borrow_mut().remote(value, ack);
+                    if !merger.replica.borrow_mut().follow(op, value) {
+                        refresh_replica(&merger).await;
+                    }
+                }
+                ServerMessage::Rejected {
+                    counter_id: id,
+                    seq,
+                    reason,
+                } if id == counter_id => {
+                    leptos::logging::warn!("server rejected operation: {reason}");
+                    state.borrow_mut().reject(seq);
+                }
+                _ => continue,
+            }
+            on_value(state.borrow().value());
+        }
+        leptos::logging::warn!("live sync disconnected, using REST");
+        queue.close_channel();
+        match api::get_counter(&base_url, counter_id).await {
+            Ok(record) => state.borrow_mut().resync(record.value),
+            Err(err) => leptos::logging::warn!("could not re-read counter: {err}"),
+        }
+        refresh_replica(&merger).await;
+        on_value(state.borrow().value());
+    };
+    leptos::task::spawn_local(async move {
+        _ = Abortable::new(reader, registration).await;
+    });
+
+    (tx, abort)
+}
+
+/// An exact counter saved on the server (see the module comment)
+///
+/// Created by [`ExactLink::connect`]; call [`ExactLink::save`] after every
+/// change. Saves go out one at a time, newest value last, so a slow request
+/// can never overwrite a newer value.
+pub struct ExactLink {
+    base_url: String,
+    counter_id: u64,
+    /// The value still to be saved, if any
+    unsaved: Rc<Cell<Option<Rational>>>,
+    saving: Rc<Cell<bool>>,
+}
+
+impl ExactLink {
+    /// Find the configured counter; returns the link and its saved value
+    pub async fn connect(config: &ApiConfig) -> Result<(Self, Rational), ApiError> {
+        let record = api::resolve_counter(config, true).await?;
+        let link = Self {
+            base_url: config.base_url.clone(),
+            counter_id: record.id,
+            unsaved: Rc::default(),
+            saving: Rc::default(),
+        };
+        Ok((link, record.exact.unwrap_or(record.value.into())))
+    }
+
+    /// Save `value` (after the saves already under way)
+    pub fn save(&self, value: Rational) {
+        self.unsaved.set(Some(value));
+        if self.saving.replace(true) {
+            // The running task picks the new value up when it's done
+            return;
+        }
+        let (base_url, id) = (self.base_url.clone(), self.counter_id);
+        let (unsaved, saving) = (self.unsaved.clone(), self.saving.clone());
+        leptos::task::spawn_local(async move {
+            while let Some(value) = unsaved.take() {
+                // `value` too, so a counter that isn't exact on the server
+                // still gets the whole part
+                let update = CounterUpdate {
+                    exact: Some(value),
+                    value: Some(value.trunc()),
+                    ..CounterUpdate::default()
+                };
+                if let Err(err) = api::update_counter(&base_url, id, &update).await {
+                    leptos::logging::warn!("could not save the exact value: {err}");
+                }
+            }
+            saving.set(false);
+        });
+    }
+}
@@ -0,0 +1,216 @@
+// ============================================================================
+// TIMER - APPLYING AN OPERATION EVERY N MILLISECONDS
+// ============================================================================
+//
+// WHAT: The clock behind the counter's timer mode ("+1 every second").
+//
+// WHY NOT `setInterval`? Browsers only promise to call a timer "no sooner
+//      than" asked. Each call is a few milliseconds late, and with
+//      `setInterval` those delays add up: after an hour a 1s ticker can be
+//      seconds behind. Background tabs are throttled even harder.
+//
+// HOW (drift correction): `Ticker` remembers when tick 0 happened. Tick N is
+//      due at `origin + N * interval`, no matter how late earlier ticks were.
+//      After every tick we schedule a single `setTimeout` for exactly the
+//      time left until the next one, and `poll` reports every tick that
+//      became due meanwhile - so a throttled tab catches up instead of
+//      falling behind.
+//
+// COUNTDOWN: `Countdown` describes the countdown mode: tick -1 from a
+//            target down to zero (or +1 up to a goal), then sound the alarm.
+//
+// LEARNING: `Ticker` is plain Rust that is given the current time instead of
+//           reading a clock, so tests/timer.rs can check it without waiting.
+//           Only `Timeout` and `now` talk to the browser.
+
+use crate::operation::Operation;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Whether the timer is counting
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimerState {
+    #[default]
+    Stopped,
+    Running,
+    Paused,
+}
+
+/// Drift-free tick bookkeeping (see the module comment); times are in ms
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ticker {
+    interval: f64,
+    state: TimerState,
+    /// When tick 0 happened (shifted forward by the time spent paused)
+    origin: f64,
+    /// Ticks reported by `poll` so far
+    ticks: u64,
+    /// When `pause` was called
+    paused_at: f64,
+}
+
+impl Ticker {
+    /// Shortest allowed interval; faster timers would just burn CPU
+    pub const MIN_INTERVAL: f64 = 10.0;
+
+    /// Most ticks a single `poll` reports; a tab asleep for hours skips
+    /// the rest instead of freezing the page while it catches up
+    pub const MAX_CATCH_UP: u64 = 1000;
+
+    /// A stopped ticker firing every `interval` ms once started
+    pub fn new(interval: f64) -> Self {
+        Self {
+            interval: interval.max(Self::MIN_INTERVAL),
+            state: TimerState::Stopped,
+            origin: 0.0,
+            ticks: 0,
+            paused_at: 0.0,
+        }
+    }
+
+    /// The interval in ms
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    /// Running, paused or stopped
+    pub fn state(&self) -> TimerState {
+        self.state
+    }
+
+    /// Start counting (from a stopped ticker) or continue (from a paused one)
+    ///
+    /// Resuming keeps the progress made towards the next tick before pausing.
+    pub fn start(&mut self, now: f64) {
+        match self.state {
+            TimerState::Stopped => {
+                self.origin = now;
+                self.ticks = 0;
+            }
+            TimerState::Paused => self.origin += now - self.paused_at,
+            TimerState::Running => return,
+        }
+        self.state = TimerState::Running;
+    }
+
+    /// Pause a running ticker
+    pub fn pause(&mut self, now: f64) {
+        if self.state == TimerState::Running {
+            self.state = TimerState::Paused;
+            self.paused_at = now;
+        }
+    }
+
+    /// Stop; the next `start` begins a fresh interval
+    pub fn stop(&mut self) {
+        self.state = TimerState::Stopped;
+    }
+
+    /// How many ticks became due since the last `poll` (0 unless running)
+    pub fn poll(&mut self, now: f64) -> u64 {
+        if self.state != TimerState::Running {
+            return 0;
+        }
+        let due = ((now - self.origin) / self.interval).floor().max(0.0) as u64;
+        let new = due.saturating_sub(self.ticks);
+        self.ticks = self.ticks.max(due);
+        new.min(Self::MAX_CATCH_UP)
+    }
+
+    /// Milliseconds from `now` until the next tick is due (never negative)
+    pub fn delay_until_next(&self, now: f64) -> f64 {
+        let next = self.origin + (self.ticks + 1) as f64 * self.interval;
+        (next - now).max(0.0)
+    }
+}
+
+/// The browser's high-resolution clock in ms (unaffected by clock changes)
+pub fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or_else(js_sys::Date::now, |performance| performance.now())
+}
+
+/// A `setTimeout` that is cancelled when dropped
+///
+/// Dropping it is all the cleanup a component needs when it unmounts.
+pub struct Timeout {
+    handle: i32,
+    // Must outlive the timeout: JavaScript calls into it
+    _callback: Closure<dyn FnMut()>,
+}
+
+impl Timeout {
+    /// Call `f` once after `delay` ms (`None` outside a browser)
+    pub fn new(delay: f64, f: impl FnOnce() + 'static) -> Option<Self> {
+        let mut f = Some(f);
+        let callback = Closure::<dyn FnMut()>::new(move || {
+            if let Some(f) = f.take() {
+                f();
+            }
+        });
+        let handle = web_sys::window()?
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.as_ref().unchecked_ref(),
+                delay.ceil() as i32,
+            )
+            .ok()?;
+        Some(Self {
+            handle,
+            _callback: callback,
+        })
+    }
+}
+
+impl Drop for Timeout {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            window.clear_timeout_with_handle(self.handle);
+        }
+    }
+}
+
+/// What a countdown counts towards (used by the counter's countdown mode)
+///
+/// The timer applies `operation()` every second, starting from
+/// `start_value()`, until `is_finished` says the target was reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Countdown {
+    /// From `from` down to zero
+    Down { from: i32 },
+    /// From zero up to `to`
+    Up { to: i32 },
+}
+
+impl Countdown {
+    /// Where the counter jumps when the countdown starts
+    pub fn start_value(self) -> i32 {
+        match self {
+            Countdown::Down { from } => from,
+            Countdown::Up { .. } => 0,
+        }
+    }
+
+    /// What every tick does
+    pub fn operation(self) -> Operation {
+        match self {
+            Countdown::Down { .. } => Operation::Decrement,
+            Countdown::Up { .. } => Operation::Increment,
+        }
+    }
+
+    /// Whether `value` has reached the target
+    pub fn is_finished(self, value: i32) -> bool {
+        match self {
+            Countdown::Down { .. } => value <= 0,
+            Countdown::Up { to } => value >= to,
+        }
+    }
+
+    /// The alarm text shown when it finishes
+    pub fn message(self) -> String {
+        match self {
+            Countdown::Down { from } => format!("⏰ Countdown from {from} finished!"),
+            Countdown::Up { to } => format!("⏰ Goal of {to} reached!"),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// ============================================================================
+// UNITS - LITERS, KILOGRAMS, MINUTES
+// ============================================================================
+//
+// WHAT: A counter can count something measured: `<Counter unit=Unit::Milliliter />`.
+//       `CounterDisplay` shows the unit, and the value can be shown in any
+//       unit of the same kind (1500 ml is shown as 1.5 l).
+//
+// WHY `Dimension`? Liters and kilograms can't be added. Every unit belongs
+//      to one dimension (volume, mass, time), conversion only works inside
+//      a dimension, and `Quantity::sum` - used wherever counters are added
+//      together - rejects mixes with a `UnitError` instead of a wrong total.
+//
+// HOW: Each unit is a whole multiple of its dimension's smallest unit (ml,
+//      g, s). Converting multiplies by one factor and divides by the other,
+//      using `Rational` (src/rational.rs), so 1 ml really is 1/1000 l.
+//
+// LEARNING: `FromStr` lets attributes like `unit="kg"` become a `Unit`
+//           with `"kg".parse()`.
+
+use crate::rational::Rational;
+use std::fmt;
+use std::str::FromStr;
+
+/// What kind of thing a unit measures
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    Volume,
+    Mass,
+    Time,
+}
+
+/// A unit a counter can carry
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Milliliter,
+    Liter,
+    Gram,
+    Kilogram,
+    Second,
+    Minute,
+    Hour,
+}
+
+impl Unit {
+    /// Every unit, grouped by dimension, smallest first
+    pub const ALL: [Unit; 7] = [
+        Unit::Milliliter,
+        Unit::Liter,
+        Unit::Gram,
+        Unit::Kilogram,
+        Unit::Second,
+        Unit::Minute,
+        Unit::Hour,
+    ];
+
+    /// The short name shown after values ("ml", "kg", ...)
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Unit::Milliliter => "ml",
+            Unit::Liter => "l",
+            Unit::Gram => "g",
+            Unit::Kilogram => "kg",
+            Unit::Second => "s",
+            Unit::Minute => "min",
+            Unit::Hour => "h",
+        }
+    }
+
+    /// What the unit measures
+    pub fn dimension(self) -> Dimension {
+        match self {
+            Unit::Milliliter | Unit::Liter => Dimension::Volume,
+            Unit::Gram | Unit::Kilogram => Dimension::Mass,
+            Unit::Second | Unit::Minute | Unit::Hour => Dimension::Time,
+        }
+    }
+
+    /// How many of the dimension's smallest unit one of this is
+    fn factor(self) -> i32 {
+        match self {
+            Unit::Milliliter | Unit::Gram | Unit::Second => 1,
+            Unit::Minute => 60,
+            Unit::Liter | Unit::Kilogram => 1000,
+            Unit::Hour => 3600,
+        }
+    }
+
+    /// The smaller of two units of one dimension: a whole number of it
+    /// makes up one of the other (ml for ml and l)
+    pub fn smaller(self, other: Unit) -> Unit {
+        if self.factor() <= other.factor() {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// The units `self` can be converted to (including itself)
+    pub fn compatible(self) -> impl Iterator<Item = Unit> {
+        Self::ALL
+            .into_iter()
+            .filter(move |unit| unit.dimension() == self.dimension())
+    }
+
+    /// Convert `value` in this unit to `to`
+    ///
+    /// `Err` for units of different dimensions; `Ok(None)` when the result
+    /// is too large to represent.
+    pub fn convert(self, value: Rational, to: Unit) -> Result<Option<Rational>, UnitError> {
+        if self.dimension() != to.dimension() {
+            return Err(UnitError::Incompatible(Some(self), Some(to)));
+        }
+        Ok(value
+            .checked_mul(self.factor().into())
+            .and_then(|value| value.checked_div(to.factor().into())))
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = UnitError;
+
+    /// Parses a symbol, like "ml" or "kg"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Self::ALL
+            .into_iter()
+            .find(|unit| unit.symbol().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnitError::Unknown(s.to_string()))
+    }
+}
+
+/// Why units couldn't be parsed or combined
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnitError {
+    /// Not a known unit symbol
+    Unknown(String),
+    /// The units measure different things (`None` = no unit)
+    Incompatible(Option<Unit>, Option<Unit>),
+    /// The combined value is too large to represent
+    Overflow,
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |unit: &Option<Unit>| unit.map_or("no unit", Unit::symbol);
+        match self {
+            UnitError::Unknown(symbol) => write!(f, "unknown unit `{symbol}`"),
+            UnitError::Incompatible(a, b) => {
+                write!(f, "can't combine {} with {}", name(a), name(b))
+            }
+            UnitError::Overflow => write!(f, "the total is too large"),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+/// A value with its (optional) unit - what aggregates add up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quantity {
+    pub value: Rational,
+    pub unit: Option<Unit>,
+}
+
+impl Quantity {
+    /// A quantity of `value` (whole) in `unit`
+    pub fn new(value: impl Into<Rational>, unit: Option<Unit>) -> Self {
+        Self {
+            value: value.into(),
+            unit,
+        }
+    }
+
+    /// This quantity expressed in `unit` (same dimension only)
+    pub fn convert(self, unit: Option<Unit>) -> Result<Self, UnitError> {
+        let value = match (self.unit, unit) {
+            (Some(from), Some(to)) => from.convert(self.value, to)?.ok_or(UnitError::Overflow)?,
+            (None, None) => self.value,
+            (from, to) => return Err(UnitError::Incompatible(from, to)),
+        };
+        Ok(Self { value, unit })
+    }
+
+    /// Add quantities up, in the unit of the first one
+    ///
+    /// Fails on the first quantity of another dimension (or a unitless
+    /// quantity among ones with units), instead of adding liters to
+    /// kilograms. An empty list sums to a unitless zero.
+    pub fn sum(quantities: impl IntoIterator<Item = Quantity>) -> Result<Quantity, UnitError> {
+        let mut quantities = quantities.into_iter();
+        let Some(first) = quantities.next() else {
+            return Ok(Quantity::new(0, None));
+        };
+        quantities.try_fold(first, |total, quantity| {
+            let value = quantity.convert(total.unit)?.value;
+            Ok(Quantity {
+                value: total.value.checked_add(value).ok_or(UnitError::Overflow)?,
+                unit: total.unit,
+            })
+        })
+    }
+}
@@ -0,0 +1,48 @@
+//! Tests for the rolling numbers of `CounterDisplay`.
+//! Time is passed in explicitly, so nothing here waits.
+
+use proptest::prelude::*;
+use webtest::animation::{ease_out, Tween};
+
+#[test]
+fn easing_starts_fast_and_settles() {
+    assert_eq!(ease_out(0.0), 0.0);
+    assert_eq!(ease_out(1.0), 1.0);
+    // Past halfway in much less than half the time
+    assert!(ease_out(0.25) > 0.5);
+    // Out-of-range times are clamped
+    assert_eq!(ease_out(-1.0), 0.0);
+    assert_eq!(ease_out(2.0), 1.0);
+}
+
+#[test]
+fn tweens_roll_from_the_old_value_to_the_new_one() {
+    let tween = Tween::new(10, 20, 1000.0);
+    assert_eq!(tween.value_at(1000.0), 10);
+    let halfway = tween.value_at(1000.0 + Tween::DURATION / 2.0);
+    assert!((11..20).contains(&halfway), "{halfway}");
+    assert!(!tween.is_done(1000.0 + Tween::DURATION - 1.0));
+
+    assert!(tween.is_done(1000.0 + Tween::DURATION));
+    assert_eq!(tween.value_at(1000.0 + Tween::DURATION), 20);
+    assert_eq!(tween.value_at(1_000_000.0), 20);
+    assert_eq!(tween.target(), 20);
+}
+
+#[test]
+fn tweens_roll_down_and_across_the_whole_range() {
+    let tween = Tween::new(5, -5, 0.0);
+    assert!(tween.value_at(100.0) < 5);
+
+    let tween = Tween::new(i32::MIN, i32::MAX, 0.0);
+    assert_eq!(tween.value_at(0.0), i32::MIN);
+    assert_eq!(tween.value_at(Tween::DURATION), i32::MAX);
+}
+
+proptest! {
+    #[test]
+    fn rolling_never_overshoots(from: i32, to: i32, elapsed in 0.0..400.0f64) {
+        let value = Tween::new(from, to, 0.0).value_at(elapsed);
+        prop_assert!(from.min(to) <= value && value <= from.max(to));
+    }
+}
@@ -0,0 +1,321 @@
+//! Counter boards: folders, moving counters between them, per-folder
+//! totals (unit-safe) and folder reset.
+
+mod common;
+
+use common::{buttons, init_executor, text, CounterHarness};
+use leptos::prelude::*;
+use webtest::board::{Board, BoardError};
+use webtest::operation::Operation;
+use webtest::rational::Rational;
+use webtest::units::{Unit, UnitError};
+use webtest::{BoardCard, Counter, CounterBoard, CounterGroup};
+
+/// Run `test` with a fresh reactive owner
+fn with_owner(test: impl FnOnce()) {
+    init_executor();
+    Owner::new().with(test);
+}
+
+/// Mount a live counter for each of `board`'s counters (see tests/formula.rs)
+fn mount(board: Board) {
+    for counter in board.counters().get_untracked() {
+        let (control, initial, unit) = (counter.control, counter.initial, counter.unit);
+        _ = view! { <Counter control=control initial=initial unit=unit local=true /> }.to_html();
+    }
+}
+
+#[test]
+fn the_text_field_adds_counters_measured_counters_and_formulas() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_from_input("door_a").unwrap();
+        board.add_from_input(" water ml ").unwrap();
+        board.add_from_input("total = door_a + 1").unwrap();
+        let counters = board.counters().get_untracked();
+        assert_eq!(counters[0].unit, None);
+        assert_eq!(counters[1].name, "water");
+        assert_eq!(counters[1].unit, Some(Unit::Milliliter));
+        assert_eq!(board.formulas().get_untracked()[0].name, "total");
+
+        assert_eq!(
+            board.add_from_input("juice pints"),
+            Err(BoardError::Unit(UnitError::Unknown("pints".into())))
+        );
+        assert!(matches!(
+            board.add_from_input("a b c"),
+            Err(BoardError::InvalidName(_))
+        ));
+    });
+}
+
+#[test]
+fn a_bad_tag_adds_nothing_so_the_input_can_be_fixed_and_retried() {
+    with_owner(|| {
+        let board = Board::new();
+        assert_eq!(
+            board.add_from_input("door #"),
+            Err(BoardError::InvalidTag(String::new()))
+        );
+        assert!(board.counters().get_untracked().is_empty());
+
+        board.add_from_input("door #front").unwrap();
+        let counters = board.counters().get_untracked();
+        assert_eq!(counters[0].name, "door");
+        assert_eq!(counters[0].tags, ["front"]);
+    });
+}
+
+#[test]
+fn counters_move_between_folders() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_group("Kitchen").unwrap();
+        assert_eq!(
+            board.add_group(" Kitchen "),
+            Err(BoardError::NameTaken("Kitchen".into()))
+        );
+        assert!(matches!(
+            board.add_group("  "),
+            Err(BoardError::InvalidName(_))
+        ));
+
+        board.add_counter("cups", 4, None).unwrap();
+        mount(board);
+        board.control("cups").unwrap().apply(Operation::Increment);
+        let id = board.counters().get_untracked()[0].id;
+
+        assert_eq!(
+            board.move_counter(id, Some("Garage")),
+            Err(BoardError::UnknownGroup("Garage".into()))
+        );
+        board.move_counter(id, Some("Kitchen")).unwrap();
+        assert_eq!(
+            board.counters().get_untracked()[0].group.as_deref(),
+            Some("Kitchen")
+        );
+        let cups = board.control("cups").unwrap();
+        assert_eq!(cups.get(), 5, "the moved counter keeps its count");
+        cups.undo();
+        assert_eq!(cups.get(), 4, "...and its undo history");
+
+        board.move_counter(id, None).unwrap();
+        assert_eq!(board.counters().get_untracked()[0].group, None);
+    });
+}
+
+#[test]
+fn folder_totals_convert_units_and_reject_mixes() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_group("Drinks").unwrap();
+        board
+            .add_counter("water", 1500, Some(Unit::Milliliter))
+            .unwrap();
+        board.add_counter("juice", 2, Some(Unit::Liter)).unwrap();
+        board
+            .add_counter("apples", 3, Some(Unit::Kilogram))
+            .unwrap();
+        mount(board);
+        let ids: Vec<u64> = board
+            .counters()
+            .get_untracked()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+        board.move_counter(ids[0], Some("Drinks")).unwrap();
+        board.move_counter(ids[1], Some("Drinks")).unwrap();
+
+        let total = Memo::new(move |_| board.group_total(Some("Drinks")));
+        let drinks = total.get().unwrap();
+        assert_eq!(
+            (drinks.value, drinks.unit),
+            (Rational::from(3500), Some(Unit::Milliliter))
+        );
+        assert_eq!(board.group_total(None).unwrap().value, Rational::from(3));
+
+        board.move_counter(ids[2], Some("Drinks")).unwrap();
+        assert_eq!(
+            total.get(),
+            Err(UnitError::Incompatible(
+                Some(Unit::Kilogram),
+                Some(Unit::Milliliter)
+            ))
+        );
+    });
+}
+
+#[test]
+fn reset_group_only_resets_that_folder() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_group("Doors").unwrap();
+        board.add_counter("door_a", 3, None).unwrap();
+        board.add_counter("door_b", 4, None).unwrap();
+        board.add_counter("visitors", 5, None).unwrap();
+        let ids: Vec<u64> = board
+            .counters()
+            .get_untracked()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+        board.move_counter(ids[0], Some("Doors")).unwrap();
+        board.move_counter(ids[1], Some("Doors")).unwrap();
+        mount(board);
+
+        board.reset_group(Some("Doors"));
+        let count = |name: &str| board.control(name).unwrap().get();
+        assert_eq!(
+            (count("door_a"), count("door_b"), count("visitors")),
+            (0, 0, 5)
+        );
+        board.control("door_a").unwrap().undo();
+        assert_eq!(count("door_a"), 3, "a group reset is undone like a click");
+    });
+}
+
+#[test]
+fn folders_render_with_a_total_and_collapse_button() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_group("Doors").unwrap();
+        board.add_counter("door_a", 3, None).unwrap();
+        let id = board.counters().get_untracked()[0].id;
+        board.move_counter(id, Some("Doors")).unwrap();
+
+        let html = view! { <CounterGroup board=board group="Doors".to_string() /> }.to_html();
+        assert!(html.contains(r#"aria-expanded="true""#), "{html}");
+        assert!(text(&html).contains("Total: 3"), "{}", text(&html));
+        assert_eq!(buttons(&html), ["▾ Doors", "Reset group"]);
+
+        let board_html = view! { <CounterBoard board=board /> }.to_html();
+        assert!(board_html.contains(r#"draggable="true""#));
+        assert!(text(&board_html).contains("door_a"));
+        assert!(buttons(&board_html).ends_with(&["Add".to_string(), "Add folder".to_string()]));
+    });
+}
+
+#[test]
+fn folders_and_counters_share_one_list_ordered_by_folder() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_group("Doors").unwrap();
+        board.add_group("Windows").unwrap();
+        board.add_counter("door_a", 0, None).unwrap();
+        board.add_counter("loose", 0, None).unwrap();
+        let id = board.counters().get_untracked()[0].id;
+        board.move_counter(id, Some("Windows")).unwrap();
+
+        // Headers at 2, 4; each counter right after its folder's header.
+        // Moving a counter changes its order, not its place in the list.
+        let html = view! { <CounterBoard board=board /> }.to_html();
+        let orders: Vec<&str> = html
+            .split(";order:")
+            .skip(1)
+            .map(|rest| rest.split(';').next().unwrap().trim())
+            .collect();
+        assert_eq!(orders, ["2", "4", "5", "1"], "{html}");
+    });
+}
+
+#[test]
+fn collapsed_folders_hide_their_counters_unless_searching() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_group("Doors").unwrap();
+        board.add_counter("door_a", 0, None).unwrap();
+        let counter = board.counters().get_untracked()[0].clone();
+        board.move_counter(counter.id, Some("Doors")).unwrap();
+        board.set_collapsed("Doors", true);
+
+        // `hidden` keeps it out of the arrow keys' results too
+        let card = |shown: Option<Vec<u64>>| {
+            let counter = counter.clone();
+            let html = view! { <BoardCard board=board counter=counter shown=shown /> }.to_html();
+            let (tag, _) = html.split_once('>').unwrap();
+            tag.contains(" hidden")
+        };
+        assert!(card(None), "collapsed");
+        assert!(
+            !card(Some(vec![counter.id])),
+            "a search result shows anyway"
+        );
+
+        board.set_collapsed("Doors", false);
+        assert!(!card(None), "expanded");
+    });
+}
+
+#[test]
+fn the_countdown_is_turned_on_per_counter_without_losing_the_count() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_counter("eggs", 3, None).unwrap();
+        let counter = board.counters().get_untracked()[0].clone();
+        let (id, eggs) = (counter.id, counter.control);
+        let card = CounterHarness::mount(|_| view! { <BoardCard board=board counter=counter /> });
+        assert!(!card.html().contains("Start countdown"));
+
+        eggs.apply(Operation::Increment);
+        board.set_countdown(id, true);
+        assert!(board.has_countdown(id));
+        assert!(buttons(&card.html()).contains(&"Start countdown".to_string()));
+        assert_eq!(eggs.get(), 4, "the same counter, with its count");
+
+        board.set_countdown(id, false);
+        assert!(!card.html().contains("Start countdown"));
+    });
+}
+
+#[test]
+fn a_board_counter_keeps_its_goal() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_counter("laps", 0, None).unwrap();
+        let counter = board.counters().get_untracked()[0].clone();
+        let (id, laps) = (counter.id, counter.control);
+        let card = CounterHarness::mount(|_| view! { <BoardCard board=board counter=counter /> });
+        assert!(!card.html().contains(r#"role="progressbar""#));
+
+        board.set_goal(id, Some(10));
+        laps.apply(Operation::Add(4));
+        assert_eq!(board.goal_of(id), Some(10));
+        assert!(text(&card.html()).contains("4 / 10 (40%)"));
+
+        board.set_goal(id, None);
+        assert!(!card.html().contains(r#"role="progressbar""#));
+    });
+}
+
+#[test]
+fn board_counters_switch_to_exact_fractions_and_pick_the_decimals() {
+    with_owner(|| {
+        let board = Board::new();
+        board.add_counter("litres", 3, None).unwrap();
+        let counter = board.counters().get_untracked()[0].clone();
+        let (id, litres) = (counter.id, counter.control);
+        let card = CounterHarness::mount(|_| view! { <BoardCard board=board counter=counter /> });
+        // The number after "Count: ", up to the message under it
+        let shown = || {
+            let html = text(&card.html());
+            let value = html.strip_prefix("Count: ").unwrap();
+            let end = value
+                .find(|c: char| !(c.is_ascii_digit() || "≈.-".contains(c)))
+                .unwrap();
+            value[..end].to_string()
+        };
+
+        board.set_exact(id, true);
+        litres.apply(Operation::Halve);
+        assert_eq!(litres.get(), 1, "the whole part of 3/2");
+        assert_eq!(shown(), "1.5");
+
+        board.set_precision(id, 0);
+        assert_eq!(shown(), "≈2");
+
+        board.set_exact(id, false);
+        assert_eq!(shown(), "1");
+        litres.apply(Operation::Halve);
+        assert_eq!(shown(), "0", "whole numbers again");
+    });
+}
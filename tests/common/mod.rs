@@ -0,0 +1,238 @@
+//! Render components to HTML on the host - no browser, no WASM.
+//!
+//! Components are rendered the way the `server` binary renders `App`
+//! (`to_html`). Effects are spawned but never run, exactly like on the
+//! server, so nothing touches browser-only APIs.
+
+#![allow(dead_code)] // each test file uses a different subset of these helpers
+
+use leptos::ev::MouseEvent;
+use leptos::prelude::*;
+use std::cell::RefCell;
+use std::sync::Once;
+use wasm_bindgen::{JsCast, JsValue};
+use webtest::control::CounterControl;
+use webtest::probe::TestProbe;
+use webtest::Counter;
+
+/// Leptos needs an executor to spawn effects on, even if they never run
+pub fn init_executor() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        any_spawner::Executor::init_futures_executor().expect("no other executor was initialized");
+    });
+}
+
+/// A fresh path for `name` in a per-process temp directory (nothing there yet)
+pub fn temp_file(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("webtest-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("temp dir is writable");
+    let path = dir.join(name);
+    _ = std::fs::remove_file(&path);
+    _ = std::fs::remove_dir_all(&path);
+    path
+}
+
+/// Render a view to an HTML string
+pub fn render<V: IntoView + 'static>(view: impl FnOnce() -> V) -> String {
+    init_executor();
+    Owner::new().with(|| view().into_view().to_html())
+}
+
+/// The text of every `<button>` in `html`, in order
+pub fn buttons(html: &str) -> Vec<String> {
+    html.split("<button")
+        .skip(1)
+        .filter_map(|rest| {
+            let start = rest.find('>')? + 1;
+            let end = rest.find("</button>")?;
+            Some(text(&rest[start..end]))
+        })
+        .collect()
+}
+
+/// `html` without its tags, e.g. `Count: 5`
+pub fn text(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// A `Counter` that can be rendered and clicked from a test
+///
+/// The live counter is created once, under a `TestProbe` (see src/probe.rs).
+/// `html()` renders that same counter again from its current state, and a
+/// "click" calls the real click handler of the `EvilButton` with that label
+/// in the latest render - exactly what a click in the browser would call.
+pub struct CounterHarness {
+    /// Keeps the live counter alive
+    owner: Owner,
+    probe: TestProbe,
+    control: CounterControl,
+    /// Keeps the latest render (and so its buttons' handlers) alive
+    last_render: RefCell<Option<Owner>>,
+}
+
+impl CounterHarness {
+    /// A counter starting at `initial`, with step 1 and no bounds
+    pub fn new(initial: i32) -> Self {
+        Self::with_props(initial, 1, None, None)
+    }
+
+    /// A counter with all of `Counter`'s optional props set
+    pub fn with_props(initial: i32, step: i32, min: Option<i32>, max: Option<i32>) -> Self {
+        Self::mount(move |control| {
+            view! { <Counter control=control initial=initial step=step min=min max=max /> }
+        })
+    }
+
+    /// Any counter, built by `counter` around the harness' `control`
+    pub fn mount<V: IntoView>(counter: impl FnOnce(CounterControl) -> V) -> Self {
+        init_executor();
+        let owner = Owner::new();
+        let (probe, control) = owner.with(|| {
+            let probe = TestProbe::new();
+            probe.provide();
+            (probe, CounterControl::new())
+        });
+        // The live counter; `html()` renders it again from its state
+        owner.with(|| _ = counter(control).to_html());
+        Self {
+            owner,
+            probe,
+            control,
+            last_render: RefCell::new(None),
+        }
+    }
+
+    /// The current count
+    pub fn count(&self) -> i32 {
+        self.control.get()
+    }
+
+    /// The live counter as HTML
+    pub fn html(&self) -> String {
+        let render = self.owner.with(Owner::new);
+        let html = render.with(|| {
+            let view = self.probe.live_view().expect("Counter registers its view");
+            view.to_html()
+        });
+        // The previous render's buttons are gone from the probe: drop them
+        self.last_render.replace(Some(render));
+        html
+    }
+
+    /// Click the button labelled `label` (panics if there is no such button)
+    pub fn click(&self, label: &str) {
+        let html = self.html();
+        assert!(
+            self.probe.click(label, no_event()),
+            "no button labelled {label:?} in {html}"
+        );
+    }
+
+    /// Click several buttons in a row
+    pub fn click_all(&self, labels: &[&str]) {
+        for label in labels {
+            self.click(label);
+        }
+    }
+}
+
+/// Stands in for the `MouseEvent` a browser passes to click handlers
+///
+/// There are no JavaScript objects on the host; the counter's handlers never
+/// look at the event, so a placeholder is enough.
+fn no_event() -> MouseEvent {
+    JsValue::UNDEFINED.unchecked_into()
+}
+
+/// `html` laid out one tag per line, with every CSS declaration of a
+/// `style` attribute on its own line - so a changed color shows up as a
+/// one-line diff in a snapshot instead of a changed 500-character line
+pub fn pretty(html: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    let mut rest = html;
+    while !rest.is_empty() {
+        let (token, tail) = match rest.find('<') {
+            Some(0) => rest.split_at(rest.find('>').map_or(rest.len(), |end| end + 1)),
+            Some(start) => rest.split_at(start),
+            None => (rest, ""),
+        };
+        rest = tail;
+
+        if token.starts_with("</") {
+            depth -= 1;
+            line(&mut out, depth, token);
+        } else if token.starts_with("<!") {
+            // Hydration markers and comments
+            line(&mut out, depth, token);
+        } else if let Some(tag) = token.strip_prefix('<') {
+            let tag = tag.trim_end_matches('>').trim_end_matches('/');
+            pretty_tag(&mut out, depth, tag);
+            // Void elements (`<input>`, `<br>`) have no closing tag
+            let name = tag.split_whitespace().next().unwrap_or_default();
+            if !VOID_ELEMENTS.contains(&name) {
+                depth += 1;
+            }
+        } else if !token.trim().is_empty() {
+            line(&mut out, depth, token.trim());
+        }
+    }
+    out
+}
+
+/// Elements that never have a closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+fn line(out: &mut String, depth: usize, text: &str) {
+    out.push_str(&"  ".repeat(depth));
+    out.push_str(text);
+    out.push('\n');
+}
+
+/// An opening tag: `name`, then one attribute per line, `style` split up
+fn pretty_tag(out: &mut String, depth: usize, tag: &str) {
+    let (name, mut attributes) = tag.split_once(' ').unwrap_or((tag, ""));
+    line(out, depth, &format!("<{name}"));
+    loop {
+        attributes = attributes.trim_start();
+        let key_end = attributes.find(['=', ' ']).unwrap_or(attributes.len());
+        let key = &attributes[..key_end];
+        if key.is_empty() {
+            break;
+        }
+        // Boolean attributes (`selected`, `disabled`) have no value
+        let Some(value) = attributes[key_end..].strip_prefix("=\"") else {
+            line(out, depth + 1, key);
+            attributes = &attributes[key_end..];
+            continue;
+        };
+        let end = value.find('"').unwrap_or(value.len());
+        if key == "style" {
+            line(out, depth + 1, "style=\"");
+            for declaration in value[..end].split(';').map(str::trim) {
+                if !declaration.is_empty() {
+                    line(out, depth + 2, &format!("{declaration};"));
+                }
+            }
+            line(out, depth + 1, "\"");
+        } else {
+            line(out, depth + 1, &format!("{key}=\"{}\"", &value[..end]));
+        }
+        attributes = value.get(end + 1..).unwrap_or("");
+    }
+    line(out, depth, ">");
+}
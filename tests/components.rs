@@ -0,0 +1,189 @@
+//! Component tests: render to HTML on the host and simulate button clicks.
+//! Run with plain `cargo test` - no browser needed (see tests/common).
+
+mod common;
+
+use common::{buttons, render, text, CounterHarness};
+use leptos::prelude::*;
+use webtest::settings::Settings;
+use webtest::{Counter, CounterButtons, CounterDisplay, CounterMessage, EvilButton};
+
+#[test]
+fn evil_button_renders_its_label() {
+    let html = render(|| view! { <EvilButton label="Boo" on_click=Box::new(|_| {}) /> });
+    assert_eq!(buttons(&html), ["Boo"]);
+}
+
+#[test]
+fn counter_display_shows_the_count() {
+    let html = render(|| view! { <CounterDisplay count=|| 42 /> });
+    assert_eq!(text(&html), "Count: 42");
+}
+
+#[test]
+fn counter_message_matches_the_count() {
+    let message = |value: i32| {
+        text(&render(move || {
+            let (count, _) = signal(value);
+            view! { <CounterMessage count=count /> }
+        }))
+    };
+
+    assert!(message(51).contains("HIGH! 51 is above 50"));
+    assert!(message(-3).contains("NEGATIVE! -3 is below zero"));
+    assert!(message(0).contains("ZERO! Reset complete"));
+    assert_eq!(message(7), "Count is normal (1-50)");
+    assert_eq!(message(50), "Count is normal (1-50)", "50 is not high yet");
+}
+
+#[test]
+fn counter_buttons_label_the_step() {
+    let html = render(|| {
+        view! {
+            <CounterButtons
+                step=5
+                on_decrement=Box::new(|_| {})
+                on_increment=Box::new(|_| {})
+                on_reset=Box::new(|_| {})
+                on_multiply=Box::new(|_| {})
+                on_divide=Box::new(|_| {})
+            />
+        }
+    });
+    assert_eq!(buttons(&html), ["-5", "+5", "Reset", "*2", "/2"]);
+}
+
+#[test]
+fn counter_starts_at_zero_with_default_buttons() {
+    let counter = CounterHarness::new(0);
+    let html = counter.html();
+    assert!(text(&html).starts_with("Count: 0"));
+    assert_eq!(buttons(&html), ["-1", "+1", "Reset", "*2", "/2", "Undo"]);
+}
+
+#[test]
+fn clicking_buttons_updates_the_rendered_count() {
+    let counter = CounterHarness::new(0);
+
+    counter.click_all(&["+1", "+1", "+1"]);
+    assert_eq!(counter.count(), 3);
+    counter.click("*2");
+    assert_eq!(counter.count(), 6);
+    counter.click("/2");
+    assert_eq!(counter.count(), 3);
+    counter.click_all(&["-1", "-1", "-1", "-1"]);
+    assert_eq!(counter.count(), -1);
+
+    let html = counter.html();
+    assert!(text(&html).starts_with("Count: -1"));
+    assert!(text(&html).contains("NEGATIVE!"));
+
+    counter.click("Reset");
+    assert!(text(&counter.html()).contains("Count: 0"));
+}
+
+#[test]
+fn clicks_respect_step_and_bounds() {
+    let counter = CounterHarness::with_props(8, 5, Some(0), Some(10));
+    assert_eq!(buttons(&counter.html())[..2], ["-5", "+5"]);
+
+    counter.click("+5");
+    assert_eq!(counter.count(), 10, "clamped to max");
+    counter.click("*2");
+    assert_eq!(counter.count(), 10);
+    counter.click_all(&["-5", "-5", "-5"]);
+    assert_eq!(counter.count(), 0, "clamped to min");
+}
+
+#[test]
+fn initial_value_is_clamped_into_bounds() {
+    let counter = CounterHarness::with_props(500, 1, None, Some(100));
+    assert_eq!(counter.count(), 100);
+    assert!(text(&counter.html()).contains("HIGH! 100"));
+}
+
+#[test]
+fn undo_puts_back_the_previous_count() {
+    let counter = CounterHarness::new(0);
+    assert_eq!(
+        buttons(&counter.html()).last().map(String::as_str),
+        Some("Undo")
+    );
+
+    counter.click_all(&["+1", "+1", "+1", "/2", "Reset"]);
+    assert_eq!(counter.count(), 0);
+    counter.click("Undo");
+    assert_eq!(counter.count(), 1, "undoing Reset");
+    counter.click("Undo");
+    assert_eq!(counter.count(), 3, "undoing /2 restores the odd value");
+    counter.click_all(&["Undo", "Undo", "Undo", "Undo"]);
+    assert_eq!(counter.count(), 0, "undo with nothing left does nothing");
+}
+
+#[test]
+fn timer_mode_adds_start_pause_and_stop() {
+    let html = render(|| view! { <Counter step=2 timer=true /> });
+    assert!(buttons(&html).ends_with(&["Start", "Pause", "Stop"].map(String::from)));
+    assert!(text(&html).contains("Timer stopped"));
+    // The timer offers the counter's own step
+    assert!(html.contains(">+2</option>") && html.contains(">-2</option>"));
+}
+
+#[test]
+fn counter_message_shows_an_alarm_instead_of_the_band() {
+    let html = render(|| {
+        let (count, _) = signal(0);
+        view! { <CounterMessage count=count alarm="⏰ Countdown from 10 finished!".to_string() /> }
+    });
+    assert!(html.contains(r#"role="alert""#));
+    assert_eq!(text(&html), "⏰ Countdown from 10 finished!");
+}
+
+#[test]
+fn countdown_mode_adds_its_controls() {
+    let html = render(|| view! { <Counter countdown=true /> });
+    assert!(buttons(&html).ends_with(&["Start countdown", "Stop countdown"].map(String::from)));
+    assert!(html.contains(">down from</option>") && html.contains(">up to</option>"));
+}
+
+#[test]
+fn a_finished_countdown_is_listed_as_a_milestone() {
+    // With max=0 the countdown from 10 starts at 0, so it finishes at once
+    let counter = CounterHarness::mount(|control| {
+        // There is no audio on the host: mute the alarm
+        provide_context(RwSignal::new(Settings {
+            muted: true,
+            ..Settings::default()
+        }));
+        view! { <Counter control=control countdown=true max=0 /> }
+    });
+    assert!(!counter.html().contains(r#"aria-label="Milestones""#));
+
+    counter.click("Start countdown");
+    let html = counter.html();
+    // The list's text, from the end of its opening tag on
+    let (_, milestones) = html
+        .split_once(r#"aria-label="Milestones""#)
+        .and_then(|(_, list)| list.split_once('>'))
+        .expect("a milestone list");
+    assert!(text(milestones).starts_with("⏰ Countdown from 10 finished! (at 0)"));
+}
+
+#[test]
+fn goal_shows_progress_and_celebrates_when_reached() {
+    let html = render(|| view! { <Counter initial=25 goal=100 /> });
+    assert!(html.contains(r#"role="progressbar""#));
+    assert!(text(&html).contains("25 / 100 (25%)"));
+    assert!(text(&html).contains("Count is normal"));
+
+    let html = render(|| view! { <Counter initial=120 goal=100 /> });
+    assert!(text(&html).contains("120 / 100 (100%)"));
+    assert!(text(&html).contains("🎉 Goal reached! 100 done!"));
+}
+
+#[test]
+fn expression_mode_adds_the_input() {
+    let html = render(|| view! { <Counter expression=true /> });
+    assert!(html.contains(r#"placeholder="count * 3 + 7""#));
+    assert!(buttons(&html).contains(&"Apply".to_string()));
+}
@@ -0,0 +1,167 @@
+//! Property-based tests for `CrdtCounter`: replicas that exchange states in
+//! any order must end up with the same value, and clicks are never lost or
+//! double-counted unless a `*2`, `/2` or `Reset` supersedes them.
+
+use proptest::prelude::*;
+use webtest::crdt::{CrdtCounter, ReplicaId};
+use webtest::operation::Operation;
+
+const REPLICAS: usize = 3;
+
+/// Something that happens in a simulated network of replicas
+#[derive(Clone, Debug)]
+enum Step {
+    /// Replica `.0` applies an operation locally
+    Apply(usize, Operation),
+    /// Replica `.1` receives replica `.0`'s state and merges it
+    Sync(usize, usize),
+}
+
+fn additive_operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        prop::sample::select(vec![Operation::Increment, Operation::Decrement]),
+        (-5i32..=5).prop_map(Operation::Add),
+    ]
+}
+
+fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        prop::sample::select(Operation::ALL.to_vec()),
+        additive_operation(),
+        any::<i32>().prop_map(Operation::Set),
+    ]
+}
+
+fn steps(op: impl Strategy<Value = Operation>) -> impl Strategy<Value = Vec<Step>> {
+    let step = prop_oneof![
+        (0..REPLICAS, op).prop_map(|(replica, op)| Step::Apply(replica, op)),
+        (0..REPLICAS, 0..REPLICAS).prop_map(|(from, to)| Step::Sync(from, to)),
+    ];
+    prop::collection::vec(step, 0..60)
+}
+
+/// Run `steps` and return every replica's final state
+fn simulate(steps: &[Step]) -> Vec<CrdtCounter> {
+    let mut replicas = vec![CrdtCounter::new(); REPLICAS];
+    for step in steps {
+        match *step {
+            Step::Apply(replica, op) => replicas[replica].apply(replica as ReplicaId, op),
+            Step::Sync(from, to) => {
+                let state = replicas[from].clone();
+                replicas[to].merge(&state);
+            }
+        }
+    }
+    replicas
+}
+
+/// A single state produced by some history of operations and merges
+fn counter_state() -> impl Strategy<Value = CrdtCounter> {
+    (steps(operation()), 0..REPLICAS).prop_map(|(steps, pick)| simulate(&steps)[pick].clone())
+}
+
+fn merged(a: &CrdtCounter, b: &CrdtCounter) -> CrdtCounter {
+    let mut out = a.clone();
+    out.merge(b);
+    out
+}
+
+proptest! {
+    #[test]
+    fn merge_is_commutative(a in counter_state(), b in counter_state()) {
+        prop_assert_eq!(merged(&a, &b), merged(&b, &a));
+    }
+
+    #[test]
+    fn merge_is_associative(a in counter_state(), b in counter_state(), c in counter_state()) {
+        prop_assert_eq!(merged(&merged(&a, &b), &c), merged(&a, &merged(&b, &c)));
+    }
+
+    #[test]
+    fn merge_is_idempotent(a in counter_state(), b in counter_state()) {
+        prop_assert_eq!(merged(&a, &a), a.clone());
+        let once = merged(&a, &b);
+        prop_assert_eq!(merged(&once, &b), once);
+    }
+
+    #[test]
+    fn replicas_converge_in_any_sync_order(
+        steps in steps(operation()),
+        order in Just((0..REPLICAS).collect::<Vec<_>>()).prop_shuffle(),
+    ) {
+        let replicas = simulate(&steps);
+
+        // Everyone eventually receives everyone else's state, in a random order
+        let mut everything = CrdtCounter::new();
+        for &replica in &order {
+            everything.merge(&replicas[replica]);
+        }
+        for replica in &replicas {
+            let mut synced = replica.clone();
+            synced.merge(&everything);
+            prop_assert_eq!(synced.value(), everything.value());
+            prop_assert_eq!(synced, everything.clone());
+        }
+    }
+
+    #[test]
+    fn clicks_are_counted_exactly_once(steps in steps(additive_operation())) {
+        let replicas = simulate(&steps);
+        let mut everything = CrdtCounter::new();
+        for replica in &replicas {
+            everything.merge(replica);
+        }
+
+        let expected: i32 = steps
+            .iter()
+            .map(|step| match step {
+                Step::Apply(_, Operation::Increment) => 1,
+                Step::Apply(_, Operation::Decrement) => -1,
+                Step::Apply(_, Operation::Add(n)) => *n,
+                _ => 0,
+            })
+            .sum();
+        prop_assert_eq!(everything.value(), expected);
+    }
+}
+
+#[test]
+fn newer_multiplicative_epoch_supersedes_concurrent_clicks() {
+    let mut a = CrdtCounter::new();
+    a.apply(1, Operation::Increment); // a = 1
+    let mut b = a.clone();
+
+    a.apply(1, Operation::Increment); // a = 2, concurrent with b's *2
+    b.apply(2, Operation::Double); // b = 2, new epoch
+
+    a.merge(&b);
+    b.merge(&a);
+    assert_eq!(a, b);
+    assert_eq!(a.value(), 2);
+
+    // Clicks after seeing the new epoch are kept
+    a.apply(1, Operation::Increment);
+    b.merge(&a);
+    assert_eq!(b.value(), 3);
+}
+
+#[test]
+fn counts_at_the_limits_saturate_instead_of_overflowing() {
+    let mut huge: CrdtCounter = serde_json::from_value(serde_json::json!({
+        "epoch": {"clock": u64::MAX, "replica": 1},
+        "base": i32::MAX,
+        "increments": {"1": u64::MAX, "2": u64::MAX},
+        "decrements": {},
+    }))
+    .unwrap();
+    assert!(!huge.is_valid());
+    assert_eq!(huge.value(), i32::MAX);
+
+    huge.apply(1, Operation::Increment);
+    huge.apply(2, Operation::Add(i32::MAX));
+    assert_eq!(huge.value(), i32::MAX);
+
+    huge.apply(1, Operation::Double);
+    assert_eq!(huge.epoch().clock, u64::MAX);
+    assert_eq!(huge.value(), i32::MAX);
+}
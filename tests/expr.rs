@@ -0,0 +1,91 @@
+//! Tests for the expression language behind `ExpressionInput` (src/expr.rs).
+
+use proptest::prelude::*;
+use webtest::expr::{evaluate, Expr, ExprError};
+use webtest::operation::Operation;
+
+#[test]
+fn evaluates_with_precedence_and_parentheses() {
+    assert_eq!(evaluate("count * 3 + 7", 5), Ok(22));
+    assert_eq!(evaluate("(count / 4) ^ 2", 10), Ok(4));
+    assert_eq!(evaluate("2 + 3 * 4 - 1", 0), Ok(13));
+    assert_eq!(evaluate("2 ^ 3 ^ 2", 0), Ok(512), "^ is right-associative");
+    assert_eq!(evaluate("-2 ^ 2", 0), Ok(-4), "^ binds tighter than minus");
+    assert_eq!(evaluate("count % 4 - -1", 10), Ok(3));
+    assert_eq!(evaluate("  count  ", -8), Ok(-8));
+}
+
+#[test]
+fn reports_syntax_errors_with_their_position() {
+    let position = |input: &str| match evaluate(input, 0) {
+        Err(ExprError::Syntax { position, .. }) => position,
+        other => panic!("{input:?} gave {other:?}"),
+    };
+    assert_eq!(position("count +"), 7, "unexpected end");
+    assert_eq!(position("(count * 2"), 10, "missing `)`");
+    assert_eq!(position("count $ 2"), 6, "unknown character");
+    assert_eq!(position("2 3"), 2, "two numbers in a row");
+    assert_eq!(position(")"), 0);
+    assert_eq!(position(""), 0);
+    assert_eq!(position(&"(".repeat(1000)), 64, "nesting is limited");
+
+    let message = evaluate("count +", 0).unwrap_err().to_string();
+    assert_eq!(
+        message,
+        "syntax error at position 8: unexpected end, expected a number"
+    );
+}
+
+#[test]
+fn reports_arithmetic_errors() {
+    assert_eq!(evaluate("count / 0", 1), Err(ExprError::DivisionByZero));
+    assert_eq!(
+        evaluate("count % (2 - 2)", 1),
+        Err(ExprError::DivisionByZero)
+    );
+    assert_eq!(evaluate("count * 2", i32::MAX), Err(ExprError::Overflow));
+    assert_eq!(evaluate("-count", i32::MIN), Err(ExprError::Overflow));
+    assert_eq!(evaluate("10 ^ 10", 0), Err(ExprError::Overflow));
+    assert_eq!(evaluate("99999999999", 0), Err(ExprError::Overflow));
+    assert_eq!(evaluate("2 ^ -1", 0), Err(ExprError::NegativeExponent));
+    assert_eq!(
+        evaluate("total + 1", 0),
+        Err(ExprError::UnknownName("total".into()))
+    );
+}
+
+#[test]
+fn lists_the_names_it_uses() {
+    let expr = Expr::parse("door_a + door_b * door_a").unwrap();
+    assert_eq!(expr.names(), ["door_a", "door_b", "door_a"]);
+}
+
+proptest! {
+    #[test]
+    fn matches_the_buttons(count in -1_000_000..1_000_000i32) {
+        prop_assert_eq!(evaluate("count * 2", count), Ok(Operation::Double.apply(count)));
+        prop_assert_eq!(evaluate("count / 2", count), Ok(Operation::Halve.apply(count)));
+        prop_assert_eq!(evaluate("count + 1", count), Ok(Operation::Increment.apply(count)));
+    }
+
+    #[test]
+    fn never_panics(input in "[ count0-9+*/%^()-]{0,40}", count: i32) {
+        let _ = evaluate(&input, count);
+    }
+}
+
+#[test]
+fn long_flat_chains_are_rejected_before_they_overflow_the_stack() {
+    // 999 tokens still evaluate...
+    assert_eq!(evaluate(&format!("{}1", "1+".repeat(499)), 0), Ok(500));
+
+    // ...but 100k terms stop at the 1001st token
+    let input = format!("{}1", "1+".repeat(100_000));
+    match evaluate(&input, 0) {
+        Err(ExprError::Syntax { position, message }) => {
+            assert_eq!(position, 1000);
+            assert_eq!(message, "expression is too long");
+        }
+        other => panic!("gave {other:?}"),
+    }
+}
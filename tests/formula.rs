@@ -0,0 +1,252 @@
+//! Formula counters: parsing, evaluation, cycle detection, and a board
+//! whose formulas follow its counters.
+
+mod common;
+
+use common::{init_executor, text};
+use leptos::prelude::*;
+use std::collections::HashMap;
+use webtest::board::{Board, BoardError};
+use webtest::expr::ExprError;
+use webtest::formula::{evaluate_all, Formula, FormulaError};
+use webtest::operation::Operation;
+use webtest::units::{Quantity, Unit, UnitError};
+use webtest::{Counter, CounterBoard};
+
+fn formulas(definitions: &[&str]) -> Vec<Formula> {
+    definitions.iter().map(|d| d.parse().unwrap()).collect()
+}
+
+/// Evaluate `definitions` over plain (unitless) counters
+fn evaluate(
+    counters: &[(&str, i32)],
+    definitions: &[&str],
+) -> HashMap<String, Result<Quantity, FormulaError>> {
+    let counters: Vec<_> = counters
+        .iter()
+        .map(|&(name, value)| (name, value, None))
+        .collect();
+    evaluate_measured(&counters, definitions)
+}
+
+/// Evaluate `definitions` over counters with units
+fn evaluate_measured(
+    counters: &[(&str, i32, Option<Unit>)],
+    definitions: &[&str],
+) -> HashMap<String, Result<Quantity, FormulaError>> {
+    let counters: HashMap<&str, Quantity> = counters
+        .iter()
+        .map(|&(name, value, unit)| (name, Quantity::new(value, unit)))
+        .collect();
+    evaluate_all(&|name| counters.get(name).copied(), &formulas(definitions))
+}
+
+/// A unitless value
+fn plain(value: i32) -> Result<Quantity, FormulaError> {
+    Ok(Quantity::new(value, None))
+}
+
+#[test]
+fn definitions_need_a_name_and_an_expression() {
+    let formula: Formula = " total = door_a + door_b ".parse().unwrap();
+    assert_eq!(formula.name, "total");
+    assert_eq!(formula.source, "door_a + door_b");
+    assert_eq!(formula.expr.names(), ["door_a", "door_b"]);
+
+    assert!(matches!(
+        "door_a + door_b".parse::<Formula>(),
+        Err(FormulaError::Definition(_))
+    ));
+    assert!(matches!(
+        "2x = 1".parse::<Formula>(),
+        Err(FormulaError::Definition(_))
+    ));
+    assert!(matches!(
+        "total = door_a +".parse::<Formula>(),
+        Err(FormulaError::Expr(ExprError::Syntax { .. }))
+    ));
+}
+
+#[test]
+fn formulas_use_counters_and_other_formulas() {
+    let values = evaluate(
+        &[("door_a", 12), ("door_b", 30), ("wins", 3), ("games", 0)],
+        &[
+            "percent = total * 100 / 84",
+            "total = door_a + door_b",
+            "ratio = wins / games",
+        ],
+    );
+    assert_eq!(values["total"], plain(42));
+    assert_eq!(
+        values["percent"],
+        plain(50),
+        "formulas may use formulas defined later"
+    );
+    assert_eq!(
+        values["ratio"],
+        Err(FormulaError::Expr(ExprError::DivisionByZero))
+    );
+}
+
+#[test]
+fn a_formula_using_itself_is_a_cycle() {
+    let values = evaluate(&[], &["x = x + 1"]);
+    assert_eq!(
+        values["x"],
+        Err(FormulaError::Cycle(vec!["x".into(), "x".into()]))
+    );
+    assert_eq!(
+        values["x"].clone().unwrap_err().to_string(),
+        "circular formula: x → x"
+    );
+}
+
+#[test]
+fn every_formula_in_a_loop_reports_it_and_users_are_broken() {
+    let values = evaluate(
+        &[("n", 1)],
+        &["a = b + n", "b = a", "c = a * 2", "d = n + 1"],
+    );
+    let cycle = |path: [&str; 3]| Err(FormulaError::Cycle(path.map(String::from).to_vec()));
+    assert_eq!(values["a"], cycle(["a", "b", "a"]));
+    assert_eq!(
+        values["b"],
+        cycle(["b", "a", "b"]),
+        "each from its own name"
+    );
+    assert_eq!(values["c"], Err(FormulaError::Broken("a".into())));
+    assert_eq!(
+        values["d"],
+        plain(2),
+        "formulas outside the loop still work"
+    );
+}
+
+#[test]
+fn formulas_in_overlapping_loops_all_report_a_loop() {
+    // `a` reaches the `b ↔ c` loop, and `b` uses `a` back: all three loop
+    let values = evaluate(&[], &["a = b", "b = c + a", "c = b"]);
+    let cycle = |path: [&str; 3]| Err(FormulaError::Cycle(path.map(String::from).to_vec()));
+    assert_eq!(values["a"], cycle(["a", "b", "a"]));
+    assert_eq!(values["b"], cycle(["b", "c", "b"]));
+    assert_eq!(values["c"], cycle(["c", "b", "c"]));
+}
+
+#[test]
+fn formulas_convert_units_and_reject_mixed_dimensions() {
+    let values = evaluate_measured(
+        &[
+            ("juice", 1, Some(Unit::Liter)),
+            ("water", 500, Some(Unit::Milliliter)),
+            ("flour", 200, Some(Unit::Gram)),
+            ("cups", 3, None),
+        ],
+        &[
+            "drinks = juice + water",
+            "rounds = water * cups",
+            "share = juice / water",
+            "dough = water + flour",
+            "extra = water + 1",
+            "broken = dough * 2",
+        ],
+    );
+    assert_eq!(
+        values["drinks"],
+        Ok(Quantity::new(1500, Some(Unit::Milliliter))),
+        "1 l + 500 ml, in the smaller unit"
+    );
+    assert_eq!(
+        values["rounds"],
+        Ok(Quantity::new(1500, Some(Unit::Milliliter)))
+    );
+    assert_eq!(values["share"], plain(2), "same dimension: a plain ratio");
+    assert_eq!(
+        values["dough"],
+        Err(FormulaError::Unit(UnitError::Incompatible(
+            Some(Unit::Milliliter),
+            Some(Unit::Gram)
+        )))
+    );
+    assert_eq!(
+        values["dough"].clone().unwrap_err().to_string(),
+        "can't combine ml with g"
+    );
+    assert_eq!(
+        values["extra"],
+        Err(FormulaError::Unit(UnitError::Incompatible(
+            Some(Unit::Milliliter),
+            None
+        )))
+    );
+    assert_eq!(values["broken"], Err(FormulaError::Broken("dough".into())));
+}
+
+#[test]
+fn unknown_names_are_reported() {
+    let values = evaluate(&[], &["total = door_a + 1"]);
+    assert_eq!(
+        values["total"],
+        Err(FormulaError::Expr(ExprError::UnknownName("door_a".into())))
+    );
+}
+
+#[test]
+fn board_names_are_unique_identifiers() {
+    init_executor();
+    Owner::new().with(|| {
+        let board = Board::new();
+        board.add_counter("wins", 0, None).unwrap();
+        assert!(matches!(
+            board.add_counter("wins", 1, None),
+            Err(BoardError::NameTaken(_))
+        ));
+        assert!(matches!(
+            board.add_formula("wins = 1"),
+            Err(BoardError::NameTaken(_))
+        ));
+        assert!(matches!(
+            board.add_counter("door a", 0, None),
+            Err(BoardError::InvalidName(_))
+        ));
+        board.add_formula("double = wins * 2").unwrap();
+        assert!(matches!(
+            board.add_counter("double", 0, None),
+            Err(BoardError::NameTaken(_))
+        ));
+    });
+}
+
+#[test]
+fn board_formulas_follow_their_counters() {
+    init_executor();
+    let owner = Owner::new();
+    owner.with(|| {
+        let board = Board::new();
+        board.add_counter("wins", 3, None).unwrap();
+        board.add_counter("games", 4, None).unwrap();
+        board.add_formula("ratio = wins * 100 / games").unwrap();
+        board.add_formula("loop = loop + 1").unwrap();
+
+        let html = text(&view! { <CounterBoard board=board /> }.to_html());
+        assert!(html.contains("ratio = wins * 100 / games75"), "{html}");
+        assert!(html.contains("⚠️ circular formula: loop → loop"), "{html}");
+
+        // Server rendering throws the board's rows away afterwards, so
+        // mount live counters for the controls, like `CounterHarness` does
+        for (name, initial) in [("wins", 3), ("games", 4)] {
+            let control = board.control(name).unwrap();
+            _ = view! { <Counter control=control initial=initial local=true /> }.to_html();
+        }
+
+        let ratio = Memo::new(move |_| board.values().remove("ratio"));
+        assert_eq!(ratio.get(), Some(plain(75)));
+        board.control("games").unwrap().apply(Operation::Increment);
+        assert_eq!(ratio.get(), Some(plain(60)));
+        board.control("games").unwrap().apply(Operation::Reset);
+        assert_eq!(
+            ratio.get(),
+            Some(Err(FormulaError::Expr(ExprError::DivisionByZero)))
+        );
+    });
+}
@@ -0,0 +1,72 @@
+//! Tests for swipe recognition and the hold-to-repeat schedule.
+//! Positions and times are passed in explicitly, so no touchscreen is needed.
+
+use webtest::gesture::{Hold, Swipe, SwipeTracker};
+
+#[test]
+fn quick_vertical_movements_are_swipes() {
+    let mut tracker = SwipeTracker::default();
+    tracker.start(100.0, 300.0, 0.0);
+    // Screen y grows downwards: a smaller y is further up
+    assert_eq!(tracker.end(110.0, 200.0, 150.0), Some(Swipe::Up));
+
+    tracker.start(100.0, 300.0, 1000.0);
+    assert_eq!(tracker.end(95.0, 360.0, 1200.0), Some(Swipe::Down));
+}
+
+#[test]
+fn taps_slow_drags_and_sideways_movements_are_not_swipes() {
+    let mut tracker = SwipeTracker::default();
+    // A tap barely moves
+    tracker.start(0.0, 0.0, 0.0);
+    assert_eq!(tracker.end(2.0, 5.0, 80.0), None);
+    // Far enough, but far too slow
+    tracker.start(0.0, 0.0, 0.0);
+    assert_eq!(tracker.end(0.0, -200.0, 2000.0), None);
+    // Mostly sideways (a horizontal scroll)
+    tracker.start(0.0, 0.0, 0.0);
+    assert_eq!(tracker.end(150.0, -60.0, 100.0), None);
+}
+
+#[test]
+fn a_swipe_needs_its_own_start() {
+    let mut tracker = SwipeTracker::default();
+    assert_eq!(tracker.end(0.0, -100.0, 100.0), None);
+
+    tracker.start(0.0, 0.0, 0.0);
+    assert_eq!(tracker.end(0.0, -100.0, 100.0), Some(Swipe::Up));
+    // The start was used up
+    assert_eq!(tracker.end(0.0, -200.0, 200.0), None);
+
+    tracker.start(0.0, 0.0, 0.0);
+    tracker.cancel();
+    assert_eq!(tracker.end(0.0, -100.0, 100.0), None);
+}
+
+#[test]
+fn holds_wait_before_repeating_then_speed_up() {
+    let hold = Hold::default();
+    assert_eq!(hold.wait(0), hold.delay);
+    assert_eq!(hold.wait(1), hold.interval);
+    assert!(hold.wait(0) > hold.wait(1));
+    // Each repeat comes sooner than the one before, down to `fastest`
+    for repeat in 1..30 {
+        assert!(hold.wait(repeat + 1) <= hold.wait(repeat));
+    }
+    assert!(hold.wait(2) < hold.wait(1));
+    assert_eq!(hold.wait(100), hold.fastest);
+}
+
+#[test]
+fn long_holds_never_repeat_faster_than_fastest() {
+    let hold = Hold::default();
+    assert_eq!(hold.wait(u32::MAX), hold.fastest);
+
+    // Three seconds of holding goes a long way
+    let (mut elapsed, mut repeats) = (0.0, 0);
+    while elapsed + hold.wait(repeats) <= 3000.0 {
+        elapsed += hold.wait(repeats);
+        repeats += 1;
+    }
+    assert!(repeats > 60, "only {repeats} repeats");
+}
@@ -0,0 +1,67 @@
+//! Tests for goal progress and the pace-based completion estimate.
+//! Time is passed in explicitly (in seconds), so nothing here waits.
+
+use webtest::goal::{format_duration, Goal, Pace};
+
+#[test]
+fn progress_is_clamped_and_only_100_percent_when_reached() {
+    let goal = Goal { target: 200 };
+    assert_eq!(goal.percent(-5), 0);
+    assert_eq!(goal.percent(50), 25);
+    assert_eq!(goal.percent(199), 99, "99.5% is not done yet");
+    assert_eq!(goal.percent(200), 100);
+    assert_eq!(goal.percent(500), 100);
+    assert_eq!(goal.progress(300), 1.0);
+}
+
+#[test]
+fn eta_follows_the_recent_rate() {
+    let goal = Goal { target: 100 };
+    let mut pace = Pace::new(60.0);
+    assert_eq!(pace.eta(goal, 0), None, "no rate without samples");
+
+    // 1 per second for 10 seconds
+    for second in 0..=10 {
+        pace.record(f64::from(second), second);
+    }
+    assert_eq!(pace.per_second(), Some(1.0));
+    assert_eq!(pace.eta(goal, 10), Some(90.0));
+    assert_eq!(pace.eta(goal, 100), None, "already reached");
+}
+
+#[test]
+fn old_samples_leave_the_window() {
+    let mut pace = Pace::new(60.0);
+    // Slow for the first 10 minutes, then 2 per second
+    pace.record(0.0, 0);
+    pace.record(600.0, 10);
+    for second in 1..=60 {
+        pace.record(600.0 + f64::from(second), 10 + 2 * second);
+    }
+    assert_eq!(pace.per_second(), Some(2.0));
+}
+
+#[test]
+fn eta_spans_the_whole_i32_range() {
+    let mut pace = Pace::default();
+    pace.record(0.0, i32::MIN);
+    pace.record(1.0, i32::MAX);
+    assert_eq!(pace.per_second(), Some(f64::from(u32::MAX)));
+    let eta = pace.eta(Goal { target: i32::MAX }, i32::MIN);
+    assert_eq!(eta, Some(1.0));
+}
+
+#[test]
+fn a_shrinking_count_has_no_eta() {
+    let mut pace = Pace::default();
+    pace.record(0.0, 10);
+    pace.record(5.0, 5);
+    assert_eq!(pace.eta(Goal { target: 100 }, 5), None);
+}
+
+#[test]
+fn durations_are_rounded_for_humans() {
+    assert_eq!(format_duration(44.6), "45s");
+    assert_eq!(format_duration(150.0), "2m 30s");
+    assert_eq!(format_duration(3.0 * 3600.0 + 5.0 * 60.0 + 59.0), "3h 5m");
+}
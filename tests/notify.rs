@@ -0,0 +1,61 @@
+//! Tests for the notification texts and the per-band opt-ins stored in the
+//! settings. Nothing here asks the browser for anything.
+
+use webtest::band::Band;
+use webtest::notify::message;
+use webtest::settings::Settings;
+
+#[test]
+fn messages_say_which_threshold_was_crossed() {
+    assert_eq!(
+        message(Band::High, 51, None),
+        ("🔥 Count is HIGH".to_string(), "51 is above 50".to_string())
+    );
+    assert_eq!(
+        message(Band::Negative, -3, None),
+        (
+            "❄️ Count is NEGATIVE".to_string(),
+            "-3 is below zero".to_string()
+        )
+    );
+    assert_eq!(message(Band::Zero, 0, None).0, "😴 Count is ZERO");
+    assert_eq!(message(Band::Normal, 12, None).1, "It is now 12");
+}
+
+#[test]
+fn board_counters_are_named() {
+    let (title, body) = message(Band::High, 80, Some("door_a"));
+    assert_eq!(title, "🔥 `door_a` is HIGH");
+    assert_eq!(body, "80 is above 50");
+}
+
+#[test]
+fn rules_are_opt_in_and_toggle_per_band() {
+    let mut settings = Settings::default();
+    assert!(Band::ALL.into_iter().all(|band| !settings.notifies(band)));
+
+    settings.set_notify(Band::High, true);
+    settings.set_notify(Band::High, true);
+    settings.set_notify(Band::Negative, true);
+    assert_eq!(settings.notify, [Band::High, Band::Negative]);
+    assert!(settings.notifies(Band::High));
+    assert!(!settings.notifies(Band::Zero));
+
+    settings.set_notify(Band::High, false);
+    assert_eq!(settings.notify, [Band::Negative]);
+}
+
+#[test]
+fn rules_are_saved_by_band_name() {
+    let mut settings = Settings::default();
+    settings.set_notify(Band::High, true);
+    let json = settings.to_json();
+    assert!(json.contains(r#""notify":["high"]"#), "{json}");
+    assert_eq!(Settings::from_json(&json), settings);
+
+    // Settings saved before notifications existed still load
+    assert_eq!(
+        Settings::from_json(r#"{"muted": false, "volume": 0.7, "sound_effects": false}"#),
+        Settings::default()
+    );
+}
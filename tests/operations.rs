@@ -0,0 +1,173 @@
+//! Property-based tests for the operations behind `CounterButtons`, over
+//! random sequences of clicks (see src/operation.rs and src/history.rs).
+
+use proptest::prelude::*;
+use webtest::history::{History, Milestone};
+use webtest::operation::{Bounds, Operation};
+
+/// Any button click, including custom steps of any size
+fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        prop::sample::select(Operation::ALL.to_vec()),
+        any::<i32>().prop_map(Operation::step),
+        any::<i32>().prop_map(Operation::Set),
+    ]
+}
+
+fn operations() -> impl Strategy<Value = Vec<Operation>> {
+    prop::collection::vec(operation(), 0..100)
+}
+
+/// Values near the edges of `i32` show up far more often than by chance
+fn value() -> impl Strategy<Value = i32> {
+    prop_oneof![
+        any::<i32>(),
+        prop::sample::select(vec![
+            i32::MIN,
+            i32::MIN + 1,
+            -1,
+            0,
+            1,
+            i32::MAX - 1,
+            i32::MAX
+        ]),
+    ]
+}
+
+/// Optional bounds; `min` may even be greater than `max`
+fn bounds() -> impl Strategy<Value = Bounds> {
+    (prop::option::of(value()), prop::option::of(value()))
+        .prop_map(|(min, max)| Bounds { min, max })
+}
+
+proptest! {
+    #[test]
+    fn reset_always_yields_zero(start in value(), ops in operations()) {
+        let value = ops.iter().fold(start, |value, op| op.apply(value));
+        prop_assert_eq!(Operation::Reset.apply(value), 0);
+    }
+
+    #[test]
+    fn halve_rounds_toward_zero(value in value()) {
+        let halved = Operation::Halve.apply(value);
+        // Exact in i64: the true half, truncated toward zero
+        prop_assert_eq!(halved as i64, value as i64 / 2);
+        prop_assert!((halved as i64 * 2 - value as i64).abs() <= 1);
+        // Symmetric: -5 / 2 == -(5 / 2) == -2, never -3
+        if value != i32::MIN {
+            prop_assert_eq!(Operation::Halve.apply(-value), -halved);
+        }
+    }
+
+    #[test]
+    fn bounds_are_never_violated(bounds in bounds(), start in value(), ops in operations()) {
+        let mut value = bounds.clamp(start);
+        prop_assert!(bounds.contains(value));
+        for op in ops {
+            value = bounds.apply(op, value);
+            prop_assert!(bounds.contains(value), "{:?} left {:?} with {}", op, bounds, value);
+        }
+    }
+
+    #[test]
+    fn connected_counters_send_an_operation_that_stays_inside(
+        bounds in bounds(),
+        start in value(),
+        op in operation(),
+    ) {
+        let value = bounds.clamp(start);
+        prop_assert_eq!(bounds.operation(op, value).apply(value), bounds.apply(op, value));
+    }
+
+    #[test]
+    fn undo_restores_the_exact_prior_state(
+        bounds in bounds(),
+        start in value(),
+        ops in operations(),
+    ) {
+        let mut history = History::new();
+        let mut value = bounds.clamp(start);
+        // Every value the counter actually went through, oldest first
+        let mut seen = vec![value];
+        for op in ops {
+            let before = value;
+            value = bounds.apply(op, value);
+            history.record(op, before, value);
+            if value != before {
+                seen.push(value);
+            }
+        }
+
+        seen.pop();
+        while let Some(entry) = history.undo() {
+            prop_assert_eq!(entry.after, value);
+            value = entry.before;
+            prop_assert_eq!(Some(value), seen.pop());
+        }
+        prop_assert!(seen.is_empty());
+        prop_assert_eq!(value, bounds.clamp(start));
+    }
+
+    #[test]
+    fn nothing_panics_on_overflow(start in value(), ops in operations()) {
+        // Debug builds panic on overflowing arithmetic; saturating ops must
+        // not, and must land on the nearest `i32` to the exact result
+        let mut value = start;
+        for op in ops {
+            let wide = i64::from(value);
+            let exact = match op {
+                Operation::Increment => wide + 1,
+                Operation::Decrement => wide - 1,
+                Operation::Add(n) => wide + i64::from(n),
+                Operation::Reset => 0,
+                Operation::Double => wide * 2,
+                Operation::Halve => wide / 2,
+                Operation::Set(n) => i64::from(n),
+            };
+            let expected = exact.clamp(i32::MIN.into(), i32::MAX.into());
+            let next = op.apply(value);
+            prop_assert_eq!(i64::from(next), expected, "{:?} on {}", op, value);
+            value = next;
+        }
+    }
+}
+
+#[test]
+fn the_smallest_step_subtracts_without_overflowing() {
+    assert_eq!(Operation::step_down(i32::MIN), Operation::Add(i32::MAX));
+    assert_eq!(Operation::step_down(1), Operation::Decrement);
+}
+
+#[test]
+fn history_forgets_the_oldest_changes_beyond_its_limit() {
+    let mut history = History::with_limit(3);
+    for value in 1..=5 {
+        history.record(Operation::Increment, value - 1, value);
+    }
+    assert_eq!(history.len(), 3);
+    let befores: Vec<i32> =
+        std::iter::from_fn(|| history.undo().map(|entry| entry.before)).collect();
+    assert_eq!(befores, [4, 3, 2]);
+}
+
+#[test]
+fn history_skips_operations_that_changed_nothing() {
+    let mut history = History::new();
+    history.record(Operation::Reset, 0, 0);
+    assert!(history.is_empty());
+}
+
+#[test]
+fn milestones_are_kept_apart_from_undo() {
+    let mut history = History::new();
+    history.record(Operation::Increment, 0, 1);
+    let milestone = Milestone {
+        message: "⏰ Goal of 1 reached!".into(),
+        value: 1,
+    };
+    history.mark(milestone.clone());
+
+    assert_eq!(history.undo().map(|entry| entry.before), Some(0));
+    assert!(history.undo().is_none());
+    assert_eq!(history.milestones().collect::<Vec<_>>(), [&milestone]);
+}
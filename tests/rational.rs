@@ -0,0 +1,135 @@
+//! Tests for exact mode: `Rational` arithmetic, display precision and
+//! exact round-tripping through JSON (see src/rational.rs).
+
+use leptos::prelude::*;
+use proptest::prelude::*;
+use webtest::api::CounterRecord;
+use webtest::control::CounterControl;
+use webtest::operation::{Bounds, Operation};
+use webtest::rational::Rational;
+use webtest::Counter;
+
+mod common;
+use common::{render, text};
+
+fn r(s: &str) -> Rational {
+    s.parse().unwrap()
+}
+
+#[test]
+fn halving_an_odd_number_keeps_the_fraction() {
+    assert_eq!(Operation::Halve.apply_exact(3.into()), Some(r("3/2")));
+    assert_eq!(Operation::Halve.apply(3), 1, "integer mode still truncates");
+    assert_eq!(r("3/2").trunc(), 1);
+    assert_eq!(
+        r("-3/2").trunc(),
+        -1,
+        "rounds toward zero like i32 division"
+    );
+}
+
+#[test]
+fn fractions_are_kept_in_lowest_terms() {
+    assert_eq!(r("6/4"), r("3/2"));
+    assert_eq!(r("3/-6"), r("-1/2"));
+    assert_eq!(r("1.25"), r("5/4"));
+    assert_eq!(r("-0.5"), r("-1/2"));
+    assert_eq!(r("4/2").to_string(), "2");
+    assert!("1/0".parse::<Rational>().is_err());
+    assert!("1.".parse::<Rational>().is_err());
+    assert!("--1.5".parse::<Rational>().is_err());
+}
+
+#[test]
+fn decimals_respect_the_precision() {
+    assert_eq!(r("3/2").to_decimal(2), "1.5");
+    assert_eq!(r("1/3").to_decimal(2), "0.33");
+    assert_eq!(r("2/3").to_decimal(4), "0.6667");
+    assert_eq!(
+        r("-5/8").to_decimal(2),
+        "-0.63",
+        "halves round away from zero"
+    );
+    assert_eq!(r("-1/1000").to_decimal(2), "0", "no negative zero");
+    assert_eq!(r("7").to_decimal(0), "7");
+    assert!(r("5/4").is_exact_at(2));
+    assert!(!r("1/3").is_exact_at(12));
+}
+
+#[test]
+fn bounds_clamp_exact_values() {
+    let bounds = Bounds {
+        min: Some(0),
+        max: Some(10),
+    };
+    assert_eq!(bounds.clamp_exact(r("21/2")), r("10"));
+    assert_eq!(bounds.clamp_exact(r("-1/2")), r("0"));
+    assert_eq!(bounds.clamp_exact(r("9/2")), r("9/2"));
+}
+
+#[test]
+fn records_round_trip_exactly_through_json() {
+    let record = CounterRecord {
+        id: 1,
+        name: "Liters".into(),
+        value: 0,
+        exact: Some(r("1/3")),
+    };
+    let json = serde_json::to_string(&record).unwrap();
+    assert!(
+        json.contains(r#""exact":"1/3""#),
+        "stored as a string: {json}"
+    );
+    assert_eq!(
+        serde_json::from_str::<CounterRecord>(&json).unwrap(),
+        record
+    );
+
+    // Plain counters look exactly like before
+    let plain = CounterRecord {
+        exact: None,
+        ..record
+    };
+    assert_eq!(
+        serde_json::to_string(&plain).unwrap(),
+        r#"{"id":1,"name":"Liters","value":0}"#
+    );
+}
+
+#[test]
+fn exact_counter_shows_and_undoes_fractions() {
+    let html = render(|| {
+        let control = CounterControl::new();
+        let view = view! { <Counter control=control initial=3 exact=true precision=3 /> };
+        control.apply(Operation::Halve);
+        control.apply(Operation::Halve);
+        assert_eq!(control.get(), 0, "the count is the whole part of 3/4");
+        control.apply(Operation::Double);
+        control.apply(Operation::Double);
+        assert_eq!(control.get(), 3, "doubling gets back to exactly 3");
+        control.undo();
+        control.apply(Operation::Halve);
+        view
+    });
+    assert!(text(&html).starts_with("Count: 0.75"), "{}", text(&html));
+}
+
+proptest! {
+    #[test]
+    fn halving_then_doubling_is_exact(value: i32, times in 0..30usize) {
+        let mut exact = Rational::from(value);
+        for _ in 0..times {
+            exact = Operation::Halve.apply_exact(exact).unwrap();
+        }
+        for _ in 0..times {
+            exact = Operation::Double.apply_exact(exact).unwrap();
+        }
+        prop_assert_eq!(exact, Rational::from(value));
+    }
+
+    #[test]
+    fn strings_round_trip(numerator: i32, denominator in 1..1_000_000i64) {
+        let exact = Rational::new(numerator.into(), denominator).unwrap();
+        prop_assert_eq!(exact.to_string().parse::<Rational>(), Ok(exact));
+    }
+}
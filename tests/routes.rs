@@ -0,0 +1,254 @@
+//! The REST API, driven in-process through the axum router against a temp
+//! `FileStore` (`ssr` feature only: `cargo test --features ssr`).
+
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use common::temp_file;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use webtest::crdt::CrdtCounter;
+use webtest::operation::Operation;
+use webtest::routes::{api_routes, AppState};
+use webtest::store::FileStore;
+
+fn app(name: &str) -> Router {
+    api_routes(AppState::new(FileStore::open(temp_file(name)).unwrap()))
+}
+
+/// Send one request and return the status and the JSON body (`Null` if empty)
+async fn call(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, json)
+}
+
+#[tokio::test]
+async fn create_then_list() {
+    let app = app("routes-list.json");
+    let (status, created) = call(
+        &app,
+        Method::POST,
+        "/api/counters",
+        Some(json!({"name": "door a"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created, json!({"id": 1, "name": "door a", "value": 0}));
+
+    let (status, list) = call(&app, Method::GET, "/api/counters", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(list, json!([created]));
+}
+
+#[tokio::test]
+async fn operations_change_the_value_and_are_logged() {
+    let app = app("routes-operations.json");
+    call(
+        &app,
+        Method::POST,
+        "/api/counters",
+        Some(json!({"name": "a", "value": 4})),
+    )
+    .await;
+
+    let (status, record) = call(
+        &app,
+        Method::POST,
+        "/api/counters/1/operations",
+        Some(json!({"op": "increment"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(record["value"], 5);
+
+    let (_, operations) = call(&app, Method::GET, "/api/counters/1/operations", None).await;
+    assert_eq!(operations, json!(["increment"]));
+}
+
+#[tokio::test]
+async fn update_and_delete() {
+    let app = app("routes-update.json");
+    call(
+        &app,
+        Method::POST,
+        "/api/counters",
+        Some(json!({"name": "a"})),
+    )
+    .await;
+
+    let (status, record) = call(
+        &app,
+        Method::PUT,
+        "/api/counters/1",
+        Some(json!({"name": "b", "value": 7})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(record, json!({"id": 1, "name": "b", "value": 7}));
+
+    let (status, _) = call(&app, Method::DELETE, "/api/counters/1", None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = call(&app, Method::GET, "/api/counters/1", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn exact_counters_reload_with_their_fraction() {
+    let path = temp_file("routes-exact.json");
+    let app = api_routes(AppState::new(FileStore::open(&path).unwrap()));
+    call(
+        &app,
+        Method::POST,
+        "/api/counters",
+        Some(json!({"name": "a", "value": 3, "exact": true})),
+    )
+    .await;
+    let (_, record) = call(
+        &app,
+        Method::POST,
+        "/api/counters/1/operations",
+        Some(json!({"op": "halve"})),
+    )
+    .await;
+    assert_eq!(record["exact"], "3/2");
+
+    // What an exact page saves after a change (see `ExactLink` in src/sync.rs)
+    let (status, _) = call(
+        &app,
+        Method::PUT,
+        "/api/counters/1",
+        Some(json!({"exact": "5/4", "value": 1})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // A restarted server reads the fraction back from the file
+    let app = api_routes(AppState::new(FileStore::open(&path).unwrap()));
+    let (_, record) = call(&app, Method::GET, "/api/counters/1", None).await;
+    assert_eq!(
+        record,
+        json!({"id": 1, "name": "a", "value": 1, "exact": "5/4"})
+    );
+}
+
+#[tokio::test]
+async fn unknown_counters_are_404() {
+    let app = app("routes-404.json");
+    for (method, uri, body) in [
+        (Method::GET, "/api/counters/9", None),
+        (Method::PUT, "/api/counters/9", Some(json!({"value": 1}))),
+        (Method::DELETE, "/api/counters/9", None),
+        (Method::GET, "/api/counters/9/operations", None),
+        (
+            Method::POST,
+            "/api/counters/9/operations",
+            Some(json!({"op": "reset"})),
+        ),
+        (Method::GET, "/api/counters/9/crdt", None),
+    ] {
+        let (status, _) = call(&app, method.clone(), uri, body).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{method} {uri}");
+    }
+}
+
+#[tokio::test]
+async fn a_failed_save_is_500_and_changes_nothing() {
+    let dir = temp_file("routes-vanishing-dir");
+    std::fs::create_dir_all(&dir).unwrap();
+    let app = api_routes(AppState::new(
+        FileStore::open(dir.join("counters.json")).unwrap(),
+    ));
+    call(
+        &app,
+        Method::POST,
+        "/api/counters",
+        Some(json!({"name": "a", "value": 1})),
+    )
+    .await;
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/api/counters/1/operations",
+        Some(json!({"op": "increment"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    let (_, record) = call(&app, Method::GET, "/api/counters/1", None).await;
+    assert_eq!(record["value"], 1);
+}
+
+#[tokio::test]
+async fn replica_ids_are_unique_and_never_the_servers() {
+    let app = app("routes-replicas.json");
+    let (status, first) = call(&app, Method::POST, "/api/replicas", None).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (_, second) = call(&app, Method::POST, "/api/replicas", None).await;
+    assert_eq!(first, json!({"replica": 1}));
+    assert_eq!(second, json!({"replica": 2}));
+}
+
+#[tokio::test]
+async fn an_offline_replica_merges_into_the_counter() {
+    let app = app("routes-merge.json");
+    call(
+        &app,
+        Method::POST,
+        "/api/counters",
+        Some(json!({"name": "a", "value": 2})),
+    )
+    .await;
+    let (_, replica) = call(&app, Method::POST, "/api/replicas", None).await;
+    let (_, state) = call(&app, Method::GET, "/api/counters/1/crdt", None).await;
+    let mut offline: CrdtCounter = serde_json::from_value(state).unwrap();
+    offline.apply(replica["replica"].as_u64().unwrap(), Operation::Add(3));
+
+    // Sending the same state twice counts its clicks once
+    for _ in 0..2 {
+        let body = serde_json::to_value(&offline).unwrap();
+        let (status, _) = call(&app, Method::POST, "/api/counters/1/crdt", Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (_, record) = call(&app, Method::GET, "/api/counters/1", None).await;
+    assert_eq!(record["value"], 5);
+}
+
+#[tokio::test]
+async fn an_out_of_range_replica_state_is_rejected() {
+    let app = app("routes-merge-range.json");
+    call(
+        &app,
+        Method::POST,
+        "/api/counters",
+        Some(json!({"name": "a", "value": 2})),
+    )
+    .await;
+    let (_, state) = call(&app, Method::GET, "/api/counters/1/crdt", None).await;
+
+    let mut late = state.clone();
+    late["epoch"]["clock"] = json!(u64::MAX);
+    let mut huge = state;
+    huge["increments"] = json!({"1": u64::MAX});
+    for body in [late, huge] {
+        let (status, _) = call(&app, Method::POST, "/api/counters/1/crdt", Some(body)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+    let (_, record) = call(&app, Method::GET, "/api/counters/1", None).await;
+    assert_eq!(record["value"], 2);
+}
@@ -0,0 +1,153 @@
+//! Tags and the board's search bar (src/search.rs, src/band.rs).
+
+mod common;
+
+use common::{init_executor, render, text};
+use leptos::prelude::*;
+use proptest::prelude::*;
+use webtest::band::Band;
+use webtest::board::{Board, BoardError};
+use webtest::search::{Comparison, Query, SearchError, Term};
+use webtest::{BoardCard, CounterMessage};
+
+fn query(input: &str) -> Query {
+    input.parse().unwrap()
+}
+
+#[test]
+fn parses_every_kind_of_term() {
+    assert_eq!(
+        query("Door #Kitchen >=5 <0 =7 -5..5 band:HIGH").terms,
+        [
+            Term::Text("door".into()),
+            Term::Tag("kitchen".into()),
+            Term::Compare(Comparison::GreaterOrEqual, 5),
+            Term::Compare(Comparison::Less, 0),
+            Term::Compare(Comparison::Equal, 7),
+            Term::Range(-5, 5),
+            Term::Band(Band::High),
+        ]
+    );
+    assert!(query("   ").is_empty());
+    assert_eq!(
+        ">abc".parse::<Query>(),
+        Err(SearchError::InvalidNumber(">abc".into()))
+    );
+    assert_eq!(
+        "1..x".parse::<Query>(),
+        Err(SearchError::InvalidNumber("1..x".into()))
+    );
+    assert_eq!(
+        "band:lukewarm".parse::<Query>(),
+        Err(SearchError::UnknownBand("lukewarm".into()))
+    );
+}
+
+#[test]
+fn every_term_must_match() {
+    let tags = ["Kitchen".to_string(), "ground".to_string()];
+    let door = |input: &str, value: i32| query(input).matches("front_door", &tags, value);
+
+    assert!(door("DOOR", 0));
+    assert!(door("kitch", 0), "text also searches tags");
+    assert!(door("#kitchen", 0));
+    assert!(!door("#kitch", 0), "#tag must be the whole tag");
+    assert!(door("door >50", 51));
+    assert!(!door("door >50", 50));
+    assert!(door("10..20", 20) && !door("10..20", 21));
+    assert!(!door("door #garage", 0));
+    assert!(query("#küche").matches("pot", &["Küche".to_string()], 0));
+}
+
+#[test]
+fn bands_match_counter_message() {
+    let message = |value: i32| {
+        text(&render(move || {
+            let (count, _) = signal(value);
+            view! { <CounterMessage count=count /> }
+        }))
+    };
+    let high = query("band:high");
+    for value in [-1, 0, 1, 50, 51] {
+        let is_high = high.matches("c", &[], value);
+        assert_eq!(is_high, message(value).contains("HIGH"), "value {value}");
+        assert_eq!(Band::of(value).name().parse::<Band>(), Ok(Band::of(value)));
+    }
+    assert!(query("band:zero").matches("c", &[], 0));
+    assert!(query("band:negative").matches("c", &[], -3));
+    assert!(query("band:normal").matches("c", &[], 50));
+}
+
+#[test]
+fn counters_get_tags_and_are_found_by_them() {
+    init_executor();
+    Owner::new().with(|| {
+        let board = Board::new();
+        board
+            .add_from_input("front_door #Entrance #ground")
+            .unwrap();
+        board.add_from_input("water ml #kitchen").unwrap();
+        board.add_counter("visitors", 60, None).unwrap();
+        let ids: Vec<u64> = board
+            .counters()
+            .get_untracked()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+
+        assert_eq!(board.tags(ids[0]), ["Entrance", "ground"]);
+        board.add_tag(ids[0], "#entrance").unwrap();
+        assert_eq!(
+            board.tags(ids[0]).len(),
+            2,
+            "tags are unique, ignoring case"
+        );
+        assert_eq!(
+            board.add_tag(ids[0], "two words"),
+            Err(BoardError::InvalidTag("two words".into()))
+        );
+        board.remove_tag(ids[0], "ground");
+        assert_eq!(board.tags(ids[0]), ["Entrance"]);
+
+        assert_eq!(board.search(&query("#kitchen")), [ids[1]]);
+        assert_eq!(board.search(&query("band:high")), [ids[2]]);
+        assert_eq!(board.search(&query("<=0")), [ids[0], ids[1]]);
+        assert_eq!(board.search(&query("")), ids);
+    });
+}
+
+#[test]
+fn only_shown_counters_are_visible_and_focusable() {
+    init_executor();
+    Owner::new().with(|| {
+        let board = Board::new();
+        board.add_from_input("door_a #entrance").unwrap();
+        board.add_from_input("door_b").unwrap();
+        let shown = vec![board.counters().get_untracked()[1].id];
+
+        let html = board
+            .counters()
+            .get_untracked()
+            .into_iter()
+            .map(|counter| view! { <BoardCard board=board counter=counter shown=shown.clone() /> })
+            .collect_view()
+            .to_html();
+        let rows: Vec<&str> = html.split("data-board-result").skip(1).collect();
+        assert_eq!(rows.len(), 2);
+        // Attributes come before `data-board-result`, so look at what precedes it
+        let before: Vec<&str> = html.split("data-board-result").collect();
+        assert!(before[0].contains(" hidden"), "door_a is hidden");
+        assert!(!before[1].contains(" hidden"), "door_b is shown");
+        assert!(html.contains(r#"tabindex="0""#));
+        assert!(text(&html).contains("#entrance ×"));
+    });
+}
+
+proptest! {
+    #[test]
+    fn comparisons_agree_with_rust(value: i32, n: i32) {
+        prop_assert_eq!(query(&format!(">{n}")).matches("c", &[], value), value > n);
+        prop_assert_eq!(query(&format!("<={n}")).matches("c", &[], value), value <= n);
+        prop_assert_eq!(query(&format!("={n}")).matches("c", &[], value), value == n);
+    }
+}
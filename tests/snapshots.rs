@@ -0,0 +1,238 @@
+//! Snapshot tests of every component's rendered HTML.
+//!
+//! Each test renders a component on the host and compares it with the file
+//! in tests/snapshots/. When a style changes on purpose, review the diff and
+//! accept it with `cargo insta review` (or `INSTA_UPDATE=always cargo test`).
+
+mod common;
+
+use common::{pretty, render, CounterHarness};
+use insta::assert_snapshot;
+use leptos::prelude::*;
+use webtest::band::Band;
+use webtest::board::Board;
+use webtest::expr::ExprError;
+use webtest::formula::FormulaError;
+use webtest::goal::Goal;
+use webtest::settings::Settings;
+use webtest::units::{Quantity, Unit};
+use webtest::{
+    App, CountdownControls, CounterButtons, CounterDisplay, CounterMessage, EvilBackground,
+    EvilButton, ExpressionInput, FormulaDisplay, GoalProgress, NotificationControls, SoundControls,
+    TagEditor, TimerControls, UnitSelect, UpdateBanner,
+};
+
+fn counter_message(value: i32) -> String {
+    pretty(&render(move || {
+        let (count, _) = signal(value);
+        view! { <CounterMessage count=count /> }
+    }))
+}
+
+#[test]
+fn evil_background() {
+    assert_snapshot!(pretty(&render(
+        || view! { <EvilBackground>"content"</EvilBackground> }
+    )));
+}
+
+#[test]
+fn evil_background_embedded() {
+    assert_snapshot!(pretty(&render(|| {
+        view! { <EvilBackground embedded=true>"content"</EvilBackground> }
+    })));
+}
+
+#[test]
+fn evil_button() {
+    assert_snapshot!(pretty(&render(|| {
+        view! { <EvilButton label="+1" on_click=Box::new(|_| {}) /> }
+    })));
+}
+
+#[test]
+fn counter_display() {
+    assert_snapshot!(pretty(&render(|| view! { <CounterDisplay count=|| 7 /> })));
+}
+
+#[test]
+fn counter_message_high() {
+    assert_snapshot!(counter_message(51));
+}
+
+#[test]
+fn counter_message_negative() {
+    assert_snapshot!(counter_message(-1));
+}
+
+#[test]
+fn counter_message_zero() {
+    assert_snapshot!(counter_message(0));
+}
+
+#[test]
+fn counter_message_normal() {
+    assert_snapshot!(counter_message(25));
+}
+
+#[test]
+fn counter_message_alarm() {
+    assert_snapshot!(pretty(&render(|| {
+        let (count, _) = signal(0);
+        view! { <CounterMessage count=count alarm="⏰ Goal of 10 reached!".to_string() /> }
+    })));
+}
+
+#[test]
+fn counter_message_goal_reached() {
+    assert_snapshot!(pretty(&render(|| {
+        let (count, _) = signal(100);
+        view! { <CounterMessage count=count goal=100 /> }
+    })));
+}
+
+#[test]
+fn goal_progress() {
+    assert_snapshot!(pretty(&render(|| {
+        let (count, _) = signal(30);
+        view! { <GoalProgress count=count goal=Goal { target: 120 } /> }
+    })));
+}
+
+#[test]
+fn counter_buttons() {
+    assert_snapshot!(pretty(&render(|| {
+        view! {
+            <CounterButtons
+                on_decrement=Box::new(|_| {})
+                on_increment=Box::new(|_| {})
+                on_reset=Box::new(|_| {})
+                on_multiply=Box::new(|_| {})
+                on_divide=Box::new(|_| {})
+            />
+        }
+    })));
+}
+
+#[test]
+fn counter() {
+    assert_snapshot!(pretty(&CounterHarness::new(0).html()));
+}
+
+#[test]
+fn counter_after_clicks() {
+    let counter = CounterHarness::with_props(0, 5, None, Some(60));
+    counter.click_all(&["+5", "*2", "*2", "*2"]);
+    assert_snapshot!(pretty(&counter.html()));
+}
+
+#[test]
+fn expression_input() {
+    assert_snapshot!(pretty(&render(|| {
+        let (count, _) = signal(0);
+        view! { <ExpressionInput count=count on_apply=Box::new(|_| {}) /> }
+    })));
+}
+
+#[test]
+fn timer_controls() {
+    assert_snapshot!(pretty(&render(|| {
+        view! { <TimerControls step=3 on_tick=Box::new(|_| {}) /> }
+    })));
+}
+
+#[test]
+fn countdown_controls() {
+    assert_snapshot!(pretty(&render(|| {
+        let (count, _) = signal(0);
+        view! {
+            <CountdownControls
+                count=count
+                on_tick=Box::new(|_| {})
+                on_finish=Box::new(|_| {})
+            />
+        }
+    })));
+}
+
+#[test]
+fn unit_select() {
+    assert_snapshot!(pretty(&render(|| {
+        view! { <UnitSelect unit=Unit::Minute on_select=Box::new(|_| {}) /> }
+    })));
+}
+
+#[test]
+fn formula_display() {
+    assert_snapshot!(pretty(&render(|| {
+        view! { <FormulaDisplay name="total".to_string() source="door_a + door_b".to_string() value=Signal::stored(Ok(Quantity::new(42, None))) /> }
+    })));
+}
+
+#[test]
+fn formula_display_error() {
+    assert_snapshot!(pretty(&render(|| {
+        let error = FormulaError::Expr(ExprError::DivisionByZero);
+        view! { <FormulaDisplay name="ratio".to_string() source="wins / games".to_string() value=Signal::stored(Err(error)) /> }
+    })));
+}
+
+#[test]
+fn tag_editor() {
+    assert_snapshot!(pretty(&render(|| {
+        let board = Board::new();
+        board.add_from_input("door_a #entrance #ground").unwrap();
+        let id = board.counters().get_untracked()[0].id;
+        view! { <TagEditor board=board id=id /> }
+    })));
+}
+
+#[test]
+fn update_banner_hidden() {
+    assert_snapshot!(pretty(&render(|| {
+        let (show, _) = signal(false);
+        view! { <UpdateBanner show=show on_reload=Box::new(|_| {}) /> }
+    })));
+}
+
+#[test]
+fn update_banner_shown() {
+    assert_snapshot!(pretty(&render(|| {
+        let (show, _) = signal(true);
+        view! { <UpdateBanner show=show on_reload=Box::new(|_| {}) /> }
+    })));
+}
+
+#[test]
+fn sound_controls() {
+    assert_snapshot!(pretty(&render(|| {
+        let settings = RwSignal::new(Settings::default());
+        view! { <SoundControls settings=settings /> }
+    })));
+}
+
+#[test]
+fn sound_controls_muted() {
+    assert_snapshot!(pretty(&render(|| {
+        let settings = RwSignal::new(Settings {
+            muted: true,
+            ..Settings::default()
+        });
+        view! { <SoundControls settings=settings /> }
+    })));
+}
+
+#[test]
+fn notification_controls() {
+    assert_snapshot!(pretty(&render(|| {
+        let mut settings = Settings::default();
+        settings.set_notify(Band::High, true);
+        let settings = RwSignal::new(settings);
+        view! { <NotificationControls settings=settings /> }
+    })));
+}
+
+#[test]
+fn app() {
+    assert_snapshot!(pretty(&render(|| view! { <App /> })));
+}
@@ -0,0 +1,121 @@
+//! Tests for which tones each sound plays, and the settings that decide
+//! whether (and how loud) they play. Nothing here makes a sound.
+
+use std::collections::HashSet;
+use webtest::band::Band;
+use webtest::operation::Operation;
+use webtest::settings::Settings;
+use webtest::sound::Sound;
+
+/// The pitches of a sound, in order (Hz, rounded, for comparing)
+fn pitches(sound: Sound) -> Vec<u32> {
+    sound
+        .notes()
+        .iter()
+        .map(|note| note.frequency.round() as u32)
+        .collect()
+}
+
+#[test]
+fn every_button_sounds_different() {
+    let sounds: HashSet<_> = Operation::ALL
+        .into_iter()
+        .map(|op| pitches(Sound::Operation(op)))
+        .collect();
+    assert_eq!(sounds.len(), Operation::ALL.len());
+}
+
+#[test]
+fn steps_sound_like_their_direction() {
+    assert_eq!(
+        pitches(Sound::Operation(Operation::Add(5))),
+        pitches(Sound::Operation(Operation::Increment))
+    );
+    assert_eq!(
+        pitches(Sound::Operation(Operation::Add(-5))),
+        pitches(Sound::Operation(Operation::Decrement))
+    );
+    // "*2" rises, "/2" falls
+    let double = pitches(Sound::Operation(Operation::Double));
+    assert!(double.windows(2).all(|pair| pair[0] < pair[1]));
+    let halve = pitches(Sound::Operation(Operation::Halve));
+    assert!(halve.windows(2).all(|pair| pair[0] > pair[1]));
+}
+
+#[test]
+fn bands_have_their_own_tunes() {
+    let tunes: HashSet<_> = Band::ALL
+        .into_iter()
+        .map(|band| pitches(Sound::Band(band)))
+        .collect();
+    assert_eq!(tunes.len(), Band::ALL.len());
+    // Climbing into HIGH goes up the scale, sinking below zero goes down
+    let high = pitches(Sound::Band(Band::High));
+    assert!(high.windows(2).all(|pair| pair[0] < pair[1]));
+    let negative = pitches(Sound::Band(Band::Negative));
+    assert!(negative.windows(2).all(|pair| pair[0] > pair[1]));
+}
+
+#[test]
+fn notes_follow_each_other() {
+    for sound in [
+        Sound::Alarm,
+        Sound::Band(Band::High),
+        Sound::Operation(Operation::Reset),
+    ] {
+        let notes = sound.notes();
+        assert!(!notes.is_empty());
+        assert!(notes.iter().all(|note| note.duration > 0.0));
+        assert!(notes.windows(2).all(|pair| pair[0].delay < pair[1].delay));
+    }
+    assert_eq!(Sound::Alarm.notes().len(), 3);
+}
+
+#[test]
+fn effects_are_optional_and_mute_silences_everything() {
+    let settings = Settings::default();
+    assert!(settings.alarm_volume() > 0.0);
+    assert_eq!(settings.effects_volume(), 0.0);
+
+    let effects = Settings {
+        sound_effects: true,
+        volume: 0.5,
+        ..Settings::default()
+    };
+    assert_eq!(effects.effects_volume(), 0.5);
+
+    let muted = Settings {
+        muted: true,
+        ..effects.clone()
+    };
+    assert_eq!(muted.alarm_volume(), 0.0);
+    assert_eq!(muted.effects_volume(), 0.0);
+
+    // Out-of-range volumes are clamped
+    let loud = Settings {
+        volume: 7.0,
+        ..Settings::default()
+    };
+    assert_eq!(loud.alarm_volume(), 1.0);
+}
+
+#[test]
+fn settings_survive_a_round_trip_and_bad_json() {
+    let settings = Settings {
+        muted: true,
+        volume: 0.25,
+        sound_effects: true,
+        notify: vec![Band::High],
+    };
+    assert_eq!(Settings::from_json(&settings.to_json()), settings);
+
+    assert_eq!(Settings::from_json("not json"), Settings::default());
+    // Missing fields (say, from an older version) keep their defaults
+    assert_eq!(
+        Settings::from_json(r#"{"muted": true}"#),
+        Settings {
+            muted: true,
+            ..Settings::default()
+        }
+    );
+}
@@ -0,0 +1,131 @@
+//! `FileStore` against a real file in a temp directory (`ssr` feature only:
+//! `cargo test --features ssr`).
+
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use common::temp_file;
+use webtest::api::{CounterUpdate, NewCounter};
+use webtest::operation::Operation;
+use webtest::rational::Rational;
+use webtest::store::{FileStore, MAX_OPERATIONS};
+
+fn new_counter(name: &str, value: i32) -> NewCounter {
+    NewCounter {
+        name: name.to_string(),
+        value,
+        exact: false,
+    }
+}
+
+#[test]
+fn a_missing_file_opens_empty() {
+    let store = FileStore::open(temp_file("missing.json")).unwrap();
+    assert!(store.list().is_empty());
+}
+
+#[test]
+fn changes_survive_a_reopen() {
+    let path = temp_file("reopen.json");
+    let mut store = FileStore::open(&path).unwrap();
+    let door = store.create(new_counter("door", 5)).unwrap();
+    store.append(door.id, Operation::Increment).unwrap();
+    store.append(door.id, Operation::Double).unwrap();
+    let update = CounterUpdate {
+        name: Some("front door".to_string()),
+        ..Default::default()
+    };
+    store.update(door.id, update).unwrap();
+
+    let reopened = FileStore::open(&path).unwrap();
+    let record = reopened.get(door.id).unwrap();
+    assert_eq!(record.name, "front door");
+    assert_eq!(record.value, 12);
+    assert_eq!(
+        reopened.operations(door.id).unwrap(),
+        [Operation::Increment, Operation::Double]
+    );
+}
+
+#[test]
+fn the_operation_log_keeps_only_the_latest_operations() {
+    let mut store = FileStore::open(temp_file("log-cap.json")).unwrap();
+    let door = store.create(new_counter("door", 0)).unwrap();
+    store.append(door.id, Operation::Decrement).unwrap();
+    for _ in 0..MAX_OPERATIONS {
+        store.append(door.id, Operation::Increment).unwrap();
+    }
+
+    let operations = store.operations(door.id).unwrap();
+    assert_eq!(operations.len(), MAX_OPERATIONS);
+    assert!(operations.iter().all(|op| *op == Operation::Increment));
+    // Dropping old operations never changes the value
+    assert_eq!(store.get(door.id).unwrap().value, MAX_OPERATIONS as i32 - 1);
+}
+
+#[test]
+fn ids_are_never_reused() {
+    let mut store = FileStore::open(temp_file("ids.json")).unwrap();
+    let first = store.create(new_counter("a", 0)).unwrap();
+    assert!(store.delete(first.id).unwrap());
+    let second = store.create(new_counter("b", 0)).unwrap();
+    assert_ne!(first.id, second.id);
+    assert!(!store.delete(first.id).unwrap());
+}
+
+#[test]
+fn unknown_ids_are_none() {
+    let mut store = FileStore::open(temp_file("unknown.json")).unwrap();
+    assert_eq!(store.get(9), None);
+    assert_eq!(store.append(9, Operation::Increment).unwrap(), None);
+    assert_eq!(store.update(9, CounterUpdate::default()).unwrap(), None);
+}
+
+#[test]
+fn exact_values_round_trip() {
+    let path = temp_file("exact.json");
+    let mut store = FileStore::open(&path).unwrap();
+    let new = NewCounter {
+        exact: true,
+        ..new_counter("half", 3)
+    };
+    let half = store.create(new).unwrap();
+    store.append(half.id, Operation::Halve).unwrap();
+
+    let record = FileStore::open(&path).unwrap().get(half.id).unwrap();
+    assert_eq!(record.exact, Some(Rational::new(3, 2).unwrap()));
+    assert_eq!(record.value, 1);
+}
+
+#[test]
+fn a_failed_save_changes_nothing() {
+    // The directory doesn't exist, so every save fails
+    let path = temp_file("no-such-dir").join("counters.json");
+    let mut store = FileStore::open(&path).unwrap();
+
+    assert!(store.create(new_counter("lost", 1)).is_err());
+    assert!(store.list().is_empty());
+}
+
+#[test]
+fn a_failed_save_keeps_the_old_value() {
+    let dir = temp_file("vanishing-dir");
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut store = FileStore::open(dir.join("counters.json")).unwrap();
+    let door = store.create(new_counter("door", 1)).unwrap();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(store.append(door.id, Operation::Increment).is_err());
+    assert!(store
+        .update(
+            door.id,
+            CounterUpdate {
+                value: Some(9),
+                ..Default::default()
+            }
+        )
+        .is_err());
+    assert_eq!(store.get(door.id).unwrap().value, 1);
+    assert_eq!(store.operations(door.id).unwrap(), []);
+}
@@ -0,0 +1,137 @@
+//! `Reconciler`: optimistic local operations on top of the server's value.
+//! `Replica`: a tab's CRDT state, edited offline and merged on reconnect.
+
+use webtest::crdt::{CrdtCounter, SERVER_REPLICA};
+use webtest::operation::Operation;
+use webtest::sync::{Reconciler, Replica};
+
+#[test]
+fn pending_operations_replay_on_the_server_value() {
+    let mut sync = Reconciler::new(10);
+    sync.local(Operation::Increment);
+    sync.local(Operation::Double);
+    assert_eq!(sync.value(), 22);
+
+    // Someone else's operation: our two are still pending
+    sync.remote(20, None);
+    assert_eq!(sync.value(), 42);
+}
+
+#[test]
+fn an_ack_confirms_operations_up_to_its_seq() {
+    let mut sync = Reconciler::new(0);
+    let first = sync.local(Operation::Increment);
+    sync.local(Operation::Increment);
+
+    sync.remote(1, Some(first));
+    assert_eq!(sync.value(), 2);
+}
+
+#[test]
+fn a_late_ack_never_moves_the_count_back() {
+    let mut sync = Reconciler::new(0);
+    let first = sync.local(Operation::Increment);
+    let second = sync.local(Operation::Increment);
+
+    // The response for `second` overtakes the one for `first`
+    sync.remote(2, Some(second));
+    sync.remote(1, Some(first));
+    assert_eq!(sync.value(), 2);
+}
+
+#[test]
+fn a_rejected_operation_is_no_longer_replayed() {
+    let mut sync = Reconciler::new(5);
+    let first = sync.local(Operation::Increment);
+    sync.local(Operation::Double);
+
+    sync.reject(first);
+    assert_eq!(sync.value(), 10);
+}
+
+#[test]
+fn resync_forgets_pending_operations() {
+    let mut sync = Reconciler::new(5);
+    sync.local(Operation::Increment);
+    sync.resync(8);
+    assert_eq!(sync.value(), 8);
+
+    // Sequence numbers keep counting, so older acks stay stale
+    let next = sync.local(Operation::Increment);
+    assert_eq!(next, 2);
+    sync.remote(9, Some(next));
+    assert_eq!(sync.value(), 9);
+}
+
+/// Two tabs edit offline, one reloads in between, then both reconnect
+#[test]
+fn offline_replicas_merge_every_click_exactly_once() {
+    // The server's state, as both tabs got it when they connected
+    let mut server = CrdtCounter::new();
+    server.apply(SERVER_REPLICA, Operation::Add(10));
+    let mut a = Replica::new(1, server.clone());
+    let mut b = Replica::new(2, server.clone());
+
+    // Meanwhile, online clicks still reach the server
+    server.apply(SERVER_REPLICA, Operation::Increment);
+
+    // Both tabs lose the connection and keep counting
+    a.record(Operation::Increment);
+    a.record(Operation::Increment);
+    b.record(Operation::Add(5));
+    assert!(a.has_unmerged() && b.has_unmerged());
+
+    // Tab `a` is reloaded while still offline: its saved replica survives
+    let mut a = Replica::from_json(&a.to_json()).unwrap();
+    a.record(Operation::Decrement);
+
+    // Back online: each tab merges its state and adopts the result
+    for replica in [&mut a, &mut b] {
+        let (state, sent) = replica.outgoing();
+        server.merge(&state);
+        replica.adopt(&server, sent);
+        assert!(!replica.has_unmerged());
+    }
+    assert_eq!(server.value(), 10 + 1 + 2 + 5 - 1);
+
+    // A merge that is sent twice (e.g. a retried request) changes nothing
+    let (again, _) = a.outgoing();
+    server.merge(&again);
+    assert_eq!(server.value(), 17);
+
+    // Once both catch up with the server, they hold the same state
+    a.adopt(&server, 0);
+    b.adopt(&server, 0);
+    assert_eq!(a.crdt(), b.crdt());
+    assert_eq!(a.crdt().value(), 17);
+}
+
+#[test]
+fn clicks_made_while_a_merge_is_on_its_way_stay_unmerged() {
+    let mut server = CrdtCounter::new();
+    let mut tab = Replica::new(1, server.clone());
+    tab.record(Operation::Increment);
+    let (state, sent) = tab.outgoing();
+
+    tab.record(Operation::Increment);
+    server.merge(&state);
+    tab.adopt(&server, sent);
+
+    assert!(tab.has_unmerged());
+    assert_eq!(tab.crdt().value(), 2);
+}
+
+#[test]
+fn a_replica_follows_additive_operations_only() {
+    let mut server = CrdtCounter::new();
+    let mut tab = Replica::new(1, server.clone());
+
+    server.apply(SERVER_REPLICA, Operation::Add(3));
+    assert!(tab.follow(Operation::Add(3), server.value()));
+
+    // "*2" starts a new epoch that only the server knows
+    server.apply(SERVER_REPLICA, Operation::Double);
+    assert!(!tab.follow(Operation::Double, server.value()));
+    tab.adopt(&server, 0);
+    assert_eq!(tab.crdt(), &server);
+}
@@ -0,0 +1,95 @@
+//! Tests for the drift-corrected `Ticker` behind the counter's timer mode.
+//! Time is passed in explicitly, so nothing here waits.
+
+use webtest::timer::{Countdown, Ticker, TimerState};
+
+#[test]
+fn late_timeouts_do_not_accumulate_drift() {
+    let mut ticker = Ticker::new(1000.0);
+    ticker.start(0.0);
+
+    // Every timeout fires 7ms late; a naive interval would lose 7ms per tick
+    let mut now = 0.0;
+    let mut ticks = 0;
+    for _ in 0..3600 {
+        now += ticker.delay_until_next(now) + 7.0;
+        ticks += ticker.poll(now);
+    }
+    assert_eq!(ticks, 3600);
+    // The next tick is still due on the original one-second grid
+    assert_eq!(now + ticker.delay_until_next(now), 3_601_000.0);
+}
+
+#[test]
+fn throttled_tabs_catch_up() {
+    let mut ticker = Ticker::new(100.0);
+    ticker.start(50.0);
+    assert_eq!(ticker.poll(120.0), 0);
+    // The tab slept for a while: every missed tick is reported at once
+    assert_eq!(ticker.poll(1_060.0), 10);
+    assert_eq!(ticker.poll(1_100.0), 0);
+    assert_eq!(ticker.delay_until_next(1_100.0), 50.0);
+}
+
+#[test]
+fn catching_up_is_capped() {
+    let mut ticker = Ticker::new(10.0);
+    ticker.start(0.0);
+    assert_eq!(ticker.poll(1_000_000.0), Ticker::MAX_CATCH_UP);
+    // The skipped ticks are not reported later either
+    assert_eq!(ticker.poll(1_000_005.0), 0);
+}
+
+#[test]
+fn pausing_keeps_progress_towards_the_next_tick() {
+    let mut ticker = Ticker::new(1000.0);
+    ticker.start(0.0);
+    ticker.pause(400.0);
+    assert_eq!(ticker.state(), TimerState::Paused);
+    assert_eq!(ticker.poll(5_000.0), 0, "no ticks while paused");
+
+    ticker.start(10_000.0);
+    assert_eq!(ticker.state(), TimerState::Running);
+    assert_eq!(ticker.delay_until_next(10_000.0), 600.0);
+    assert_eq!(ticker.poll(10_600.0), 1);
+}
+
+#[test]
+fn stopping_starts_over() {
+    let mut ticker = Ticker::new(1000.0);
+    ticker.start(0.0);
+    assert_eq!(ticker.poll(2_500.0), 2);
+    ticker.stop();
+    assert_eq!(ticker.poll(3_000.0), 0);
+
+    ticker.start(5_000.0);
+    assert_eq!(ticker.delay_until_next(5_000.0), 1000.0);
+    assert_eq!(ticker.poll(6_000.0), 1);
+}
+
+#[test]
+fn interval_has_a_minimum() {
+    assert_eq!(Ticker::new(0.0).interval(), Ticker::MIN_INTERVAL);
+}
+
+#[test]
+fn countdown_runs_from_its_target_to_zero() {
+    let countdown = Countdown::Down { from: 3 };
+    let mut value = countdown.start_value();
+    let mut ticks = 0;
+    while !countdown.is_finished(value) {
+        value = countdown.operation().apply(value);
+        ticks += 1;
+    }
+    assert_eq!((value, ticks), (0, 3));
+    assert_eq!(countdown.message(), "⏰ Countdown from 3 finished!");
+}
+
+#[test]
+fn count_up_runs_from_zero_to_its_goal() {
+    let countdown = Countdown::Up { to: 5 };
+    assert_eq!(countdown.start_value(), 0);
+    assert!(!countdown.is_finished(4));
+    assert!(countdown.is_finished(5));
+    assert_eq!(countdown.message(), "⏰ Goal of 5 reached!");
+}
@@ -0,0 +1,82 @@
+//! Tests for units, conversions and unit-safe totals (see src/units.rs).
+
+mod common;
+
+use common::{render, text};
+use leptos::prelude::*;
+use webtest::rational::Rational;
+use webtest::units::{Quantity, Unit, UnitError};
+use webtest::{Counter, CounterDisplay};
+
+fn r(s: &str) -> Rational {
+    s.parse().unwrap()
+}
+
+#[test]
+fn converts_within_a_dimension() {
+    assert_eq!(
+        Unit::Milliliter.convert(1500.into(), Unit::Liter),
+        Ok(Some(r("3/2")))
+    );
+    assert_eq!(
+        Unit::Hour.convert(r("1/4"), Unit::Minute),
+        Ok(Some(15.into()))
+    );
+    assert_eq!(
+        Unit::Kilogram.convert(1.into(), Unit::Liter),
+        Err(UnitError::Incompatible(
+            Some(Unit::Kilogram),
+            Some(Unit::Liter)
+        ))
+    );
+    let volumes: Vec<Unit> = Unit::Liter.compatible().collect();
+    assert_eq!(volumes, [Unit::Milliliter, Unit::Liter]);
+}
+
+#[test]
+fn parses_symbols() {
+    assert_eq!("kg".parse(), Ok(Unit::Kilogram));
+    assert_eq!(" ML ".parse(), Ok(Unit::Milliliter));
+    assert_eq!(
+        "parsec".parse::<Unit>(),
+        Err(UnitError::Unknown("parsec".into()))
+    );
+}
+
+#[test]
+fn sums_compatible_quantities_in_the_first_unit() {
+    let total = Quantity::sum([
+        Quantity::new(2, Some(Unit::Liter)),
+        Quantity::new(250, Some(Unit::Milliliter)),
+    ]);
+    assert_eq!(total, Ok(Quantity::new(r("9/4"), Some(Unit::Liter))));
+    assert_eq!(
+        Quantity::sum([Quantity::new(1, None), Quantity::new(2, None)]),
+        Ok(Quantity::new(3, None))
+    );
+    assert_eq!(Quantity::sum([]), Ok(Quantity::new(0, None)));
+}
+
+#[test]
+fn rejects_incompatible_quantities() {
+    let error = Quantity::sum([
+        Quantity::new(2, Some(Unit::Liter)),
+        Quantity::new(1, Some(Unit::Kilogram)),
+    ])
+    .unwrap_err();
+    assert_eq!(error.to_string(), "can't combine kg with l");
+
+    let error =
+        Quantity::sum([Quantity::new(5, Some(Unit::Minute)), Quantity::new(5, None)]).unwrap_err();
+    assert_eq!(error, UnitError::Incompatible(None, Some(Unit::Minute)));
+}
+
+#[test]
+fn display_shows_the_unit() {
+    let html = render(|| view! { <CounterDisplay count=|| 3 unit=Unit::Kilogram /> });
+    assert_eq!(text(&html), "Count: 3 kg");
+
+    let html = render(|| view! { <Counter initial=750 unit=Unit::Milliliter /> });
+    assert!(text(&html).starts_with("Count: 750 ml"));
+    assert!(html.contains(r#"<option value="l">"#));
+}